@@ -0,0 +1,56 @@
+/// What happens to a client asking for the focus on its own, e.g. with _NET_ACTIVE_WINDOW or
+/// by mapping a window on a hidden workspace, when the user did something else since.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationPolicy {
+    /// Switches to the client and focuses it.
+    Switch,
+    /// Marks the client urgent and leaves the focus alone.
+    #[default]
+    Urgent,
+    /// Leaves the focus alone.
+    Ignore,
+}
+
+/// Decides about an activation request made at `request_time` (X server time, 0 if unknown)
+/// when the user last pressed a key or a button at `user_time`. Requests made in response to
+/// the latest user input are always granted, the others follow the policy.
+pub fn decide(
+    policy: ActivationPolicy,
+    request_time: u32,
+    user_time: Option<u32>,
+) -> ActivationPolicy {
+    if request_time == 0 {
+        return policy;
+    }
+
+    match user_time {
+        // Server time wraps around after ~49 days.
+        Some(user_time) if (request_time.wrapping_sub(user_time) as i32) < 0 => policy,
+        _ => ActivationPolicy::Switch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decide, ActivationPolicy};
+
+    #[test]
+    fn grants_requests_newer_than_user_input() {
+        let policy = ActivationPolicy::Ignore;
+
+        assert_eq!(decide(policy, 1000, Some(900)), ActivationPolicy::Switch);
+        assert_eq!(decide(policy, 1000, Some(1000)), ActivationPolicy::Switch);
+        assert_eq!(decide(policy, 1000, None), ActivationPolicy::Switch);
+        assert_eq!(
+            decide(policy, 5, Some(u32::MAX - 5)),
+            ActivationPolicy::Switch
+        );
+
+        assert_eq!(decide(policy, 900, Some(1000)), ActivationPolicy::Ignore);
+        assert_eq!(decide(policy, 0, None), ActivationPolicy::Ignore);
+        assert_eq!(
+            decide(ActivationPolicy::Urgent, 0, Some(1000)),
+            ActivationPolicy::Urgent
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use xcb::x;
+
+xcb::atoms_struct! {
+    /// Atoms interned once at startup.
+    #[derive(Debug)]
+    pub struct Atoms {
+        pub utf8_string => b"UTF8_STRING",
+        pub wm_state => b"WM_STATE",
+        pub wm_change_state => b"WM_CHANGE_STATE",
+        pub wm_window_role => b"WM_WINDOW_ROLE",
+        pub wm_client_leader => b"WM_CLIENT_LEADER",
+        pub wm_protocols => b"WM_PROTOCOLS",
+        pub wm_delete_window => b"WM_DELETE_WINDOW",
+        pub wm_take_focus => b"WM_TAKE_FOCUS",
+        pub net_wm_ping => b"_NET_WM_PING",
+        pub net_supported => b"_NET_SUPPORTED",
+        pub net_supporting_wm_check => b"_NET_SUPPORTING_WM_CHECK",
+        pub net_wm_name => b"_NET_WM_NAME",
+        pub net_wm_icon => b"_NET_WM_ICON",
+        pub net_number_of_desktops => b"_NET_NUMBER_OF_DESKTOPS",
+        pub net_current_desktop => b"_NET_CURRENT_DESKTOP",
+        pub net_desktop_names => b"_NET_DESKTOP_NAMES",
+        pub net_desktop_viewport => b"_NET_DESKTOP_VIEWPORT",
+        pub net_workarea => b"_NET_WORKAREA",
+        pub net_active_window => b"_NET_ACTIVE_WINDOW",
+        pub net_showing_desktop => b"_NET_SHOWING_DESKTOP",
+        pub net_client_list => b"_NET_CLIENT_LIST",
+        pub net_client_list_stacking => b"_NET_CLIENT_LIST_STACKING",
+        pub net_close_window => b"_NET_CLOSE_WINDOW",
+        pub net_frame_extents => b"_NET_FRAME_EXTENTS",
+        pub net_request_frame_extents => b"_NET_REQUEST_FRAME_EXTENTS",
+        pub net_wm_moveresize => b"_NET_WM_MOVERESIZE",
+        pub net_wm_state => b"_NET_WM_STATE",
+        pub net_wm_state_demands_attention => b"_NET_WM_STATE_DEMANDS_ATTENTION",
+        pub net_wm_state_modal => b"_NET_WM_STATE_MODAL",
+        pub net_wm_state_hidden => b"_NET_WM_STATE_HIDDEN",
+        pub net_wm_state_maximized_vert => b"_NET_WM_STATE_MAXIMIZED_VERT",
+        pub net_wm_state_maximized_horz => b"_NET_WM_STATE_MAXIMIZED_HORZ",
+        pub net_wm_state_fullscreen => b"_NET_WM_STATE_FULLSCREEN",
+        pub net_wm_state_sticky => b"_NET_WM_STATE_STICKY",
+        pub net_wm_state_above => b"_NET_WM_STATE_ABOVE",
+        pub net_wm_state_below => b"_NET_WM_STATE_BELOW",
+        pub net_wm_desktop => b"_NET_WM_DESKTOP",
+        pub net_wm_strut => b"_NET_WM_STRUT",
+        pub net_wm_pid => b"_NET_WM_PID",
+        pub net_wm_user_time => b"_NET_WM_USER_TIME",
+        pub net_startup_id => b"_NET_STARTUP_ID",
+        pub net_wm_window_opacity => b"_NET_WM_WINDOW_OPACITY",
+        pub net_wm_window_type => b"_NET_WM_WINDOW_TYPE",
+        pub net_wm_window_type_dock => b"_NET_WM_WINDOW_TYPE_DOCK",
+        pub net_wm_window_type_desktop => b"_NET_WM_WINDOW_TYPE_DESKTOP",
+        pub net_wm_window_type_notification => b"_NET_WM_WINDOW_TYPE_NOTIFICATION",
+        pub net_wm_window_type_tooltip => b"_NET_WM_WINDOW_TYPE_TOOLTIP",
+        pub net_wm_window_type_dropdown_menu => b"_NET_WM_WINDOW_TYPE_DROPDOWN_MENU",
+        pub net_wm_window_type_popup_menu => b"_NET_WM_WINDOW_TYPE_POPUP_MENU",
+        pub net_wm_window_type_splash => b"_NET_WM_WINDOW_TYPE_SPLASH",
+        pub rwm_workspaces => b"_RWM_WORKSPACES",
+        pub rwm_urgent_desktops => b"_RWM_URGENT_DESKTOPS",
+        pub net_wm_strut_partial => b"_NET_WM_STRUT_PARTIAL",
+        pub motif_wm_hints => b"_MOTIF_WM_HINTS",
+    }
+}
+
+impl Atoms {
+    /// EWMH hints understood by the window manager, published in _NET_SUPPORTED.
+    pub fn supported(&self) -> Vec<x::Atom> {
+        vec![
+            self.net_supported,
+            self.net_supporting_wm_check,
+            self.net_wm_name,
+            self.net_wm_icon,
+            self.net_number_of_desktops,
+            self.net_current_desktop,
+            self.net_desktop_names,
+            self.net_desktop_viewport,
+            self.net_workarea,
+            self.net_active_window,
+            self.net_showing_desktop,
+            self.net_client_list,
+            self.net_client_list_stacking,
+            self.net_close_window,
+            self.net_frame_extents,
+            self.net_request_frame_extents,
+            self.net_wm_moveresize,
+            self.net_wm_state,
+            self.net_wm_state_demands_attention,
+            self.net_wm_state_modal,
+            self.net_wm_state_hidden,
+            self.net_wm_state_maximized_vert,
+            self.net_wm_state_maximized_horz,
+            self.net_wm_state_fullscreen,
+            self.net_wm_state_sticky,
+            self.net_wm_state_above,
+            self.net_wm_state_below,
+            self.net_wm_desktop,
+            self.net_wm_strut,
+            self.net_wm_strut_partial,
+            self.net_wm_pid,
+            self.net_wm_ping,
+            self.net_wm_user_time,
+            self.net_startup_id,
+            self.net_wm_window_type,
+            self.net_wm_window_type_dock,
+            self.net_wm_window_type_desktop,
+            self.net_wm_window_type_notification,
+            self.net_wm_window_type_tooltip,
+            self.net_wm_window_type_dropdown_menu,
+            self.net_wm_window_type_popup_menu,
+            self.net_wm_window_type_splash,
+        ]
+    }
+}
@@ -0,0 +1,147 @@
+use crate::geometry::Rect;
+use crate::monitor::Monitor;
+use log::{debug, warn};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+/// How often the bars are checked, those that exited are started again.
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Monitor a bar was started for: its output, or its area for unnamed outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BarMonitor {
+    Output(String),
+    Area(Rect),
+}
+
+impl BarMonitor {
+    fn of(monitor: &Monitor) -> Self {
+        match &monitor.output {
+            Some(output) => Self::Output(output.clone()),
+            None => Self::Area(monitor.area),
+        }
+    }
+}
+
+struct Bar {
+    monitor: BarMonitor,
+    child: Child,
+}
+
+impl Drop for Bar {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// External bars (like polybar) started once per monitor, with the output name in MONITOR
+/// and its area in MONITOR_GEOMETRY. They show the workspaces of their monitor from
+/// _NET_DESKTOP_VIEWPORT and are stopped along with the window manager.
+#[derive(Default)]
+pub struct Bars {
+    running: Vec<Bar>,
+    /// Last update, None when no bar is configured.
+    updated: Option<Instant>,
+}
+
+impl Bars {
+    /// Stops the bars of monitors that are gone and starts `command` for new ones. Bars
+    /// that exited are started again.
+    pub fn update(&mut self, command: &[String], monitors: &[Monitor]) {
+        self.updated = (!command.is_empty()).then(Instant::now);
+
+        let wanted: Vec<BarMonitor> = monitors.iter().map(BarMonitor::of).collect();
+
+        self.running.retain_mut(|bar| {
+            if let Ok(Some(status)) = bar.child.try_wait() {
+                warn!("Bar on {:?} exited with {}", bar.monitor, status);
+                return false;
+            }
+            let keep = wanted.contains(&bar.monitor);
+            if !keep {
+                debug!("Stopping the bar on {:?}", bar.monitor);
+            }
+            keep
+        });
+
+        let (program, args) = match command.split_first() {
+            Some(command) => command,
+            None => return,
+        };
+        for (monitor, area) in wanted.into_iter().zip(monitors.iter().map(|m| m.area)) {
+            if self.running.iter().any(|bar| bar.monitor == monitor) {
+                continue;
+            }
+
+            let mut command = Command::new(program);
+            command.args(args).env(
+                "MONITOR_GEOMETRY",
+                format!("{}x{}+{}+{}", area.width, area.height, area.x, area.y),
+            );
+            if let BarMonitor::Output(output) = &monitor {
+                command.env("MONITOR", output);
+            }
+            match command.spawn() {
+                Ok(child) => {
+                    debug!("Started a bar on {:?}", monitor);
+                    self.running.push(Bar { monitor, child });
+                }
+                Err(e) => warn!("Failed to start the bar {}: {}", program, e),
+            }
+        }
+    }
+
+    /// Time left until the bars should be updated again to restart those that exited.
+    pub fn time_left(&self, now: Instant) -> Option<Duration> {
+        self.updated
+            .map(|updated| (updated + CHECK_INTERVAL).saturating_duration_since(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bars;
+    use crate::geometry::Rect;
+    use crate::monitor::Monitor;
+    use std::time::Instant;
+
+    #[test]
+    fn starts_and_stops_one_bar_per_monitor() {
+        let monitor = |output: &str, x: i32| Monitor {
+            output: Some(output.to_owned()),
+            ..Monitor::new(Rect::new(x, 0, 1920, 1080), 0)
+        };
+        let command = ["sleep".to_owned(), "60".to_owned()];
+        let mut bars = Bars::default();
+
+        bars.update(&command, &[monitor("DP-1", 0), monitor("HDMI-1", 1920)]);
+        assert_eq!(bars.running.len(), 2);
+
+        // The remaining output keeps its bar, wherever it moved.
+        let pid = bars.running[1].child.id();
+        bars.update(&command, &[monitor("HDMI-1", 0)]);
+        assert_eq!(bars.running.len(), 1);
+        assert_eq!(bars.running[0].child.id(), pid);
+
+        bars.update(&[], &[]);
+        assert!(bars.running.is_empty());
+    }
+
+    #[test]
+    fn bars_that_exited_are_started_again() {
+        let command = ["true".to_owned()];
+        let mut bars = Bars::default();
+
+        bars.update(&command, &[Monitor::new(Rect::new(0, 0, 1920, 1080), 0)]);
+        let pid = bars.running[0].child.id();
+        assert!(bars.time_left(Instant::now()).is_some());
+
+        bars.running[0].child.wait().unwrap();
+        bars.update(&command, &[Monitor::new(Rect::new(0, 0, 1920, 1080), 0)]);
+        assert_ne!(bars.running[0].child.id(), pid);
+
+        bars.update(&[], &[]);
+        assert_eq!(bars.time_left(Instant::now()), None);
+    }
+}
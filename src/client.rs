@@ -0,0 +1,181 @@
+use crate::geometry::Rect;
+use crate::icon::Icon;
+use crate::motif::MotifHints;
+use crate::size_hints::SizeHints;
+use crate::struts::Struts;
+use xcb::x;
+
+/// Top-level window known to the window manager.
+#[derive(Debug)]
+pub struct Client {
+    pub window: x::Window,
+
+    /// Position and size without the border, as last configured on the server.
+    pub geometry: Rect,
+    pub border_width: u32,
+    /// Border width set by a rule, overriding the one of the workspace when tiled.
+    pub border_override: Option<u32>,
+
+    pub size_hints: SizeHints,
+    pub motif_hints: MotifHints,
+
+    /// Space reserved at the screen edges, the tiling work area excludes it.
+    pub struts: Option<Struts>,
+
+    /// Docks and bars are never tiled nor focused, and stay above the other clients.
+    pub dock: bool,
+
+    /// Desktop windows (background, icons) are never tiled nor focused, and stay below.
+    pub desktop: bool,
+
+    /// Unmanaged notification, tooltip, menu or splash window, kept above the other clients.
+    pub popup: bool,
+
+    /// Needs attention, until focused.
+    pub urgent: bool,
+
+    /// Floating clients keep their own geometry instead of being tiled.
+    pub floating: bool,
+
+    /// Fullscreen clients cover their monitor, borderless and above the other clients.
+    pub fullscreen: bool,
+    /// Geometry and border width to go back to when leaving fullscreen.
+    pub pre_fullscreen: Option<(Rect, u32)>,
+
+    /// Floating clients spanning the height or width of the work area of their monitor.
+    pub maximized_vert: bool,
+    pub maximized_horz: bool,
+    /// Geometry to go back to when no longer maximized along either axis.
+    pub pre_maximized: Option<Rect>,
+
+    /// Shown on every workspace of its monitor.
+    pub sticky: bool,
+
+    /// Kept above the other clients, below fullscreen clients and docks.
+    pub above: bool,
+    /// Kept below the other clients.
+    pub below: bool,
+
+    /// _NET_WM_STATE as read when the client was mapped and written since, None for
+    /// windows that are not managed.
+    pub states: Option<Vec<x::Atom>>,
+
+    /// Opacity in percent set by a rule or a command, see [`crate::opacity::effective`].
+    pub opacity: Option<u8>,
+
+    /// Window the client is a dialog of.
+    pub transient_for: Option<x::Window>,
+    /// Dialog blocking its parent, kept above it.
+    pub modal: bool,
+
+    /// Index of the monitor showing the center of the client.
+    pub monitor: usize,
+
+    /// WM_CLASS instance and class names.
+    pub instance: String,
+    pub class: String,
+
+    /// _NET_WM_NAME, or WM_NAME for clients not setting it.
+    pub title: String,
+
+    /// _NET_WM_ICON scaled for the OSD.
+    pub icon: Option<Icon>,
+
+    /// WM_WINDOW_ROLE, telling apart windows of the same class.
+    pub role: String,
+
+    /// Process id from _NET_WM_PID.
+    pub pid: Option<u32>,
+
+    /// Answers _NET_WM_PING, listed in WM_PROTOCOLS.
+    pub pingable: bool,
+    /// Input field of WM_HINTS, the window manager sets the input focus on the client.
+    pub input: bool,
+    /// Takes the focus itself when sent WM_TAKE_FOCUS, listed in WM_PROTOCOLS (globally
+    /// and locally active input models).
+    pub take_focus: bool,
+    /// Did not answer the last ping in time.
+    pub unresponsive: bool,
+
+    /// Terminal hidden while this client takes its place, see [`crate::rules::Rule::terminal`].
+    pub swallowed: Option<x::Window>,
+}
+
+impl Client {
+    pub fn new(window: x::Window) -> Self {
+        Self {
+            window,
+            geometry: Rect::default(),
+            border_width: 0,
+            border_override: None,
+            motif_hints: MotifHints::default(),
+            size_hints: SizeHints::default(),
+            struts: None,
+            dock: false,
+            desktop: false,
+            popup: false,
+            urgent: false,
+            floating: false,
+            fullscreen: false,
+            pre_fullscreen: None,
+            maximized_vert: false,
+            maximized_horz: false,
+            pre_maximized: None,
+            sticky: false,
+            above: false,
+            below: false,
+            states: None,
+            opacity: None,
+            transient_for: None,
+            modal: false,
+            monitor: 0,
+            instance: String::new(),
+            class: String::new(),
+            title: String::new(),
+            icon: None,
+            role: String::new(),
+            pid: None,
+            pingable: false,
+            input: true,
+            take_focus: false,
+            unresponsive: false,
+            swallowed: None,
+        }
+    }
+
+    /// Border width when tiled that the workspace cannot change: set by a rule, or none
+    /// for clients asking for no decorations.
+    pub fn forced_border_width(&self) -> Option<u32> {
+        self.border_override
+            .or_else(|| (!self.motif_hints.decorated).then_some(0))
+    }
+
+    /// Area covered by the client including its border.
+    pub fn frame(&self) -> Rect {
+        self.geometry.with_border(self.border_width)
+    }
+
+    /// Geometry covering `area` along the maximized axes, if any.
+    pub fn maximized_geometry(&self, area: Rect) -> Option<Rect> {
+        if !self.maximized_vert && !self.maximized_horz {
+            return None;
+        }
+
+        let frame = area.without_border(self.border_width);
+        let mut geometry = self.geometry;
+        if self.maximized_horz {
+            geometry.x = frame.x;
+            geometry.width = frame.width;
+        }
+        if self.maximized_vert {
+            geometry.y = frame.y;
+            geometry.height = frame.height;
+        }
+        Some(geometry)
+    }
+
+    /// Whether the client takes part in the layout of its workspace.
+    pub fn is_tiled(&self) -> bool {
+        !self.dock && !self.desktop && !self.floating && !self.fullscreen
+    }
+}
@@ -0,0 +1,13 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Resets all split ratios of the current layout to their initial sizes.
+pub struct BalanceLayoutCommand;
+
+impl Command for BalanceLayoutCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.balance_layout();
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+use xcb::x;
+
+/// Asks the focused client to close its window.
+pub struct CloseCommand;
+
+impl Command for CloseCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.close_client(window, x::CURRENT_TIME);
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
+use crate::App;
+use std::rc::Rc;
+
 pub trait Command {
-    fn execute(&self) -> anyhow::Result<()>;
+    fn execute(&self, app: &mut App) -> anyhow::Result<()>;
 }
 
-pub type BoxCommand = Box<dyn Command>;
+pub type CommandRef = Rc<dyn Command>;
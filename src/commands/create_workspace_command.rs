@@ -0,0 +1,27 @@
+use crate::commands::Command;
+use crate::workspace::WorkspaceTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Shows the workspace called `name`, creating it if needed.
+/// Without a name, a new workspace named after the first unused number is created.
+pub struct CreateWorkspaceCommand {
+    name: Option<String>,
+}
+
+impl CreateWorkspaceCommand {
+    pub fn new(name: Option<String>) -> Self {
+        Self { name }
+    }
+}
+
+impl Command for CreateWorkspaceCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => app.unused_workspace_name(),
+        };
+        let index = app.workspace_index(&WorkspaceTarget::Name(name))?;
+        app.view_workspace(index)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Walks through the clients of the current workspace from the most recently focused one,
+/// until the modifiers are released (Alt-Tab).
+pub struct CycleFocusCommand {
+    offset: isize,
+}
+
+impl CycleFocusCommand {
+    pub fn new(offset: isize) -> Self {
+        Self { offset }
+    }
+}
+
+impl Command for CycleFocusCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.cycle_focus(self.offset);
+        Ok(())
+    }
+}
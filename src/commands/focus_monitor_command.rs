@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::monitor::MonitorTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Moves the focus to another monitor, onto the client focused there last.
+pub struct FocusMonitorCommand {
+    target: MonitorTarget,
+}
+
+impl FocusMonitorCommand {
+    pub fn new(target: MonitorTarget) -> Self {
+        Self { target }
+    }
+}
+
+impl Command for FocusMonitorCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.focus_monitor(&self.target)
+    }
+}
@@ -0,0 +1,12 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Shows and focuses a client that asked for attention.
+pub struct JumpToUrgentCommand;
+
+impl Command for JumpToUrgentCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.jump_to_urgent()
+    }
+}
@@ -0,0 +1,28 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Lists the clients not answering pings with the prompt command and kills the chosen one.
+pub struct KillUnresponsiveCommand;
+
+impl Command for KillUnresponsiveCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let windows = app.unresponsive();
+        if windows.is_empty() {
+            return Ok(());
+        }
+
+        let choices: Vec<String> = windows
+            .iter()
+            .enumerate()
+            .map(|(index, window)| format!("{}: {}", index + 1, app.describe_client(*window)))
+            .collect();
+
+        app.prompt_with_choices("Kill", &choices.clone(), move |app, chosen| {
+            if let Some(index) = choices.iter().position(|choice| *choice == chosen) {
+                app.kill_client(windows[index]);
+            }
+            Ok(())
+        })
+    }
+}
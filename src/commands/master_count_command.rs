@@ -0,0 +1,22 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+pub struct MasterCountCommand {
+    delta: isize,
+}
+
+impl MasterCountCommand {
+    pub fn new(delta: isize) -> Self {
+        Self { delta }
+    }
+}
+
+impl Command for MasterCountCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.adjust_layout_params(|params| {
+            params.master_count = params.master_count.saturating_add_signed(self.delta);
+        });
+        Ok(())
+    }
+}
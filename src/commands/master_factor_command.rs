@@ -0,0 +1,23 @@
+use crate::commands::Command;
+use crate::layouts::resize_master_factor;
+use crate::App;
+use anyhow::Result;
+
+pub struct MasterFactorCommand {
+    delta: f32,
+}
+
+impl MasterFactorCommand {
+    pub fn new(delta: f32) -> Self {
+        Self { delta }
+    }
+}
+
+impl Command for MasterFactorCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.adjust_layout_params(|params| {
+            params.master_factor = resize_master_factor(params.master_factor, self.delta);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Hides the focused client until it is restored.
+pub struct MinimizeCommand;
+
+impl Command for MinimizeCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.minimize(window);
+        }
+        Ok(())
+    }
+}
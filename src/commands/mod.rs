@@ -1,5 +1,104 @@
 mod command;
-pub use command::{BoxCommand, Command};
+pub use command::{Command, CommandRef};
+
+mod balance_layout_command;
+pub use balance_layout_command::BalanceLayoutCommand;
+
+mod close_command;
+pub use close_command::CloseCommand;
+
+mod create_workspace_command;
+pub use create_workspace_command::CreateWorkspaceCommand;
+
+mod cycle_focus_command;
+pub use cycle_focus_command::CycleFocusCommand;
+
+mod focus_monitor_command;
+pub use focus_monitor_command::FocusMonitorCommand;
+
+mod jump_to_urgent_command;
+pub use jump_to_urgent_command::JumpToUrgentCommand;
+
+mod kill_unresponsive_command;
+pub use kill_unresponsive_command::KillUnresponsiveCommand;
+
+mod master_count_command;
+pub use master_count_command::MasterCountCommand;
+
+mod master_factor_command;
+pub use master_factor_command::MasterFactorCommand;
+
+mod minimize_command;
+pub use minimize_command::MinimizeCommand;
+
+mod move_to_workspace_command;
+pub use move_to_workspace_command::MoveToWorkspaceCommand;
+
+mod move_workspace_to_monitor_command;
+pub use move_workspace_to_monitor_command::MoveWorkspaceToMonitorCommand;
+
+mod next_layout_command;
+pub use next_layout_command::NextLayoutCommand;
+
+mod opacity_command;
+pub use opacity_command::OpacityCommand;
+
+mod preselect_command;
+pub use preselect_command::PreselectCommand;
+
+mod rename_workspace_command;
+pub use rename_workspace_command::RenameWorkspaceCommand;
+
+mod reorder_workspace_command;
+pub use reorder_workspace_command::ReorderWorkspaceCommand;
+
+mod resize_tiled_command;
+pub use resize_tiled_command::ResizeTiledCommand;
+
+mod restore_layout_command;
+pub use restore_layout_command::RestoreLayoutCommand;
+
+mod restore_minimized_command;
+pub use restore_minimized_command::RestoreMinimizedCommand;
 
 mod rofi_command;
 pub use rofi_command::RofiCommand;
+
+mod save_layout_command;
+pub use save_layout_command::SaveLayoutCommand;
+
+mod select_minimized_command;
+pub use select_minimized_command::SelectMinimizedCommand;
+
+mod set_background_command;
+pub use set_background_command::SetBackgroundCommand;
+
+mod switch_group_command;
+pub use switch_group_command::SwitchGroupCommand;
+
+mod toggle_above_command;
+pub use toggle_above_command::ToggleAboveCommand;
+
+mod toggle_floating_command;
+pub use toggle_floating_command::ToggleFloatingCommand;
+
+mod toggle_fullscreen_command;
+pub use toggle_fullscreen_command::ToggleFullscreenCommand;
+
+mod toggle_sticky_command;
+pub use toggle_sticky_command::ToggleStickyCommand;
+
+mod toggle_tag_command;
+pub use toggle_tag_command::ToggleTagCommand;
+
+mod toggle_view_command;
+pub use toggle_view_command::ToggleViewCommand;
+
+mod transform_layout_command;
+pub use transform_layout_command::{LayoutTransformation, TransformLayoutCommand};
+
+mod view_workspace_command;
+pub use view_workspace_command::ViewWorkspaceCommand;
+
+mod workspace_history_command;
+pub use workspace_history_command::WorkspaceHistoryCommand;
@@ -0,0 +1,27 @@
+use crate::commands::Command;
+use crate::workspace::WorkspaceTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Sends the focused client to another workspace.
+pub struct MoveToWorkspaceCommand {
+    target: WorkspaceTarget,
+    /// Switches to the workspace along with the client, None follows the config.
+    follow: Option<bool>,
+}
+
+impl MoveToWorkspaceCommand {
+    pub fn new(target: WorkspaceTarget, follow: Option<bool>) -> Self {
+        Self { target, follow }
+    }
+}
+
+impl Command for MoveToWorkspaceCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let index = app.workspace_index(&self.target)?;
+        if let Some(window) = app.focused() {
+            app.move_to_workspace(window, index, self.follow);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Transfers the current workspace to the monitor at `offset` from the current one.
+pub struct MoveWorkspaceToMonitorCommand {
+    offset: isize,
+}
+
+impl MoveWorkspaceToMonitorCommand {
+    pub fn new(offset: isize) -> Self {
+        Self { offset }
+    }
+}
+
+impl Command for MoveWorkspaceToMonitorCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.move_workspace_to_adjacent_monitor(self.offset);
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+pub struct NextLayoutCommand;
+
+impl Command for NextLayoutCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.next_layout();
+        Ok(())
+    }
+}
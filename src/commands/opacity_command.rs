@@ -0,0 +1,23 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Makes the focused client more (positive delta) or less opaque, in percent.
+pub struct OpacityCommand {
+    delta: i8,
+}
+
+impl OpacityCommand {
+    pub fn new(delta: i8) -> Self {
+        Self { delta }
+    }
+}
+
+impl Command for OpacityCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.adjust_opacity(window, self.delta);
+        }
+        Ok(())
+    }
+}
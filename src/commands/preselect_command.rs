@@ -0,0 +1,27 @@
+use crate::commands::Command;
+use crate::geometry::Direction;
+use crate::App;
+use anyhow::Result;
+
+/// Reserves part of the focused client for the next one, on the given side.
+/// Running it again with the same direction cancels the preselection.
+pub struct PreselectCommand {
+    direction: Direction,
+    /// Share of the focused client's area given to the next client.
+    ratio: f32,
+}
+
+impl PreselectCommand {
+    pub fn new(direction: Direction, ratio: f32) -> Self {
+        Self { direction, ratio }
+    }
+}
+
+impl Command for PreselectCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.preselect(window, self.direction, self.ratio);
+        }
+        Ok(())
+    }
+}
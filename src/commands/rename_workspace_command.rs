@@ -0,0 +1,23 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Renames the current workspace, asking for the name with the prompt command if not given.
+pub struct RenameWorkspaceCommand {
+    name: Option<String>,
+}
+
+impl RenameWorkspaceCommand {
+    pub fn new(name: Option<String>) -> Self {
+        Self { name }
+    }
+}
+
+impl Command for RenameWorkspaceCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        match &self.name {
+            Some(name) => app.rename_workspace(name),
+            None => app.prompt("Rename workspace", |app, name| app.rename_workspace(&name)),
+        }
+    }
+}
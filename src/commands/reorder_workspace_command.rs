@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Moves the current workspace left (negative offset) or right in the workspace order.
+pub struct ReorderWorkspaceCommand {
+    offset: isize,
+}
+
+impl ReorderWorkspaceCommand {
+    pub fn new(offset: isize) -> Self {
+        Self { offset }
+    }
+}
+
+impl Command for ReorderWorkspaceCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.reorder_workspace(self.offset);
+        Ok(())
+    }
+}
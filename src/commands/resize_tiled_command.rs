@@ -0,0 +1,26 @@
+use crate::commands::Command;
+use crate::geometry::Direction;
+use crate::App;
+use anyhow::Result;
+
+/// Moves the edge of the focused tiled client, resizing its neighbours as well.
+pub struct ResizeTiledCommand {
+    direction: Direction,
+    /// Fraction of the screen, negative values shrink the client.
+    delta: f32,
+}
+
+impl ResizeTiledCommand {
+    pub fn new(direction: Direction, delta: f32) -> Self {
+        Self { direction, delta }
+    }
+}
+
+impl Command for ResizeTiledCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.resize_tiled(window, self.direction, self.delta);
+        }
+        Ok(())
+    }
+}
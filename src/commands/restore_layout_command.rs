@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub struct RestoreLayoutCommand {
+    /// Snapshot file, defaults to a per-workspace file in the state directory.
+    path: Option<PathBuf>,
+}
+
+impl RestoreLayoutCommand {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+}
+
+impl Command for RestoreLayoutCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.restore_layout_snapshot(self.path.clone())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Shows the most recently minimized client again.
+pub struct RestoreMinimizedCommand;
+
+impl Command for RestoreMinimizedCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        match app.minimized().last() {
+            Some(window) => app.restore(*window),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,15 +1,12 @@
 use crate::commands::Command;
-use anyhow::{anyhow, Result};
+use crate::App;
+use anyhow::Result;
 
 pub struct RofiCommand;
 
 impl Command for RofiCommand {
-    fn execute(&self) -> Result<()> {
-        std::process::Command::new("rofi")
-            .arg("-show")
-            .arg("run")
-            .spawn()
-            .map(|_| ())
-            .map_err(|e| anyhow!(e))
+    fn execute(&self, app: &mut App) -> Result<()> {
+        // Applications started from rofi inherit its startup id.
+        app.spawn(std::process::Command::new("rofi").arg("-show").arg("run"))
     }
 }
@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub struct SaveLayoutCommand {
+    /// Snapshot file, defaults to a per-workspace file in the state directory.
+    path: Option<PathBuf>,
+}
+
+impl SaveLayoutCommand {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+}
+
+impl Command for SaveLayoutCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.save_layout_snapshot(self.path.clone())
+    }
+}
@@ -0,0 +1,31 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Lists the minimized clients with the prompt command and restores the chosen one.
+pub struct SelectMinimizedCommand;
+
+impl Command for SelectMinimizedCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        // Most recently minimized first, numbered to tell apart clients with the same title.
+        let windows: Vec<_> = app.minimized().iter().rev().copied().collect();
+        if windows.is_empty() {
+            return Ok(());
+        }
+
+        let choices: Vec<String> = windows
+            .iter()
+            .enumerate()
+            .map(|(index, window)| format!("{}: {}", index + 1, app.describe_client(*window)))
+            .collect();
+
+        app.prompt_with_choices(
+            "Restore",
+            &choices.clone(),
+            move |app, chosen| match choices.iter().position(|choice| *choice == chosen) {
+                Some(index) => app.restore(windows[index]),
+                None => Ok(()),
+            },
+        )
+    }
+}
@@ -0,0 +1,31 @@
+use crate::commands::Command;
+use crate::wallpaper::Background;
+use crate::App;
+use anyhow::Result;
+
+/// Sets the root background of the current monitor, asking for it with the prompt command
+/// if not given.
+pub struct SetBackgroundCommand {
+    background: Option<Background>,
+}
+
+impl SetBackgroundCommand {
+    pub fn new(background: Option<Background>) -> Self {
+        Self { background }
+    }
+}
+
+impl Command for SetBackgroundCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        match &self.background {
+            Some(background) => {
+                app.set_background(background.clone());
+                Ok(())
+            }
+            None => app.prompt("Background (#rrggbb or PPM image)", |app, background| {
+                app.set_background(background.parse()?);
+                Ok(())
+            }),
+        }
+    }
+}
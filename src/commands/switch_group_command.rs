@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::workspace::GroupTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Makes another workspace group the active one.
+pub struct SwitchGroupCommand {
+    target: GroupTarget,
+}
+
+impl SwitchGroupCommand {
+    pub fn new(target: GroupTarget) -> Self {
+        Self { target }
+    }
+}
+
+impl Command for SwitchGroupCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.switch_group(&self.target)
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Keeps the focused client above the others, or lets it be covered again.
+pub struct ToggleAboveCommand;
+
+impl Command for ToggleAboveCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.set_above(window, !app.is_above(window));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Takes the focused client out of the layout, or puts it back.
+pub struct ToggleFloatingCommand;
+
+impl Command for ToggleFloatingCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.toggle_floating(window);
+        }
+        Ok(())
+    }
+}
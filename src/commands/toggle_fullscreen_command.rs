@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Makes the focused client cover its monitor, or brings it back.
+pub struct ToggleFullscreenCommand;
+
+impl Command for ToggleFullscreenCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.set_fullscreen(window, !app.is_fullscreen(window));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Shows the focused client on every workspace of its monitor, or only on its own again.
+pub struct ToggleStickyCommand;
+
+impl Command for ToggleStickyCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        if let Some(window) = app.focused() {
+            app.set_sticky(window, !app.is_sticky(window));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::commands::Command;
+use crate::workspace::WorkspaceTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Adds the focused client to a workspace or removes it from there, so that
+/// it can be shown on several workspaces like a client with multiple dwm tags.
+pub struct ToggleTagCommand {
+    index: usize,
+}
+
+impl ToggleTagCommand {
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl Command for ToggleTagCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let index = app.workspace_index(&WorkspaceTarget::Index(self.index))?;
+        if let Some(window) = app.focused() {
+            app.toggle_tag(window, index);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use crate::commands::Command;
+use crate::workspace::WorkspaceTarget;
+use crate::App;
+use anyhow::Result;
+
+/// Shows the clients of a workspace along with the current one, or hides them again.
+pub struct ToggleViewCommand {
+    index: usize,
+}
+
+impl ToggleViewCommand {
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+}
+
+impl Command for ToggleViewCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let index = app.workspace_index(&WorkspaceTarget::Index(self.index))?;
+        app.toggle_view(index)
+    }
+}
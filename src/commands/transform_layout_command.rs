@@ -0,0 +1,33 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+pub enum LayoutTransformation {
+    /// Rotates by 90 degrees clockwise.
+    Rotate,
+    /// Toggles mirroring along the vertical axis.
+    Mirror,
+    /// Toggles flipping along the horizontal axis.
+    Flip,
+}
+
+pub struct TransformLayoutCommand {
+    transformation: LayoutTransformation,
+}
+
+impl TransformLayoutCommand {
+    pub fn new(transformation: LayoutTransformation) -> Self {
+        Self { transformation }
+    }
+}
+
+impl Command for TransformLayoutCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.adjust_layout_params(|params| match self.transformation {
+            LayoutTransformation::Rotate => params.transform.rotate(),
+            LayoutTransformation::Mirror => params.transform.mirror ^= true,
+            LayoutTransformation::Flip => params.transform.flip ^= true,
+        });
+        Ok(())
+    }
+}
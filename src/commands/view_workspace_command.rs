@@ -0,0 +1,21 @@
+use crate::commands::Command;
+use crate::workspace::WorkspaceTarget;
+use crate::App;
+use anyhow::Result;
+
+pub struct ViewWorkspaceCommand {
+    target: WorkspaceTarget,
+}
+
+impl ViewWorkspaceCommand {
+    pub fn new(target: WorkspaceTarget) -> Self {
+        Self { target }
+    }
+}
+
+impl Command for ViewWorkspaceCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        let index = app.workspace_index(&self.target)?;
+        app.view_workspace(index)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::commands::Command;
+use crate::App;
+use anyhow::Result;
+
+/// Goes back or forward through the workspaces visited on the current monitor.
+pub struct WorkspaceHistoryCommand {
+    forward: bool,
+}
+
+impl WorkspaceHistoryCommand {
+    pub fn back() -> Self {
+        Self { forward: false }
+    }
+
+    pub fn forward() -> Self {
+        Self { forward: true }
+    }
+}
+
+impl Command for WorkspaceHistoryCommand {
+    fn execute(&self, app: &mut App) -> Result<()> {
+        app.navigate_workspace_history(self.forward)
+    }
+}
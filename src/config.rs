@@ -1,11 +1,255 @@
+use crate::activation::ActivationPolicy;
 use crate::keybind::Keybind;
+use crate::layouts::Gaps;
+use crate::mouse::FocusModel;
+use crate::osd::OsdPosition;
+use crate::randr::OutputProfile;
+use crate::rules::Rule;
+use crate::size_hints::HintsAlignment;
+use crate::wallpaper::Background;
+use crate::workspace::{InsertionPolicy, WorkspaceModel};
+use std::collections::HashMap;
+use std::time::Duration;
 
-#[derive(Debug, Default)]
+/// Appearance settings a workspace overrides, unset fields fall back to the global ones.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AppearanceOverrides {
+    pub gaps: Option<Gaps>,
+    pub border_width: Option<u32>,
+    pub smart_borders: Option<bool>,
+}
+
+#[derive(Debug)]
 pub struct Config {
     pub keybinds: Vec<Keybind>,
+
+    pub workspace_count: usize,
+
+    pub workspace_model: WorkspaceModel,
+
+    /// RandR output names that workspaces (by name) are pinned to.
+    pub workspace_outputs: HashMap<String, String>,
+
+    /// Initial layout of the workspaces of the monitors showing an output (by RandR name),
+    /// e.g. a vertical stack on a side monitor. Only per-monitor workspaces use it.
+    pub output_layouts: HashMap<String, String>,
+
+    /// Layout of per-monitor workspaces on portrait outputs without an output layout,
+    /// e.g. a deck. Switched to and back when an output is rotated.
+    pub portrait_layout: Option<String>,
+
+    /// Modes, positions and the primary output to set up when the outputs are connected,
+    /// like autorandr does.
+    pub output_profiles: Vec<OutputProfile>,
+
+    /// Root window background of monitors without an output background, left to other
+    /// programs if not set.
+    pub background: Option<Background>,
+
+    /// Root window backgrounds by output name.
+    pub output_backgrounds: HashMap<String, Background>,
+
+    /// Bar started for every monitor and stopped when its output disappears, the program
+    /// followed by its arguments. It gets the output name in MONITOR, e.g. for polybar.
+    pub bar_command: Vec<String>,
+
+    /// Scales borders and gaps by the resolution of each output, relative to 96 DPI.
+    pub dpi_scaling: bool,
+
+    /// Resolution of outputs (by RandR name) reporting a wrong physical size.
+    pub output_dpi: HashMap<String, f32>,
+
+    /// Names of the workspace groups (activities), each with its own set of workspaces.
+    /// Numeric binds address the workspaces of the active group, none means a single group.
+    pub workspace_groups: Vec<String>,
+
+    /// Names of the first workspaces, the others are named after their number.
+    pub workspace_names: Vec<String>,
+
+    pub gaps: Gaps,
+
+    pub border_width: u32,
+
+    /// Border colors of the focused client, the other clients and clients needing attention.
+    pub border_color_focused: u32,
+    pub border_color_normal: u32,
+    pub border_color_urgent: u32,
+    /// Border color of the client a dragged tiled client would be swapped with.
+    pub border_color_drop: u32,
+    /// Border color of clients not answering pings.
+    pub border_color_unresponsive: u32,
+
+    /// How often clients are pinged, those not answering by the next ping are marked
+    /// unresponsive. None disables pinging.
+    pub ping_interval: Option<Duration>,
+
+    /// Opacity in percent of unfocused clients, applied by a compositor. None leaves them
+    /// opaque.
+    pub inactive_opacity: Option<u8>,
+
+    /// Hides the border when only one tiled client is visible.
+    pub smart_borders: bool,
+
+    /// Gaps and borders of specific workspaces (by name), e.g. none on a "video" workspace.
+    pub workspace_appearance: HashMap<String, AppearanceOverrides>,
+
+    /// Applies the increments of WM_NORMAL_HINTS to tiled clients, which may leave part of a
+    /// tile empty. Minimum and maximum sizes are always applied.
+    pub tiled_size_hints: bool,
+
+    /// Placement of a tiled client that does not fill its whole tile.
+    pub size_hints_alignment: HintsAlignment,
+
+    /// Relative widths of the left, middle and right columns of the three-column layout.
+    pub three_column_ratios: [f32; 3],
+
+    /// Fraction of the screen width taken by the master in the deck layout.
+    pub deck_master_factor: f32,
+
+    /// Where new windows enter the tiling order.
+    pub insertion_policy: InsertionPolicy,
+
+    /// Insertion policies of specific workspaces (by index), overriding `insertion_policy`.
+    pub workspace_insertion_policies: HashMap<usize, InsertionPolicy>,
+
+    pub rules: Vec<Rule>,
+
+    pub focus_model: FocusModel,
+
+    /// Distance in pixels within which moved windows stick to screen and window edges,
+    /// 0 disables snapping.
+    pub snap_threshold: u32,
+
+    /// Lets windows moved under a still pointer by the layout take the focus.
+    pub focus_on_layout_change: bool,
+
+    /// Moves the pointer to the center of the client (or monitor) focused by a keybind,
+    /// unless it is already there.
+    pub warp_pointer: bool,
+
+    /// Lets focus cycling visit sticky clients of other workspaces.
+    pub cycle_sticky_clients: bool,
+
+    /// Handling of clients asking for the focus after the user did something else.
+    pub activation_policy: ActivationPolicy,
+
+    /// Gives the focus to the modal dialog of a window instead of the window itself.
+    pub focus_modal_dialogs: bool,
+
+    /// Switches to the workspace a window is moved to, unless the command says otherwise.
+    pub follow_moved_window: bool,
+
+    /// dmenu-like command asking for text, the prompt message is passed as the last argument.
+    pub prompt_command: Vec<String>,
+
+    /// How long the workspace name is shown after switching, None disables it.
+    pub osd_timeout: Option<Duration>,
+    pub osd_position: OsdPosition,
+    /// X core font name.
+    pub osd_font: String,
+    pub osd_foreground: u32,
+    pub osd_background: u32,
+
+    /// Color of the area reserved for the next client by a preselection.
+    pub preselection_color: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keybinds: Vec::new(),
+            workspace_count: 9,
+            workspace_model: WorkspaceModel::default(),
+            workspace_outputs: HashMap::new(),
+            output_layouts: HashMap::new(),
+            portrait_layout: None,
+            output_profiles: Vec::new(),
+            background: None,
+            output_backgrounds: HashMap::new(),
+            bar_command: Vec::new(),
+            dpi_scaling: false,
+            output_dpi: HashMap::new(),
+            workspace_groups: Vec::new(),
+            workspace_names: Vec::new(),
+            gaps: Gaps::default(),
+            border_width: 1,
+            border_color_focused: 0x4c7899,
+            border_color_normal: 0x333333,
+            border_color_urgent: 0x900000,
+            border_color_drop: 0xd79921,
+            border_color_unresponsive: 0x689d6a,
+            ping_interval: Some(Duration::from_secs(5)),
+            inactive_opacity: None,
+            smart_borders: true,
+            workspace_appearance: HashMap::new(),
+            tiled_size_hints: true,
+            size_hints_alignment: HintsAlignment::default(),
+            three_column_ratios: [1.0, 2.0, 1.0],
+            deck_master_factor: 0.55,
+            insertion_policy: InsertionPolicy::default(),
+            workspace_insertion_policies: HashMap::new(),
+            rules: Vec::new(),
+            focus_model: FocusModel::default(),
+            snap_threshold: 10,
+            focus_on_layout_change: false,
+            activation_policy: ActivationPolicy::default(),
+            warp_pointer: false,
+            cycle_sticky_clients: true,
+            focus_modal_dialogs: true,
+            follow_moved_window: false,
+            prompt_command: vec!["rofi".to_owned(), "-dmenu".to_owned(), "-p".to_owned()],
+            osd_timeout: Some(Duration::from_millis(600)),
+            osd_position: OsdPosition::default(),
+            osd_font: "fixed".to_owned(),
+            osd_foreground: 0xffffff,
+            osd_background: 0x285577,
+            preselection_color: 0x4c7899,
+        }
+    }
 }
 
 impl Config {
+    pub fn workspace_name(&self, index: usize) -> String {
+        self.workspace_names
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| (index + 1).to_string())
+    }
+
+    pub fn workspace_insertion_policy(&self, index: usize) -> InsertionPolicy {
+        self.workspace_insertion_policies
+            .get(&index)
+            .copied()
+            .unwrap_or(self.insertion_policy)
+    }
+
+    pub fn group_count(&self) -> usize {
+        self.workspace_groups.len().max(1)
+    }
+
+    fn appearance(&self, workspace: &str) -> AppearanceOverrides {
+        self.workspace_appearance
+            .get(workspace)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn workspace_gaps(&self, workspace: &str) -> Gaps {
+        self.appearance(workspace).gaps.unwrap_or(self.gaps)
+    }
+
+    pub fn workspace_border_width(&self, workspace: &str) -> u32 {
+        self.appearance(workspace)
+            .border_width
+            .unwrap_or(self.border_width)
+    }
+
+    pub fn workspace_smart_borders(&self, workspace: &str) -> bool {
+        self.appearance(workspace)
+            .smart_borders
+            .unwrap_or(self.smart_borders)
+    }
+
     pub fn add_keybind(&mut self, keybind: Keybind) {
         self.keybinds.push(keybind);
     }
@@ -0,0 +1,152 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> (i32, i32) {
+        (
+            self.x + (self.width / 2) as i32,
+            self.y + (self.height / 2) as i32,
+        )
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// Shrinks the rect by the given amount on each side, never below 1x1.
+    pub fn inset(&self, left: u32, top: u32, right: u32, bottom: u32) -> Rect {
+        Rect::new(
+            self.x + left as i32,
+            self.y + top as i32,
+            self.width.saturating_sub(left + right).max(1),
+            self.height.saturating_sub(top + bottom).max(1),
+        )
+    }
+
+    /// Part of the rect also covered by `other`, at least 1x1.
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        Rect::new(x, y, (right - x).max(1) as u32, (bottom - y).max(1) as u32)
+    }
+
+    /// Size of the client area of a window with the given border, whose outer
+    /// edge matches this rect.
+    pub fn without_border(&self, border_width: u32) -> Rect {
+        Rect::new(
+            self.x,
+            self.y,
+            self.width.saturating_sub(2 * border_width).max(1),
+            self.height.saturating_sub(2 * border_width).max(1),
+        )
+    }
+
+    /// Outer area of a window whose client area is this rect, the inverse of `without_border`.
+    pub fn with_border(&self, border_width: u32) -> Rect {
+        Rect::new(
+            self.x,
+            self.y,
+            self.width + 2 * border_width,
+            self.height + 2 * border_width,
+        )
+    }
+
+    /// Whether the point lies inside the rect.
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.x..self.right()).contains(&x) && (self.y..self.bottom()).contains(&y)
+    }
+
+    /// Splits the rect horizontally into columns sized proportionally to `weights`.
+    /// Rounding leftovers go to the last column so the columns always cover the whole rect.
+    pub fn split_columns(&self, weights: &[f32]) -> Vec<Rect> {
+        let sizes = distribute(self.width, weights);
+
+        let mut x = self.x;
+        sizes
+            .into_iter()
+            .map(|width| {
+                let column = Rect::new(x, self.y, width, self.height);
+                x += width as i32;
+                column
+            })
+            .collect()
+    }
+
+    /// Splits the rect vertically into rows sized proportionally to `weights`.
+    pub fn split_rows(&self, weights: &[f32]) -> Vec<Rect> {
+        let sizes = distribute(self.height, weights);
+
+        let mut y = self.y;
+        sizes
+            .into_iter()
+            .map(|height| {
+                let row = Rect::new(self.x, y, self.width, height);
+                y += height as i32;
+                row
+            })
+            .collect()
+    }
+}
+
+fn distribute(total: u32, weights: &[f32]) -> Vec<u32> {
+    let sum: f32 = weights.iter().sum();
+    if weights.is_empty() || sum <= 0.0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut sizes: Vec<u32> = weights
+        .iter()
+        .map(|weight| (total as f32 * weight / sum) as u32)
+        .collect();
+
+    let used: u32 = sizes.iter().sum();
+    if let Some(last) = sizes.last_mut() {
+        *last += total - used;
+    }
+
+    sizes
+}
@@ -0,0 +1,127 @@
+/// Browser-like history: visiting an entry drops everything after the current position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<T> {
+    entries: Vec<T>,
+    position: usize,
+}
+
+const MAX_ENTRIES: usize = 100;
+
+impl<T: Copy + PartialEq> History<T> {
+    pub fn new(current: T) -> Self {
+        Self {
+            entries: vec![current],
+            position: 0,
+        }
+    }
+
+    pub fn visit(&mut self, entry: T) {
+        if self.entries.get(self.position) == Some(&entry) {
+            return;
+        }
+
+        self.entries.truncate(self.position + 1);
+        self.entries.push(entry);
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.position = self.entries.len() - 1;
+    }
+
+    pub fn back(&mut self) -> Option<T> {
+        self.position = self.position.checked_sub(1)?;
+        Some(self.entries[self.position])
+    }
+
+    pub fn forward(&mut self) -> Option<T> {
+        if self.position + 1 >= self.entries.len() {
+            return None;
+        }
+
+        self.position += 1;
+        Some(self.entries[self.position])
+    }
+
+    /// Most recent entry up to the current position that satisfies `predicate`.
+    pub fn latest<F: Fn(T) -> bool>(&self, predicate: F) -> Option<T> {
+        self.entries[..=self.position]
+            .iter()
+            .rev()
+            .copied()
+            .find(|entry| predicate(*entry))
+    }
+
+    /// Replaces entries, dropping those mapped to None and merging resulting repeats.
+    pub fn update<F: Fn(T) -> Option<T>>(&mut self, update: F) {
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut position = 0;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some(entry) = update(*entry) {
+                if entries.last() != Some(&entry) {
+                    entries.push(entry);
+                }
+            }
+
+            if index == self.position {
+                position = entries.len().saturating_sub(1);
+            }
+        }
+
+        if !entries.is_empty() {
+            self.entries = entries;
+            self.position = position;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn navigates_back_and_forward() {
+        let mut history = History::new(0);
+        history.visit(1);
+        history.visit(2);
+
+        assert_eq!(history.back(), Some(1));
+        assert_eq!(history.back(), Some(0));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some(1));
+
+        // Visiting from the middle drops the forward entries.
+        history.visit(3);
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(1));
+    }
+
+    #[test]
+    fn latest_ignores_forward_entries() {
+        let mut history = History::new(0);
+        history.visit(1);
+        history.visit(2);
+        history.visit(3);
+        history.back();
+
+        assert_eq!(history.latest(|entry| entry % 2 == 1), Some(1));
+        assert_eq!(history.latest(|entry| entry > 2), None);
+    }
+
+    #[test]
+    fn update_removes_entries_and_merges_repeats() {
+        let mut history = History::new(0);
+        history.visit(1);
+        history.visit(0);
+        history.visit(2);
+
+        history.update(|entry| (entry != 1).then_some(entry));
+
+        assert_eq!(history.back(), Some(0));
+        assert_eq!(history.back(), None);
+
+        history.update(|entry| Some(entry + 1));
+        assert_eq!(history.forward(), Some(3));
+    }
+}
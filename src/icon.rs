@@ -0,0 +1,129 @@
+/// Client icon in ARGB (non-premultiplied), row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Icon {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u32>,
+}
+
+impl Icon {
+    /// Parses the icons of a _NET_WM_ICON property, each one its width, height and pixels.
+    pub fn parse_all(values: &[u32]) -> Vec<Icon> {
+        let mut icons = Vec::new();
+        let mut rest = values;
+
+        while let [width, height, data @ ..] = rest {
+            let len = (*width as usize).saturating_mul(*height as usize);
+            if len == 0 || len > data.len() {
+                break;
+            }
+            icons.push(Icon {
+                width: *width,
+                height: *height,
+                pixels: data[..len].to_vec(),
+            });
+            rest = &data[len..];
+        }
+
+        icons
+    }
+
+    /// Icon fitting a `size` square, scaled from the smallest one at least that large (or
+    /// the largest one) keeping its proportions.
+    pub fn best(icons: &[Icon], size: u32) -> Option<Icon> {
+        let side = |icon: &&Icon| icon.width.max(icon.height);
+        let source = icons
+            .iter()
+            .filter(|icon| side(icon) >= size)
+            .min_by_key(side)
+            .or_else(|| icons.iter().max_by_key(side))?;
+
+        Some(source.scaled(size))
+    }
+
+    /// Nearest-neighbour scaling so that the longer side is `size`.
+    fn scaled(&self, size: u32) -> Icon {
+        let side = self.width.max(self.height) as u64;
+        let width = ((self.width as u64 * size as u64 / side) as u32).max(1);
+        let height = ((self.height as u64 * size as u64 / side) as u32).max(1);
+
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let source_y = (y as u64 * self.height as u64 / height as u64) as u32;
+            for x in 0..width {
+                let source_x = (x as u64 * self.width as u64 / width as u64) as u32;
+                pixels.push(self.pixels[(source_y * self.width + source_x) as usize]);
+            }
+        }
+
+        Icon {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Opaque RGB pixels of the icon drawn over `background`.
+    pub fn blend(&self, background: u32) -> Vec<u32> {
+        let channel = |color: u32, shift: u32| (color >> shift) & 0xFF;
+
+        self.pixels
+            .iter()
+            .map(|pixel| {
+                let alpha = channel(*pixel, 24);
+                [16, 8, 0].into_iter().fold(0, |rgb, shift| {
+                    let value = (channel(*pixel, shift) * alpha
+                        + channel(background, shift) * (255 - alpha))
+                        / 255;
+                    rgb | value << shift
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Icon;
+
+    #[test]
+    fn parses_every_icon_of_the_property() {
+        let values = [1, 1, 0xFF000000, 2, 1, 0xFFFF0000, 0xFF00FF00, 4, 4, 0];
+        let icons = Icon::parse_all(&values);
+
+        assert_eq!(icons.len(), 2);
+        assert_eq!(icons[1].width, 2);
+        assert_eq!(icons[1].pixels, vec![0xFFFF0000, 0xFF00FF00]);
+    }
+
+    #[test]
+    fn scales_the_closest_larger_icon() {
+        let icon = |size: u32, pixel: u32| Icon {
+            width: size,
+            height: size,
+            pixels: vec![pixel; (size * size) as usize],
+        };
+        let icons = [icon(16, 1), icon(64, 2), icon(48, 3)];
+
+        let best = Icon::best(&icons, 32).unwrap();
+        assert_eq!((best.width, best.height), (32, 32));
+        assert!(best.pixels.iter().all(|pixel| *pixel == 3));
+
+        // Upscaled from the largest one.
+        assert_eq!(Icon::best(&icons, 128).unwrap().pixels[0], 2);
+        assert_eq!(Icon::best(&[], 32), None);
+    }
+
+    #[test]
+    fn keeps_proportions_and_blends_transparency() {
+        let icon = Icon {
+            width: 4,
+            height: 2,
+            pixels: vec![0x80FFFFFF; 8],
+        };
+
+        let best = Icon::best(&[icon], 2).unwrap();
+        assert_eq!((best.width, best.height), (2, 1));
+        assert_eq!(best.blend(0x000000), vec![0x808080, 0x808080]);
+    }
+}
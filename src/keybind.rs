@@ -1,4 +1,4 @@
-use crate::commands::BoxCommand;
+use crate::commands::CommandRef;
 use xcb::x::{KeyButMask, Keycode};
 use xkbcommon::xkb::Keysym;
 
@@ -61,6 +61,7 @@ impl KeySequence {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::KeySequence;
     use xcb::x::KeyButMask;
@@ -94,11 +95,11 @@ mod tests {
 pub struct Keybind {
     key_sequence: KeySequence,
     keycodes: Vec<Keycode>,
-    command: BoxCommand,
+    command: CommandRef,
 }
 
 impl Keybind {
-    pub fn new(key_sequence: KeySequence, command: BoxCommand) -> Self {
+    pub fn new(key_sequence: KeySequence, command: CommandRef) -> Self {
         Self {
             key_sequence,
             keycodes: Vec::new(),
@@ -114,7 +115,7 @@ impl Keybind {
         self.keycodes = keycodes;
     }
 
-    pub fn command(&self) -> &BoxCommand {
+    pub fn command(&self) -> &CommandRef {
         &self.command
     }
 
@@ -0,0 +1,445 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::{Layout, LayoutClient, LayoutParams, Preselection};
+
+/// Smallest share of a split either side can be resized to.
+const MIN_RATIO: f32 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf,
+    Split {
+        /// Children placed side by side rather than on top of each other.
+        side_by_side: bool,
+        /// Share of the first child.
+        ratio: f32,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+impl Node {
+    fn leaves(&self) -> usize {
+        match self {
+            Node::Leaf => 1,
+            Node::Split { first, second, .. } => first.leaves() + second.leaves(),
+        }
+    }
+
+    fn arrange(&self, area: Rect, geometries: &mut Vec<Rect>) {
+        match self {
+            Node::Leaf => geometries.push(area),
+            Node::Split {
+                side_by_side,
+                ratio,
+                first,
+                second,
+            } => {
+                let weights = [*ratio, 1.0 - ratio];
+                let areas = if *side_by_side {
+                    area.split_columns(&weights)
+                } else {
+                    area.split_rows(&weights)
+                };
+
+                first.arrange(areas[0], geometries);
+                second.arrange(areas[1], geometries);
+            }
+        }
+    }
+
+    /// Replaces the leaf at `index` by a split holding it and a new leaf.
+    fn split(&mut self, index: usize, depth: usize, split: Split) {
+        match self {
+            Node::Leaf => {
+                let ratio = if split.new_first {
+                    split.ratio
+                } else {
+                    1.0 - split.ratio
+                };
+
+                *self = Node::Split {
+                    side_by_side: split.side_by_side.unwrap_or(depth.is_multiple_of(2)),
+                    ratio,
+                    first: Box::new(Node::Leaf),
+                    second: Box::new(Node::Leaf),
+                };
+            }
+            Node::Split { first, second, .. } => {
+                let first_leaves = first.leaves();
+                if index < first_leaves {
+                    first.split(index, depth + 1, split);
+                } else {
+                    second.split(index - first_leaves, depth + 1, split);
+                }
+            }
+        }
+    }
+
+    /// Removes the leaf at `index`, returns None when the node itself disappears.
+    fn remove(self, index: usize) -> Option<Node> {
+        match self {
+            Node::Leaf => None,
+            Node::Split {
+                side_by_side,
+                ratio,
+                first,
+                second,
+            } => {
+                let first_leaves = first.leaves();
+                let (first, second) = if index < first_leaves {
+                    (first.remove(index), Some(*second))
+                } else {
+                    (Some(*first), second.remove(index - first_leaves))
+                };
+
+                match (first, second) {
+                    (Some(first), Some(second)) => Some(Node::Split {
+                        side_by_side,
+                        ratio,
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (node, None) | (None, node) => node,
+                }
+            }
+        }
+    }
+
+    fn balance(&mut self) {
+        if let Node::Split {
+            ratio,
+            first,
+            second,
+            ..
+        } = self
+        {
+            *ratio = 0.5;
+            first.balance();
+            second.balance();
+        }
+    }
+
+    /// Moves the nearest edge of the leaf at `index` facing `direction`.
+    /// `extent` is the size of this node relative to the whole area.
+    fn resize(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        delta: f32,
+        extent: (f32, f32),
+    ) -> bool {
+        let Node::Split {
+            side_by_side,
+            ratio,
+            first,
+            second,
+        } = self
+        else {
+            return false;
+        };
+
+        let first_leaves = first.leaves();
+        let in_first = index < first_leaves;
+
+        let (first_extent, second_extent) = if *side_by_side {
+            (
+                (extent.0 * *ratio, extent.1),
+                (extent.0 * (1.0 - *ratio), extent.1),
+            )
+        } else {
+            (
+                (extent.0, extent.1 * *ratio),
+                (extent.0, extent.1 * (1.0 - *ratio)),
+            )
+        };
+
+        let resized = if in_first {
+            first.resize(index, direction, delta, first_extent)
+        } else {
+            second.resize(index - first_leaves, direction, delta, second_extent)
+        };
+        if resized {
+            return true;
+        }
+
+        let change = match (*side_by_side, direction, in_first) {
+            (true, Direction::Right, true) => delta / extent.0,
+            (true, Direction::Left, false) => -delta / extent.0,
+            (false, Direction::Down, true) => delta / extent.1,
+            (false, Direction::Up, false) => -delta / extent.1,
+            _ => return false,
+        };
+
+        *ratio = (*ratio + change).clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+        true
+    }
+}
+
+/// How a leaf is split when a client is inserted.
+#[derive(Debug, Clone, Copy)]
+struct Split {
+    side_by_side: Option<bool>,
+    new_first: bool,
+    /// Share of the new client.
+    ratio: f32,
+}
+
+/// Binary space partitioning layout with manual splits.
+///
+/// Clients are the leaves of a tree of splits, in order. New clients split the
+/// leaf they are inserted at, or the preselected leaf if there is one.
+#[derive(Debug, Default, Clone)]
+pub struct Bsp {
+    root: Option<Node>,
+    preselection: Option<Preselection>,
+}
+
+impl Layout for Bsp {
+    fn name(&self) -> &'static str {
+        "bsp"
+    }
+
+    fn arrange(&self, _params: &LayoutParams, clients: &[LayoutClient], area: Rect) -> Vec<Rect> {
+        let mut geometries = Vec::with_capacity(clients.len());
+        if let Some(root) = &self.root {
+            root.arrange(area, &mut geometries);
+        }
+        geometries.truncate(clients.len());
+        geometries
+    }
+
+    fn resize(
+        &mut self,
+        _params: &mut LayoutParams,
+        _clients: &mut [LayoutClient],
+        index: usize,
+        direction: Direction,
+        delta: f32,
+    ) -> bool {
+        match &mut self.root {
+            Some(root) if index < root.leaves() => root.resize(index, direction, delta, (1.0, 1.0)),
+            _ => false,
+        }
+    }
+
+    fn balance(&mut self) {
+        if let Some(root) = &mut self.root {
+            root.balance();
+        }
+    }
+
+    fn insertion_index(&self) -> Option<usize> {
+        self.preselection
+            .map(|preselection| match preselection.direction {
+                Direction::Left | Direction::Up => preselection.index,
+                Direction::Right | Direction::Down => preselection.index + 1,
+            })
+    }
+
+    fn insert(&mut self, index: usize, _count: usize) {
+        let root = match &mut self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(Node::Leaf);
+                return;
+            }
+        };
+        let leaves = root.leaves();
+
+        match self.preselection.take() {
+            Some(preselection) => root.split(
+                preselection.index,
+                0,
+                Split {
+                    side_by_side: Some(preselection.direction.is_horizontal()),
+                    new_first: matches!(preselection.direction, Direction::Left | Direction::Up),
+                    ratio: preselection.ratio,
+                },
+            ),
+            None => root.split(
+                index.min(leaves - 1),
+                0,
+                Split {
+                    side_by_side: None,
+                    new_first: index < leaves,
+                    ratio: 0.5,
+                },
+            ),
+        }
+    }
+
+    fn remove(&mut self, index: usize, _count: usize) {
+        self.root = match self.root.take() {
+            Some(root) if index < root.leaves() => root.remove(index),
+            root => root,
+        };
+
+        self.preselection = match self.preselection {
+            Some(preselection) if preselection.index == index => None,
+            Some(mut preselection) if preselection.index > index => {
+                preselection.index -= 1;
+                Some(preselection)
+            }
+            preselection => preselection,
+        };
+    }
+
+    fn preselect(&mut self, preselection: Option<Preselection>) -> bool {
+        self.preselection = preselection;
+        true
+    }
+
+    fn preselection(&self) -> Option<Preselection> {
+        self.preselection
+    }
+
+    fn preselection_area(
+        &self,
+        params: &LayoutParams,
+        clients: &[LayoutClient],
+        area: Rect,
+    ) -> Option<Rect> {
+        let preselection = self.preselection?;
+        let target = *self
+            .arrange(params, clients, area)
+            .get(preselection.index)?;
+
+        let ratio = preselection.ratio;
+        let areas = match preselection.direction {
+            Direction::Left => target.split_columns(&[ratio, 1.0 - ratio]),
+            Direction::Up => target.split_rows(&[ratio, 1.0 - ratio]),
+            Direction::Right => target.split_columns(&[1.0 - ratio, ratio]),
+            Direction::Down => target.split_rows(&[1.0 - ratio, ratio]),
+        };
+
+        match preselection.direction {
+            Direction::Left | Direction::Up => areas.first().copied(),
+            Direction::Right | Direction::Down => areas.last().copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Bsp;
+    use crate::geometry::{Direction, Rect};
+    use crate::layouts::{Layout, LayoutClient, LayoutParams, Preselection};
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 800,
+        height: 400,
+    };
+
+    fn arrange(bsp: &Bsp, count: usize) -> Vec<Rect> {
+        bsp.arrange(
+            &LayoutParams::default(),
+            &vec![LayoutClient::default(); count],
+            AREA,
+        )
+    }
+
+    fn insert(bsp: &mut Bsp, count: usize) -> usize {
+        let index = bsp.insertion_index().unwrap_or(count);
+        bsp.insert(index, count + 1);
+        index
+    }
+
+    #[test]
+    fn dwindles_by_default() {
+        let mut bsp = Bsp::default();
+        for count in 0..3 {
+            insert(&mut bsp, count);
+        }
+
+        assert_eq!(
+            arrange(&bsp, 3),
+            vec![
+                Rect::new(0, 0, 400, 400),
+                Rect::new(400, 0, 400, 200),
+                Rect::new(400, 200, 400, 200),
+            ]
+        );
+    }
+
+    #[test]
+    fn inserts_into_preselected_area() {
+        let mut bsp = Bsp::default();
+        insert(&mut bsp, 0);
+        insert(&mut bsp, 1);
+
+        bsp.preselect(Some(Preselection {
+            index: 0,
+            direction: Direction::Down,
+            ratio: 0.25,
+        }));
+
+        let clients = [LayoutClient::default(); 2];
+        assert_eq!(
+            bsp.preselection_area(&LayoutParams::default(), &clients, AREA),
+            Some(Rect::new(0, 300, 400, 100))
+        );
+
+        assert_eq!(insert(&mut bsp, 2), 1);
+        assert_eq!(bsp.preselection(), None);
+        assert_eq!(
+            arrange(&bsp, 3),
+            vec![
+                Rect::new(0, 0, 400, 300),
+                Rect::new(0, 300, 400, 100),
+                Rect::new(400, 0, 400, 400),
+            ]
+        );
+    }
+
+    #[test]
+    fn removal_gives_space_to_sibling() {
+        let mut bsp = Bsp::default();
+        for count in 0..3 {
+            insert(&mut bsp, count);
+        }
+
+        bsp.remove(1, 2);
+        assert_eq!(
+            arrange(&bsp, 2),
+            vec![Rect::new(0, 0, 400, 400), Rect::new(400, 0, 400, 400)]
+        );
+    }
+
+    #[test]
+    fn resize_moves_nearest_matching_split() {
+        let mut bsp = Bsp::default();
+        for count in 0..3 {
+            insert(&mut bsp, count);
+        }
+
+        let mut clients = [LayoutClient::default(); 3];
+        let mut params = LayoutParams::default();
+
+        assert!(bsp.resize(&mut params, &mut clients, 2, Direction::Up, 0.125));
+        assert!(bsp.resize(&mut params, &mut clients, 2, Direction::Left, 0.125));
+        assert_eq!(arrange(&bsp, 3)[2], Rect::new(300, 150, 500, 250));
+
+        assert!(!bsp.resize(&mut params, &mut clients, 2, Direction::Right, 0.1));
+
+        bsp.balance();
+        assert_eq!(arrange(&bsp, 3)[2], Rect::new(400, 200, 400, 200));
+    }
+
+    #[test]
+    fn splits_survive_arranging_fewer_clients() {
+        let mut bsp = Bsp::default();
+        for count in 0..3 {
+            insert(&mut bsp, count);
+        }
+
+        let mut clients = [LayoutClient::default(); 2];
+        let mut params = LayoutParams::default();
+        assert!(bsp.resize(&mut params, &mut clients, 0, Direction::Right, 0.25));
+
+        let arranged = arrange(&bsp, 3);
+        assert_eq!(arranged[0], Rect::new(0, 0, 600, 400));
+        assert_eq!(arrange(&bsp, 2), arranged[..2]);
+    }
+}
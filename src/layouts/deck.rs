@@ -0,0 +1,160 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::{resize_master_factor, resize_row, Layout, LayoutClient, LayoutParams};
+
+/// Master area on the left, the remaining clients stacked on top of each other on the right.
+///
+/// Only one stack client is visible at a time, so the stack area behaves like monocle
+/// while the masters stay tiled next to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Deck {
+    /// Index of the stack client shown on top, kept while focus is on the master area.
+    top: usize,
+}
+
+impl Layout for Deck {
+    fn name(&self) -> &'static str {
+        "deck"
+    }
+
+    fn arrange(&self, params: &LayoutParams, clients: &[LayoutClient], area: Rect) -> Vec<Rect> {
+        let count = clients.len();
+        let masters = params.master_count.min(count);
+
+        let weights: Vec<f32> = clients[..masters].iter().map(|c| c.weight).collect();
+
+        if masters == 0 || masters == count {
+            return if masters == 0 {
+                vec![area; count]
+            } else {
+                area.split_rows(&weights)
+            };
+        }
+
+        let columns = area.split_columns(&[params.master_factor, 1.0 - params.master_factor]);
+
+        let mut geometries = columns[0].split_rows(&weights);
+        geometries.resize(count, columns[1]);
+        geometries
+    }
+
+    fn raised(&self, params: &LayoutParams, clients: &[LayoutClient]) -> Option<usize> {
+        let first = params.master_count;
+        if clients.len() <= first {
+            return None;
+        }
+
+        match clients.iter().position(|client| client.focused) {
+            Some(index) if index >= first => Some(index),
+            _ => Some(self.top.clamp(first, clients.len() - 1)),
+        }
+    }
+
+    fn resize(
+        &mut self,
+        params: &mut LayoutParams,
+        clients: &mut [LayoutClient],
+        index: usize,
+        direction: Direction,
+        delta: f32,
+    ) -> bool {
+        let masters = params.master_count.min(clients.len());
+
+        if !direction.is_horizontal() {
+            let members: Vec<usize> = (0..masters).collect();
+            return resize_row(clients, &members, index, direction, delta);
+        }
+
+        if masters == 0 || masters == clients.len() {
+            return false;
+        }
+
+        let delta = match (index < masters, direction) {
+            (true, Direction::Right) => delta,
+            (false, Direction::Left) => -delta,
+            _ => return false,
+        };
+
+        params.master_factor = resize_master_factor(params.master_factor, delta);
+        true
+    }
+
+    fn focus(&mut self, params: &LayoutParams, index: usize) {
+        if index >= params.master_count {
+            self.top = index;
+        }
+    }
+
+    fn enter(&mut self) {
+        self.top = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Deck;
+    use crate::geometry::{Direction, Rect};
+    use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+    #[test]
+    fn stack_clients_share_one_area() {
+        let params = LayoutParams {
+            master_factor: 0.6,
+            master_count: 1,
+            ..Default::default()
+        };
+        let area = Rect::new(0, 0, 1000, 500);
+        assert_eq!(
+            Deck::default().arrange(&params, &[LayoutClient::default(); 3], area),
+            vec![
+                Rect::new(0, 0, 600, 500),
+                Rect::new(600, 0, 400, 500),
+                Rect::new(600, 0, 400, 500),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_client_takes_whole_area() {
+        let area = Rect::new(0, 0, 1000, 500);
+        assert_eq!(
+            Deck::default().arrange(&LayoutParams::default(), &[LayoutClient::default()], area),
+            vec![area]
+        );
+    }
+
+    #[test]
+    fn remembers_top_stack_client_when_master_is_focused() {
+        let params = LayoutParams::default();
+        let mut deck = Deck::default();
+        let mut clients = [LayoutClient::default(); 3];
+
+        assert_eq!(deck.raised(&params, &clients), Some(1));
+
+        deck.focus(&params, 2);
+        clients[2].focused = true;
+        assert_eq!(deck.raised(&params, &clients), Some(2));
+
+        deck.focus(&params, 0);
+        clients[2].focused = false;
+        clients[0].focused = true;
+        assert_eq!(deck.raised(&params, &clients), Some(2));
+    }
+
+    #[test]
+    fn resizing_moves_the_master_edge() {
+        let mut deck = Deck::default();
+        let mut params = LayoutParams {
+            master_factor: 0.5,
+            ..Default::default()
+        };
+        let mut clients = [LayoutClient::default(); 2];
+
+        assert!(deck.resize(&mut params, &mut clients, 0, Direction::Right, 0.25));
+        assert_eq!(params.master_factor, 0.75);
+
+        assert!(deck.resize(&mut params, &mut clients, 1, Direction::Left, 0.5));
+        assert_eq!(params.master_factor, 0.25);
+
+        assert!(!deck.resize(&mut params, &mut clients, 0, Direction::Left, 0.1));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::geometry::Rect;
+use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+/// Empty space around tiled clients, applied on top of any layout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Gaps {
+    /// Space between neighbouring clients.
+    pub inner: u32,
+
+    /// Space between clients and the edge of the area.
+    pub outer: u32,
+
+    /// Disables the gaps when there is only one client.
+    pub smart: bool,
+}
+
+impl Gaps {
+    /// Gaps multiplied by the scale of a monitor.
+    pub fn scaled(&self, scale: f32) -> Self {
+        let scale = |gap: u32| (gap as f32 * scale).round() as u32;
+
+        Self {
+            inner: scale(self.inner),
+            outer: scale(self.outer),
+            smart: self.smart,
+        }
+    }
+
+    /// Area left for `count` clients once the outer gap is taken away.
+    pub fn area(&self, count: usize, area: Rect) -> Rect {
+        if self.smart && count == 1 {
+            area
+        } else {
+            area.inset(self.outer, self.outer, self.outer, self.outer)
+        }
+    }
+
+    /// Runs `layout` (with its transform) on the area reduced by the outer gap and separates the
+    /// resulting geometries by the inner gap.
+    pub fn arrange(
+        &self,
+        layout: &dyn Layout,
+        params: &LayoutParams,
+        clients: &[LayoutClient],
+        area: Rect,
+    ) -> Vec<Rect> {
+        if self.smart && clients.len() == 1 {
+            return params.transform.arrange(layout, params, clients, area);
+        }
+
+        let area = self.area(clients.len(), area);
+        let before = self.inner - self.inner / 2;
+        let after = self.inner / 2;
+
+        params
+            .transform
+            .arrange(layout, params, clients, area)
+            .into_iter()
+            .map(|rect| {
+                rect.inset(
+                    if rect.x > area.x { before } else { 0 },
+                    if rect.y > area.y { before } else { 0 },
+                    if rect.right() < area.right() {
+                        after
+                    } else {
+                        0
+                    },
+                    if rect.bottom() < area.bottom() {
+                        after
+                    } else {
+                        0
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Gaps;
+    use crate::geometry::Rect;
+    use crate::layouts::{Deck, LayoutClient, LayoutParams};
+
+    const GAPS: Gaps = Gaps {
+        inner: 10,
+        outer: 20,
+        smart: true,
+    };
+
+    #[test]
+    fn separates_clients_by_inner_gap() {
+        let params = LayoutParams {
+            master_factor: 0.5,
+            master_count: 1,
+            ..Default::default()
+        };
+        let area = Rect::new(0, 0, 1040, 540);
+
+        assert_eq!(
+            GAPS.arrange(
+                &Deck::default(),
+                &params,
+                &[LayoutClient::default(); 2],
+                area
+            ),
+            vec![Rect::new(20, 20, 495, 500), Rect::new(525, 20, 495, 500)]
+        );
+    }
+
+    #[test]
+    fn smart_gaps_skip_single_client() {
+        let area = Rect::new(0, 0, 1000, 500);
+        let clients = [LayoutClient::default()];
+
+        assert_eq!(
+            GAPS.arrange(&Deck::default(), &LayoutParams::default(), &clients, area),
+            vec![area]
+        );
+
+        let gaps = Gaps {
+            smart: false,
+            ..GAPS
+        };
+        assert_eq!(
+            gaps.arrange(&Deck::default(), &LayoutParams::default(), &clients, area),
+            vec![Rect::new(20, 20, 960, 460)]
+        );
+    }
+}
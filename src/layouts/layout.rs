@@ -0,0 +1,181 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::Transform;
+
+/// Per-client information a layout may use when computing geometries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutClient {
+    pub focused: bool,
+
+    /// Relative share of the space the client gets next to its siblings
+    /// in the same column or row.
+    pub weight: f32,
+}
+
+impl Default for LayoutClient {
+    fn default() -> Self {
+        Self {
+            focused: false,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Parameters shared by all layouts of a workspace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutParams {
+    /// Fraction of the area taken by the master area.
+    pub master_factor: f32,
+
+    /// Number of clients in the master area.
+    pub master_count: usize,
+
+    /// Orientation of the layout, applied by the caller.
+    pub transform: Transform,
+}
+
+impl Default for LayoutParams {
+    fn default() -> Self {
+        Self {
+            master_factor: 0.55,
+            master_count: 1,
+            transform: Transform::default(),
+        }
+    }
+}
+
+/// Area of an existing client reserved for the next inserted client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Preselection {
+    /// Client whose area is split.
+    pub index: usize,
+    /// Side of the client the new one goes to.
+    pub direction: Direction,
+    /// Share of the split area given to the new client.
+    pub ratio: f32,
+}
+
+/// Tiling algorithm.
+///
+/// Layouts only compute geometries; mapping them onto windows is done by the caller,
+/// so a layout can be written and tested without an X connection.
+pub trait Layout: std::fmt::Debug {
+    fn name(&self) -> &'static str;
+
+    /// Returns one geometry for each of `clients`, in the same order.
+    fn arrange(&self, params: &LayoutParams, clients: &[LayoutClient], area: Rect) -> Vec<Rect>;
+
+    /// Index of a client that should be raised above overlapping ones, if any.
+    fn raised(&self, _params: &LayoutParams, _clients: &[LayoutClient]) -> Option<usize> {
+        None
+    }
+
+    /// Moves the edge of the client at `index` facing `direction` by `delta`,
+    /// expressed as a fraction of the area along that axis. Positive values grow
+    /// the client. Layouts may change the client weights to do so.
+    /// Returns false when the layout has no such movable edge.
+    fn resize(
+        &mut self,
+        _params: &mut LayoutParams,
+        _clients: &mut [LayoutClient],
+        _index: usize,
+        _direction: Direction,
+        _delta: f32,
+    ) -> bool {
+        false
+    }
+
+    /// Position a new client should be inserted at, overriding the workspace policy.
+    fn insertion_index(&self) -> Option<usize> {
+        None
+    }
+
+    /// Called after a client was inserted at `index`, `count` being the new number of clients.
+    fn insert(&mut self, _index: usize, _count: usize) {}
+
+    /// Called after the client at `index` was removed, `count` being the new number of clients.
+    fn remove(&mut self, _index: usize, _count: usize) {}
+
+    /// Sets or clears the preselection, returns false if the layout does not support it.
+    fn preselect(&mut self, _preselection: Option<Preselection>) -> bool {
+        false
+    }
+
+    fn preselection(&self) -> Option<Preselection> {
+        None
+    }
+
+    /// Area the preselected client will take, in layout space.
+    fn preselection_area(
+        &self,
+        _params: &LayoutParams,
+        _clients: &[LayoutClient],
+        _area: Rect,
+    ) -> Option<Rect> {
+        None
+    }
+
+    /// Undoes manual resizes of the layout's own split ratios.
+    fn balance(&mut self) {}
+
+    /// Called when the client at `index` gains focus.
+    fn focus(&mut self, _params: &LayoutParams, _index: usize) {}
+
+    /// Called when the layout becomes active.
+    fn enter(&mut self) {}
+
+    /// Called when the layout stops being active.
+    fn leave(&mut self) {}
+}
+
+pub type BoxLayout = Box<dyn Layout>;
+
+/// Smallest share of the area a resized split can leave to either side.
+const MIN_SHARE: f32 = 0.05;
+
+/// Moves the boundary between `weights[boundary]` and `weights[boundary + 1]` by
+/// `delta` (a fraction of the summed weights), growing the first one for positive values.
+pub fn move_boundary(weights: &mut [f32], boundary: usize, delta: f32) {
+    let total: f32 = weights.iter().sum();
+    let min = MIN_SHARE * total;
+
+    let change = (delta * total)
+        .min(weights[boundary + 1] - min)
+        .max(min - weights[boundary]);
+
+    weights[boundary] += change;
+    weights[boundary + 1] -= change;
+}
+
+/// Moves the horizontal edge of `index` within a column made of the `column` clients,
+/// by changing the weights of the client and its neighbour.
+pub fn resize_row(
+    clients: &mut [LayoutClient],
+    column: &[usize],
+    index: usize,
+    direction: Direction,
+    delta: f32,
+) -> bool {
+    let position = match column.iter().position(|i| *i == index) {
+        Some(position) => position,
+        None => return false,
+    };
+
+    let (boundary, delta) = match direction {
+        Direction::Up if position > 0 => (position - 1, -delta),
+        Direction::Down if position + 1 < column.len() => (position, delta),
+        _ => return false,
+    };
+
+    let mut weights: Vec<f32> = column.iter().map(|i| clients[*i].weight).collect();
+    move_boundary(&mut weights, boundary, delta);
+
+    for (i, weight) in column.iter().zip(weights) {
+        clients[*i].weight = weight;
+    }
+    true
+}
+
+/// Master factor after growing the master area by `delta`.
+pub fn resize_master_factor(master_factor: f32, delta: f32) -> f32 {
+    (master_factor + delta).clamp(MIN_SHARE, 1.0 - MIN_SHARE)
+}
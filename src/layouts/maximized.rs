@@ -0,0 +1,40 @@
+use crate::geometry::Rect;
+use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+/// Every client takes the whole work area, the focused one is shown on top.
+///
+/// Unlike fullscreen, the area excludes docks and gaps so they stay visible.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Maximized;
+
+impl Layout for Maximized {
+    fn name(&self) -> &'static str {
+        "maximized"
+    }
+
+    fn arrange(&self, _params: &LayoutParams, clients: &[LayoutClient], area: Rect) -> Vec<Rect> {
+        vec![area; clients.len()]
+    }
+
+    fn raised(&self, _params: &LayoutParams, clients: &[LayoutClient]) -> Option<usize> {
+        clients.iter().position(|client| client.focused)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Maximized;
+    use crate::geometry::Rect;
+    use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+    #[test]
+    fn focused_client_covers_area() {
+        let area = Rect::new(0, 20, 800, 580);
+        let mut clients = [LayoutClient::default(); 3];
+        clients[1].focused = true;
+
+        let params = LayoutParams::default();
+        assert_eq!(Maximized.arrange(&params, &clients, area), vec![area; 3]);
+        assert_eq!(Maximized.raised(&params, &clients), Some(1));
+    }
+}
@@ -0,0 +1,23 @@
+mod layout;
+pub use layout::{
+    move_boundary, resize_master_factor, resize_row, BoxLayout, Layout, LayoutClient, LayoutParams,
+    Preselection,
+};
+
+mod bsp;
+pub use bsp::Bsp;
+
+mod deck;
+pub use deck::Deck;
+
+mod gaps;
+pub use gaps::Gaps;
+
+mod maximized;
+pub use maximized::Maximized;
+
+mod three_column;
+pub use three_column::ThreeColumn;
+
+mod transform;
+pub use transform::Transform;
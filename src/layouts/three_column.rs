@@ -0,0 +1,261 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::{move_boundary, resize_row, Layout, LayoutClient, LayoutParams};
+
+/// Column occupied by a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Left,
+    Middle,
+    Right,
+}
+
+/// Stack | master | stack layout for wide displays.
+///
+/// The master clients share the middle column, the remaining clients are dealt
+/// alternately into the right and left columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThreeColumn {
+    /// Relative widths of the left, middle and right columns.
+    ratios: [f32; 3],
+    /// Ratios restored when the layout is balanced.
+    initial_ratios: [f32; 3],
+}
+
+impl Default for ThreeColumn {
+    fn default() -> Self {
+        Self::new([1.0, 2.0, 1.0])
+    }
+}
+
+impl ThreeColumn {
+    pub fn new(ratios: [f32; 3]) -> Self {
+        Self {
+            ratios,
+            initial_ratios: ratios,
+        }
+    }
+
+    /// The middle column is never left empty.
+    fn masters(params: &LayoutParams, count: usize) -> usize {
+        params.master_count.clamp(1, count.max(1))
+    }
+
+    fn column(masters: usize, index: usize) -> Column {
+        if index < masters {
+            Column::Middle
+        } else if (index - masters).is_multiple_of(2) {
+            Column::Right
+        } else {
+            Column::Left
+        }
+    }
+
+    /// Indices of the clients placed in `column`, top to bottom.
+    fn members(masters: usize, count: usize, column: Column) -> Vec<usize> {
+        (0..count)
+            .filter(|index| Self::column(masters, *index) == column)
+            .collect()
+    }
+}
+
+impl Layout for ThreeColumn {
+    fn name(&self) -> &'static str {
+        "three-column"
+    }
+
+    fn arrange(&self, params: &LayoutParams, clients: &[LayoutClient], area: Rect) -> Vec<Rect> {
+        let [left, middle, right] = self.ratios;
+        let count = clients.len();
+
+        let masters = Self::masters(params, count);
+        let stack = count.saturating_sub(masters);
+
+        let areas = match stack {
+            0 => vec![area],
+            1 => area.split_columns(&[middle, left + right]),
+            _ => area.split_columns(&[left, middle, right]),
+        };
+
+        // With a single stack client there is no left column.
+        let column_area = |column: Column| match (column, areas.len()) {
+            (Column::Middle, 3) => areas[1],
+            (Column::Middle, _) => areas[0],
+            (Column::Right, 2) => areas[1],
+            (Column::Right, _) => areas[2],
+            (Column::Left, _) => areas[0],
+        };
+
+        let mut geometries = vec![Rect::default(); count];
+        for column in [Column::Left, Column::Middle, Column::Right] {
+            let members = Self::members(masters, count, column);
+            if members.is_empty() {
+                continue;
+            }
+
+            let weights: Vec<f32> = members.iter().map(|i| clients[*i].weight).collect();
+            let rows = column_area(column).split_rows(&weights);
+
+            for (index, row) in members.into_iter().zip(rows) {
+                geometries[index] = row;
+            }
+        }
+
+        geometries
+    }
+
+    fn resize(
+        &mut self,
+        params: &mut LayoutParams,
+        clients: &mut [LayoutClient],
+        index: usize,
+        direction: Direction,
+        delta: f32,
+    ) -> bool {
+        let masters = Self::masters(params, clients.len());
+        let stack = clients.len().saturating_sub(masters);
+        let column = Self::column(masters, index);
+
+        if !direction.is_horizontal() {
+            let members = Self::members(masters, clients.len(), column);
+            return resize_row(clients, &members, index, direction, delta);
+        }
+
+        match stack {
+            0 => false,
+            1 => {
+                // Both side columns share the stack column, keep their proportion.
+                let [left, middle, right] = self.ratios;
+                let mut weights = [middle, left + right];
+
+                match (column, direction) {
+                    (Column::Middle, Direction::Right) => move_boundary(&mut weights, 0, delta),
+                    (Column::Right, Direction::Left) => move_boundary(&mut weights, 0, -delta),
+                    _ => return false,
+                }
+
+                let scale = weights[1] / (left + right);
+                self.ratios = [left * scale, weights[0], right * scale];
+                true
+            }
+            _ => {
+                let (boundary, delta) = match (column, direction) {
+                    (Column::Left, Direction::Right) => (0, delta),
+                    (Column::Middle, Direction::Left) => (0, -delta),
+                    (Column::Middle, Direction::Right) => (1, delta),
+                    (Column::Right, Direction::Left) => (1, -delta),
+                    _ => return false,
+                };
+
+                move_boundary(&mut self.ratios, boundary, delta);
+                true
+            }
+        }
+    }
+
+    fn balance(&mut self) {
+        self.ratios = self.initial_ratios;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThreeColumn;
+    use crate::geometry::{Direction, Rect};
+    use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+    fn arrange(layout: ThreeColumn, master_count: usize, count: usize, area: Rect) -> Vec<Rect> {
+        let params = LayoutParams {
+            master_count,
+            ..Default::default()
+        };
+        layout.arrange(&params, &vec![LayoutClient::default(); count], area)
+    }
+
+    #[test]
+    fn single_client_takes_whole_area() {
+        let area = Rect::new(0, 0, 1000, 500);
+        assert_eq!(arrange(ThreeColumn::default(), 1, 1, area), vec![area]);
+    }
+
+    #[test]
+    fn two_clients_split_master_and_stack() {
+        let area = Rect::new(0, 0, 1000, 500);
+        assert_eq!(
+            arrange(ThreeColumn::default(), 1, 2, area),
+            vec![Rect::new(0, 0, 500, 500), Rect::new(500, 0, 500, 500)]
+        );
+    }
+
+    #[test]
+    fn stack_alternates_between_side_columns() {
+        let area = Rect::new(0, 0, 1000, 600);
+        assert_eq!(
+            arrange(ThreeColumn::default(), 1, 4, area),
+            vec![
+                Rect::new(250, 0, 500, 600),
+                Rect::new(750, 0, 250, 300),
+                Rect::new(0, 0, 250, 600),
+                Rect::new(750, 300, 250, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn masters_share_middle_column() {
+        let area = Rect::new(0, 0, 1000, 600);
+        let geometries = arrange(ThreeColumn::default(), 2, 4, area);
+        assert_eq!(geometries[0], Rect::new(250, 0, 500, 300));
+        assert_eq!(geometries[1], Rect::new(250, 300, 500, 300));
+    }
+
+    #[test]
+    fn custom_ratios() {
+        let area = Rect::new(0, 0, 1200, 100);
+        let geometries = arrange(ThreeColumn::new([1.0, 1.0, 1.0]), 1, 3, area);
+        assert_eq!(geometries[0], Rect::new(400, 0, 400, 100));
+    }
+
+    #[test]
+    fn resizing_master_moves_shared_edge() {
+        let mut layout = ThreeColumn::new([1.0, 2.0, 1.0]);
+        let mut params = LayoutParams::default();
+        let mut clients = [LayoutClient::default(); 3];
+
+        assert!(layout.resize(&mut params, &mut clients, 0, Direction::Right, 0.125));
+        assert_eq!(layout.ratios, [1.0, 2.5, 0.5]);
+
+        // Growing the left column to the right shrinks the master.
+        assert!(layout.resize(&mut params, &mut clients, 2, Direction::Right, 0.125));
+        assert_eq!(layout.ratios, [1.5, 2.0, 0.5]);
+
+        assert!(!layout.resize(&mut params, &mut clients, 1, Direction::Right, 0.1));
+
+        layout.balance();
+        assert_eq!(layout.ratios, [1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn weights_distribute_column_height() {
+        let area = Rect::new(0, 0, 1000, 600);
+        let mut clients = [LayoutClient::default(); 5];
+        clients[1].weight = 2.0;
+
+        let geometries = ThreeColumn::default().arrange(&LayoutParams::default(), &clients, area);
+        assert_eq!(geometries[1], Rect::new(750, 0, 250, 400));
+        assert_eq!(geometries[3], Rect::new(750, 400, 250, 200));
+    }
+
+    #[test]
+    fn resizing_rows_changes_weights() {
+        let mut layout = ThreeColumn::default();
+        let mut params = LayoutParams::default();
+        let mut clients = [LayoutClient::default(); 5];
+
+        // Clients 1 and 3 share the right column.
+        assert!(layout.resize(&mut params, &mut clients, 1, Direction::Down, 0.25));
+        assert_eq!(clients[1].weight, 1.5);
+        assert_eq!(clients[3].weight, 0.5);
+
+        assert!(!layout.resize(&mut params, &mut clients, 1, Direction::Up, 0.25));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::{Layout, LayoutClient, LayoutParams};
+
+/// Orientation change applied to the output of any layout.
+///
+/// The layout runs on a transposed area first (if requested), then the result
+/// is mirrored horizontally and flipped vertically.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Transform {
+    pub transpose: bool,
+    pub mirror: bool,
+    pub flip: bool,
+}
+
+impl Transform {
+    /// Rotates the current orientation by 90 degrees clockwise.
+    pub fn rotate(&mut self) {
+        *self = Self {
+            transpose: !self.transpose,
+            mirror: !self.flip,
+            flip: self.mirror,
+        };
+    }
+
+    /// Maps a direction on screen to the matching direction in layout space.
+    pub fn layout_direction(&self, direction: Direction) -> Direction {
+        let mut direction = direction;
+
+        if (self.mirror && direction.is_horizontal()) || (self.flip && !direction.is_horizontal()) {
+            direction = direction.opposite();
+        }
+
+        if self.transpose {
+            direction = match direction {
+                Direction::Left => Direction::Up,
+                Direction::Right => Direction::Down,
+                Direction::Up => Direction::Left,
+                Direction::Down => Direction::Right,
+            };
+        }
+
+        direction
+    }
+
+    /// Area a layout should arrange clients in, for the transformed result to cover `area`.
+    pub fn layout_area(&self, area: Rect) -> Rect {
+        if self.transpose {
+            transpose(area, area)
+        } else {
+            area
+        }
+    }
+
+    /// Maps a rect computed by a layout in [`Transform::layout_area`] onto `area`.
+    pub fn apply(&self, rect: Rect, area: Rect) -> Rect {
+        let mut rect = if self.transpose {
+            transpose(rect, area)
+        } else {
+            rect
+        };
+
+        if self.mirror {
+            rect.x = area.x + (area.right() - rect.right());
+        }
+
+        if self.flip {
+            rect.y = area.y + (area.bottom() - rect.bottom());
+        }
+
+        rect
+    }
+
+    pub fn arrange(
+        &self,
+        layout: &dyn Layout,
+        params: &LayoutParams,
+        clients: &[LayoutClient],
+        area: Rect,
+    ) -> Vec<Rect> {
+        layout
+            .arrange(params, clients, self.layout_area(area))
+            .into_iter()
+            .map(|rect| self.apply(rect, area))
+            .collect()
+    }
+}
+
+/// Swaps the axes of `rect` relative to the origin of `area`.
+fn transpose(rect: Rect, area: Rect) -> Rect {
+    Rect::new(
+        area.x + (rect.y - area.y),
+        area.y + (rect.x - area.x),
+        rect.height,
+        rect.width,
+    )
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = [
+            (self.transpose, "transpose"),
+            (self.mirror, "mirror"),
+            (self.flip, "flip"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect();
+
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(","))
+        }
+    }
+}
+
+impl std::str::FromStr for Transform {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut transform = Transform::default();
+
+        for name in s.split(',').map(str::trim) {
+            match name {
+                "none" => {}
+                "transpose" => transform.transpose = true,
+                "mirror" => transform.mirror = true,
+                "flip" => transform.flip = true,
+                _ => anyhow::bail!("Unknown layout transform: {}", name),
+            }
+        }
+
+        Ok(transform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transform;
+    use crate::geometry::{Direction, Rect};
+    use crate::layouts::{Deck, LayoutClient, LayoutParams};
+
+    fn master(transform: Transform) -> Rect {
+        let params = LayoutParams {
+            master_factor: 0.5,
+            master_count: 1,
+            ..Default::default()
+        };
+        let area = Rect::new(100, 0, 400, 200);
+
+        transform.arrange(
+            &Deck::default(),
+            &params,
+            &[LayoutClient::default(); 2],
+            area,
+        )[0]
+    }
+
+    #[test]
+    fn rotation_moves_master_clockwise() {
+        let mut transform = Transform::default();
+        assert_eq!(master(transform), Rect::new(100, 0, 200, 200));
+
+        transform.rotate();
+        assert_eq!(master(transform), Rect::new(100, 0, 400, 100));
+
+        transform.rotate();
+        assert_eq!(master(transform), Rect::new(300, 0, 200, 200));
+
+        transform.rotate();
+        assert_eq!(master(transform), Rect::new(100, 100, 400, 100));
+
+        transform.rotate();
+        assert_eq!(transform, Transform::default());
+    }
+
+    #[test]
+    fn mirror_puts_master_on_the_right() {
+        let transform = Transform {
+            mirror: true,
+            ..Default::default()
+        };
+        assert_eq!(master(transform), Rect::new(300, 0, 200, 200));
+    }
+
+    #[test]
+    fn maps_screen_directions_to_layout_space() {
+        let mut transform = Transform::default();
+        transform.rotate();
+
+        // Master on top: growing it down moves the edge that is on its right in layout space.
+        assert_eq!(
+            transform.layout_direction(Direction::Down),
+            Direction::Right
+        );
+        assert_eq!(transform.layout_direction(Direction::Left), Direction::Down);
+    }
+
+    #[test]
+    fn parses_its_own_output() {
+        let transform = Transform {
+            transpose: true,
+            mirror: false,
+            flip: true,
+        };
+        assert_eq!(
+            transform.to_string().parse::<Transform>().unwrap(),
+            transform
+        );
+        assert_eq!(
+            Transform::default()
+                .to_string()
+                .parse::<Transform>()
+                .unwrap(),
+            Transform::default()
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use log::{debug, error, info, trace, warn};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use x::EventMask;
 use xcb::{
     x::{self, KeyButMask},
@@ -8,11 +11,38 @@ use xcb::{
 };
 use xkbcommon::xkb;
 
+mod activation;
+use activation::ActivationPolicy;
+
+mod atoms;
+use atoms::Atoms;
+
+mod bar;
+use bar::Bars;
+
+mod client;
+use client::Client;
+
+mod history;
+use history::History;
+
+mod icon;
+
 mod keyboard;
 use keyboard::Keyboard;
 
 mod commands;
-use commands::RofiCommand;
+use commands::{
+    BalanceLayoutCommand, CloseCommand, CreateWorkspaceCommand, CycleFocusCommand,
+    FocusMonitorCommand, JumpToUrgentCommand, KillUnresponsiveCommand, LayoutTransformation,
+    MasterCountCommand, MasterFactorCommand, MinimizeCommand, MoveToWorkspaceCommand,
+    MoveWorkspaceToMonitorCommand, NextLayoutCommand, OpacityCommand, PreselectCommand,
+    RenameWorkspaceCommand, ReorderWorkspaceCommand, ResizeTiledCommand, RestoreLayoutCommand,
+    RestoreMinimizedCommand, RofiCommand, SaveLayoutCommand, SelectMinimizedCommand,
+    SetBackgroundCommand, SwitchGroupCommand, ToggleAboveCommand, ToggleFloatingCommand,
+    ToggleFullscreenCommand, ToggleStickyCommand, ToggleTagCommand, ToggleViewCommand,
+    TransformLayoutCommand, ViewWorkspaceCommand, WorkspaceHistoryCommand,
+};
 
 mod config;
 use config::Config;
@@ -20,19 +50,174 @@ use config::Config;
 mod keybind;
 use keybind::{KeySequence, Keybind};
 
-struct Client {
-    _window: x::Window,
+mod geometry;
+use geometry::{Direction, Rect};
+
+mod layouts;
+use layouts::{
+    BoxLayout, Bsp, Deck, Gaps, Layout, LayoutClient, LayoutParams, Maximized, ThreeColumn,
+};
+
+mod monitor;
+use monitor::{Monitor, MonitorTarget};
+
+mod mouse;
+use mouse::Drag;
+
+mod motif;
+
+mod opacity;
+
+mod osd;
+use osd::Osd;
+
+mod ping;
+use ping::Pinger;
+
+mod process;
+
+mod prompt;
+use prompt::Prompt;
+
+mod properties;
+
+mod randr;
+
+mod rules;
+
+mod selection;
+
+mod session;
+use session::{Placements, Session, SessionState};
+
+mod size_hints;
+
+mod snapshot;
+use snapshot::{LayoutSnapshot, WindowIdentity};
+
+mod stacking;
+use stacking::Layer;
+
+mod startup;
+use startup::Launches;
+
+mod struts;
+use struts::Struts;
+
+mod wallpaper;
+use wallpaper::Background;
+
+mod workspace;
+use workspace::{GroupTarget, Workspace, WorkspaceModel, WorkspaceTarget};
+
+mod xinerama;
+
+/// _NET_WM_DESKTOP of clients shown on all desktops.
+const ALL_DESKTOPS: u32 = 0xFFFFFFFF;
+
+/// Time given to a process sent SIGTERM to close its windows before it is sent SIGKILL.
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Focus walk in progress, the keyboard is grabbed until the modifiers are released.
+struct FocusCycle {
+    /// Focus order of the workspace when the walk started.
+    order: Vec<x::Window>,
+    position: usize,
 }
 
-struct App {
+pub struct App {
     conn: xcb::Connection,
     root: x::Window,
+    atoms: Atoms,
 
     config: Config,
 
     keyboard: Keyboard,
 
     clients: HashMap<u32, Client>,
+
+    workspaces: Vec<Workspace>,
+
+    monitors: Vec<Monitor>,
+    current_monitor: usize,
+
+    /// Index of the active workspace group.
+    group: usize,
+
+    focused: Option<x::Window>,
+
+    drag: Option<Drag>,
+
+    focus_cycle: Option<FocusCycle>,
+
+    /// Clients in the order they were mapped, published in _NET_CLIENT_LIST.
+    client_list: Vec<x::Window>,
+
+    /// Clients from the least to the most recently raised, see [`App::restack`].
+    stack: Vec<x::Window>,
+    /// Stacking order last applied to the windows, see [`App::restack`].
+    stacked: Vec<x::Window>,
+
+    /// Clients hidden until restored, the most recently minimized last.
+    minimized: Vec<x::Window>,
+
+    /// All clients are hidden to show the desktop (_NET_SHOWING_DESKTOP).
+    showing_desktop: bool,
+
+    /// Pending unmaps requested by the window manager itself, which are not withdrawals.
+    ignored_unmaps: HashMap<u32, usize>,
+
+    /// Last known position of the pointer on the root window.
+    pointer: (i16, i16),
+
+    /// Pings clients to find those that stopped responding, unless disabled.
+    pinger: Option<Pinger<x::Window>>,
+
+    /// Applications launched with a startup id, to send their windows to the workspace
+    /// they were launched from.
+    launches: Launches,
+
+    /// Clients whose process was sent SIGTERM, with the process id and when to kill it.
+    terminating: Vec<(x::Window, u32, Instant)>,
+
+    /// Server time of the latest user input, seen by the window manager or reported by the
+    /// focused client (_NET_WM_USER_TIME).
+    user_time: Option<u32>,
+
+    /// Server time of the latest event carrying one, sent along with WM_TAKE_FOCUS.
+    event_time: u32,
+
+    /// Shows the area reserved by a preselection.
+    preselection_window: x::Window,
+
+    /// Identifies the window manager to EWMH clients, see [`create_check_window`].
+    check_window: x::Window,
+
+    /// ICCCM manager selection (WM_Sn) owned by the check window, lost when replaced.
+    wm_selection: x::Atom,
+
+    /// Cleared to leave the event loop.
+    running: bool,
+
+    /// Connection to the X session manager, if there is one.
+    session: Option<Session>,
+
+    /// Workspaces of the clients saved with the previous session.
+    placements: Option<Placements>,
+
+    /// On-screen messages, unless the OSD could not be created (e.g. a missing font).
+    osd: Option<Osd>,
+
+    /// Prompt command waiting for the user, one at a time.
+    prompt: Option<Prompt>,
+
+    /// Bars started for each monitor.
+    bars: Bars,
+
+    screen_area: Rect,
+
+    /// Names of the connected outputs, profiles are applied to those connected later.
+    connected_outputs: Vec<String>,
 }
 
 fn register_for_xcb_events(conn: &xcb::Connection, root: x::Window) -> xcb::ProtocolResult<()> {
@@ -108,18 +293,505 @@ fn load_config() -> anyhow::Result<Config> {
 
     config.add_keybind(Keybind::new(
         KeySequence::try_from("M-d").unwrap(),
-        Box::new(RofiCommand),
+        Rc::new(RofiCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-space").unwrap(),
+        Rc::new(NextLayoutCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-h").unwrap(),
+        Rc::new(MasterFactorCommand::new(-0.05)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-l").unwrap(),
+        Rc::new(MasterFactorCommand::new(0.05)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-i").unwrap(),
+        Rc::new(MasterCountCommand::new(1)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-i").unwrap(),
+        Rc::new(MasterCountCommand::new(-1)),
+    ));
+
+    for (key, direction) in [
+        ("h", Direction::Left),
+        ("j", Direction::Down),
+        ("k", Direction::Up),
+        ("l", Direction::Right),
+    ] {
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-C-{}", key).as_str()).unwrap(),
+            Rc::new(ResizeTiledCommand::new(direction, 0.025)),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-C-S-{}", key).as_str()).unwrap(),
+            Rc::new(ResizeTiledCommand::new(direction, -0.025)),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-S-{}", key).as_str()).unwrap(),
+            Rc::new(PreselectCommand::new(direction, 0.5)),
+        ));
+    }
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-e").unwrap(),
+        Rc::new(BalanceLayoutCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-r").unwrap(),
+        Rc::new(TransformLayoutCommand::new(LayoutTransformation::Rotate)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-m").unwrap(),
+        Rc::new(TransformLayoutCommand::new(LayoutTransformation::Mirror)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-m").unwrap(),
+        Rc::new(TransformLayoutCommand::new(LayoutTransformation::Flip)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-s").unwrap(),
+        Rc::new(SaveLayoutCommand::new(None)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-r").unwrap(),
+        Rc::new(RestoreLayoutCommand::new(None)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-n").unwrap(),
+        Rc::new(CreateWorkspaceCommand::new(None)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-n").unwrap(),
+        Rc::new(RenameWorkspaceCommand::new(None)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-b").unwrap(),
+        Rc::new(SetBackgroundCommand::new(None)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-bracketleft").unwrap(),
+        Rc::new(WorkspaceHistoryCommand::back()),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-bracketright").unwrap(),
+        Rc::new(WorkspaceHistoryCommand::forward()),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-g").unwrap(),
+        Rc::new(SwitchGroupCommand::new(GroupTarget::Relative(1))),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-g").unwrap(),
+        Rc::new(SwitchGroupCommand::new(GroupTarget::Relative(-1))),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-space").unwrap(),
+        Rc::new(ToggleFloatingCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-f").unwrap(),
+        Rc::new(ToggleFullscreenCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-a").unwrap(),
+        Rc::new(ToggleAboveCommand),
     ));
 
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-t").unwrap(),
+        Rc::new(ToggleStickyCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-minus").unwrap(),
+        Rc::new(MinimizeCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-q").unwrap(),
+        Rc::new(CloseCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-minus").unwrap(),
+        Rc::new(RestoreMinimizedCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-C-minus").unwrap(),
+        Rc::new(SelectMinimizedCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-o").unwrap(),
+        Rc::new(OpacityCommand::new(-10)),
+    ));
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-C-o").unwrap(),
+        Rc::new(OpacityCommand::new(10)),
+    ));
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-Escape").unwrap(),
+        Rc::new(KillUnresponsiveCommand),
+    ));
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-Tab").unwrap(),
+        Rc::new(CycleFocusCommand::new(1)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-Tab").unwrap(),
+        Rc::new(CycleFocusCommand::new(-1)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-u").unwrap(),
+        Rc::new(JumpToUrgentCommand),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-S-o").unwrap(),
+        Rc::new(MoveWorkspaceToMonitorCommand::new(1)),
+    ));
+
+    config.add_keybind(Keybind::new(
+        KeySequence::try_from("M-w").unwrap(),
+        Rc::new(FocusMonitorCommand::new(MonitorTarget::Relative(1))),
+    ));
+
+    for (key, direction) in [
+        ("bracketleft", Direction::Left),
+        ("bracketright", Direction::Right),
+    ] {
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-S-{}", key).as_str()).unwrap(),
+            Rc::new(FocusMonitorCommand::new(MonitorTarget::Direction(
+                direction,
+            ))),
+        ));
+    }
+
+    for (key, offset) in [("comma", -1), ("period", 1)] {
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-{}", key).as_str()).unwrap(),
+            Rc::new(ViewWorkspaceCommand::new(WorkspaceTarget::Relative(offset))),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-S-{}", key).as_str()).unwrap(),
+            Rc::new(MoveToWorkspaceCommand::new(
+                WorkspaceTarget::Relative(offset),
+                None,
+            )),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-C-{}", key).as_str()).unwrap(),
+            Rc::new(ReorderWorkspaceCommand::new(offset)),
+        ));
+    }
+
+    for index in 0..config.workspace_count.min(9) {
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-{}", index + 1).as_str()).unwrap(),
+            Rc::new(ViewWorkspaceCommand::new(WorkspaceTarget::Index(index))),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-S-{}", index + 1).as_str()).unwrap(),
+            Rc::new(MoveToWorkspaceCommand::new(
+                WorkspaceTarget::Index(index),
+                None,
+            )),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-C-{}", index + 1).as_str()).unwrap(),
+            Rc::new(ToggleViewCommand::new(index)),
+        ));
+
+        config.add_keybind(Keybind::new(
+            KeySequence::try_from(format!("M-C-S-{}", index + 1).as_str()).unwrap(),
+            Rc::new(ToggleTagCommand::new(index)),
+        ));
+    }
+
+    randr::check_profiles(&config.output_profiles)?;
+
     Ok(config)
 }
 
+fn create_layouts(config: &Config) -> Vec<BoxLayout> {
+    vec![
+        Box::new(ThreeColumn::new(config.three_column_ratios)),
+        Box::new(Deck::default()),
+        Box::new(Bsp::default()),
+        Box::new(Maximized),
+    ]
+}
+
+/// Blocks until the X connection has data to read, returns false on timeout.
+fn wait_for_input(conn: &xcb::Connection, timeout: Option<std::time::Duration>) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    wait_for_fds(&[conn.as_raw_fd()], timeout)
+}
+
+/// Blocks until any of `fds` has data to read, returns false on timeout.
+fn wait_for_fds(fds: &[std::os::unix::io::RawFd], timeout: Option<std::time::Duration>) -> bool {
+    let mut fds: Vec<_> = fds
+        .iter()
+        .map(|fd| libc::pollfd {
+            fd: *fd,
+            events: libc::POLLIN,
+            revents: 0,
+        })
+        .collect();
+
+    let timeout = timeout.map_or(-1, |timeout| {
+        timeout.as_millis().min(i32::MAX as u128) as i32
+    });
+
+    // Interrupted calls count as input, the caller polls for events again anyway.
+    unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) != 0 }
+}
+
+/// Server time of the event, for the events carrying one.
+fn event_time(event: &x::Event) -> Option<u32> {
+    use xcb::x::Event;
+
+    match event {
+        Event::KeyPress(event) => Some(event.time()),
+        Event::KeyRelease(event) => Some(event.time()),
+        Event::ButtonPress(event) => Some(event.time()),
+        Event::ButtonRelease(event) => Some(event.time()),
+        Event::MotionNotify(event) => Some(event.time()),
+        Event::EnterNotify(event) => Some(event.time()),
+        Event::LeaveNotify(event) => Some(event.time()),
+        Event::PropertyNotify(event) => Some(event.time()),
+        _ => None,
+    }
+}
+
+/// Creates the hidden window whose _NET_SUPPORTING_WM_CHECK points to itself, telling clients
+/// an EWMH window manager is running.
+fn create_check_window(conn: &xcb::Connection, screen: &x::Screen, atoms: &Atoms) -> x::Window {
+    let window: x::Window = conn.generate_id();
+
+    conn.send_request(&x::CreateWindow {
+        depth: x::COPY_FROM_PARENT as u8,
+        wid: window,
+        parent: screen.root(),
+        x: -1,
+        y: -1,
+        width: 1,
+        height: 1,
+        border_width: 0,
+        class: x::WindowClass::InputOnly,
+        visual: x::COPY_FROM_PARENT,
+        value_list: &[x::Cw::OverrideRedirect(true)],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window,
+        property: atoms.net_supporting_wm_check,
+        r#type: x::ATOM_WINDOW,
+        data: &[window],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window,
+        property: atoms.net_wm_name,
+        r#type: atoms.utf8_string,
+        data: b"rwm",
+    });
+
+    window
+}
+
+fn create_preselection_window(
+    conn: &xcb::Connection,
+    screen: &x::Screen,
+    config: &Config,
+) -> x::Window {
+    let window: x::Window = conn.generate_id();
+
+    conn.send_request(&x::CreateWindow {
+        depth: x::COPY_FROM_PARENT as u8,
+        wid: window,
+        parent: screen.root(),
+        x: 0,
+        y: 0,
+        width: 1,
+        height: 1,
+        border_width: 0,
+        class: x::WindowClass::InputOutput,
+        visual: screen.root_visual(),
+        value_list: &[
+            x::Cw::BackPixel(config.preselection_color),
+            x::Cw::OverrideRedirect(true),
+        ],
+    });
+
+    window
+}
+
+/// Area of the whole screen, which RandR may have resized since connecting.
+fn screen_size(conn: &xcb::Connection, root: x::Window) -> xcb::Result<Rect> {
+    let cookie = conn.send_request(&x::GetGeometry {
+        drawable: x::Drawable::Window(root),
+    });
+    let reply = conn.wait_for_reply(cookie)?;
+    Ok(Rect::new(0, 0, reply.width().into(), reply.height().into()))
+}
+
+/// Outputs to create monitors for, from RandR or else Xinerama, none when they are unknown.
+fn query_outputs(conn: &xcb::Connection, root: x::Window, config: &Config) -> Vec<randr::Output> {
+    let mut outputs = randr::query_outputs(conn, root)
+        .or_else(|e| {
+            info!("{}, falling back to Xinerama", e);
+            xinerama::query_screens(conn)
+        })
+        .unwrap_or_else(|e| {
+            info!("{}, using a single monitor", e);
+            Vec::new()
+        });
+
+    // Shared workspaces are shown on a single monitor at a time.
+    if config.workspace_model == WorkspaceModel::Shared && outputs.len() > config.workspace_count {
+        warn!(
+            "More outputs than workspaces, ignoring {:?}",
+            &outputs[config.workspace_count..]
+        );
+        outputs.truncate(config.workspace_count);
+    }
+
+    outputs
+}
+
+/// One monitor per RandR output, each one showing its first workspace, or a single one
+/// covering the screen when the outputs are unknown.
+fn create_monitors(
+    conn: &xcb::Connection,
+    root: x::Window,
+    screen_area: Rect,
+    config: &Config,
+) -> Vec<Monitor> {
+    let outputs = query_outputs(conn, root, config);
+    if outputs.is_empty() {
+        return vec![Monitor::new(screen_area, 0)];
+    }
+
+    outputs
+        .into_iter()
+        .enumerate()
+        .map(|(index, output)| {
+            let workspace = match config.workspace_model {
+                WorkspaceModel::PerMonitor => index * config.workspace_count,
+                WorkspaceModel::Shared => index,
+            };
+            debug!("Monitor {}: {:?}", index, output);
+            let scale = output_scale(config, &output);
+
+            let mut monitor = Monitor::new(output.area, workspace);
+            monitor.output = (!output.name.is_empty()).then_some(output.name);
+            monitor.primary = output.primary;
+            monitor.rotation = output.rotation;
+            monitor.scale = scale;
+            monitor
+        })
+        .collect()
+}
+
+/// Factor for the borders and gaps of a monitor showing `output`.
+fn output_scale(config: &Config, output: &randr::Output) -> f32 {
+    let dpi = config.output_dpi.get(&output.name).copied().or(output.dpi);
+
+    match dpi {
+        Some(dpi) if config.dpi_scaling => monitor::scale_for_dpi(dpi),
+        _ => 1.0,
+    }
+}
+
+/// Sets the layout configured for the output of the monitor owning the workspace, or the
+/// portrait layout.
+fn apply_output_layout(config: &Config, workspace: &mut Workspace, monitor: &Monitor) {
+    if config.workspace_model != WorkspaceModel::PerMonitor {
+        return;
+    }
+
+    let layout = match monitor
+        .output
+        .as_ref()
+        .and_then(|output| config.output_layouts.get(output))
+        .or(config
+            .portrait_layout
+            .as_ref()
+            .filter(|_| monitor.portrait()))
+    {
+        Some(layout) => layout,
+        None => return,
+    };
+
+    if !workspace.set_layout(layout) {
+        warn!("Output layout {} is not available", layout);
+    }
+}
+
+/// Creates the workspace at `index` among those of `monitor` in `group`.
+fn create_workspace(
+    config: &Config,
+    monitor: usize,
+    group: usize,
+    index: usize,
+    name: String,
+) -> Workspace {
+    let mut workspace = Workspace::new(
+        name,
+        monitor,
+        create_layouts(config),
+        LayoutParams {
+            master_factor: config.deck_master_factor,
+            ..LayoutParams::default()
+        },
+        config.workspace_insertion_policy(index),
+    );
+    workspace.group = group;
+    workspace
+}
+
 impl App {
-    fn new() -> Self {
+    fn new(options: &Options) -> Self {
         let (conn, screen_num) = xcb::Connection::connect_with_extensions(
             None,
-            &[xcb::Extension::Xkb, xcb::Extension::RandR],
-            &[],
+            &[xcb::Extension::Xkb],
+            &[xcb::Extension::RandR, xcb::Extension::Xinerama],
         )
         .expect("XCB connection established");
 
@@ -127,6 +799,12 @@ impl App {
         let screen = setup.roots().nth(screen_num as usize).unwrap();
 
         let root: x::Window = screen.root();
+        let screen_area = Rect::new(
+            0,
+            0,
+            screen.width_in_pixels().into(),
+            screen.height_in_pixels().into(),
+        );
 
         debug!(
             "Root window: {:?}, width: {}px, height: {}px",
@@ -135,10 +813,22 @@ impl App {
             screen.height_in_pixels()
         );
 
+        let atoms = Atoms::intern_all(&conn).expect("Atoms interned");
+        let check_window = create_check_window(&conn, screen, &atoms);
+
+        let wm_selection =
+            selection::acquire(&conn, screen_num, root, check_window, options.replace)
+                .unwrap_or_else(|e| {
+                    error!("{}", e);
+                    std::process::exit(1);
+                });
+
         register_for_xcb_events(&conn, root)
             .expect("Failed to register for XCB events. Other window manager running?");
 
-        register_for_randr_events(&conn, root).expect("Failed to register for XrandR events");
+        if randr::available(&conn) {
+            register_for_randr_events(&conn, root).expect("Failed to register for XrandR events");
+        }
 
         keyboard::setup_xkb_extension(&conn);
         register_for_xkb_events(&conn).expect("Failed to register for XKB events");
@@ -147,34 +837,318 @@ impl App {
 
         let keyboard = Keyboard::new(&conn);
 
+        let preselection_window = create_preselection_window(&conn, screen, &config);
+        let osd = Osd::new(&conn, screen, &config)
+            .map_err(|e| error!("Running without the OSD: {}", e))
+            .ok();
+        let pinger = config
+            .ping_interval
+            .map(|interval| Pinger::new(interval, Instant::now()));
+
+        let session = Session::connect(options.session_id.as_deref()).unwrap_or_else(|e| {
+            warn!("{}", e);
+            None
+        });
+
+        let connected_outputs = randr::apply_profiles(&conn, root, &config.output_profiles, &[])
+            .unwrap_or_else(|e| {
+                error!("Applying the output profiles failed: {}", e);
+                Vec::new()
+            });
+        let screen_area = screen_size(&conn, root).unwrap_or(screen_area);
+        let monitors = create_monitors(&conn, root, screen_area, &config);
+
+        let current_monitor = monitors
+            .iter()
+            .position(|monitor| monitor.primary)
+            .unwrap_or_default();
+
+        let owners = match config.workspace_model {
+            WorkspaceModel::PerMonitor => monitors.len(),
+            WorkspaceModel::Shared => 1,
+        };
+
+        let mut workspaces: Vec<Workspace> = (0..config.group_count())
+            .flat_map(|group| (0..owners).map(move |monitor| (group, monitor)))
+            .flat_map(|(group, monitor)| {
+                (0..config.workspace_count).map(move |index| (group, monitor, index))
+            })
+            .map(|(group, monitor, index)| {
+                create_workspace(&config, monitor, group, index, config.workspace_name(index))
+            })
+            .collect();
+
+        // Shared workspaces belong to the monitor showing them.
+        for (index, monitor) in monitors.iter().enumerate() {
+            workspaces[monitor.workspace].monitor = index;
+        }
+
+        for workspace in &mut workspaces {
+            apply_output_layout(&config, workspace, &monitors[workspace.monitor]);
+        }
+
         Self {
             conn,
             root,
+            atoms,
             config,
             keyboard,
             clients: HashMap::new(),
+            workspaces,
+            monitors,
+            current_monitor,
+            group: 0,
+            focused: None,
+            drag: None,
+            focus_cycle: None,
+            client_list: Vec::new(),
+            stack: Vec::new(),
+            stacked: Vec::new(),
+            minimized: Vec::new(),
+            showing_desktop: false,
+            ignored_unmaps: HashMap::new(),
+            pointer: (0, 0),
+            pinger,
+            launches: Launches::default(),
+            bars: Bars::default(),
+            terminating: Vec::new(),
+            user_time: None,
+            event_time: x::CURRENT_TIME,
+            preselection_window,
+            check_window,
+            wm_selection,
+            running: true,
+            session,
+            placements: None,
+            osd,
+            prompt: None,
+            screen_area,
+            connected_outputs,
         }
     }
 
     fn run(&mut self) {
+        use std::os::unix::io::AsRawFd;
+
+        self.rehome_workspaces();
+        self.restore_session();
+        self.draw_backgrounds();
+        self.bars.update(&self.config.bar_command, &self.monitors);
+        self.unfocus();
+        self.adopt_windows();
+        self.publish_client_list();
+        self.publish_support();
+        self.publish_desktops();
+        self.publish_showing_desktop();
         self.grab_keybinds();
+        self.grab_buttons();
 
         loop {
-            let event = self.conn.wait_for_event();
-            match event {
-                Ok(event) => {
-                    self.handle_xcb_event(event);
-                }
-                Err(e) => {
-                    error!("Error while waiting for an event: {:?}", e);
-                    break;
+            loop {
+                match self.conn.poll_for_event() {
+                    Ok(Some(event)) => {
+                        self.handle_xcb_event(event);
+                        if !self.running {
+                            return;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Error while waiting for an event: {:?}", e);
+                        return;
+                    }
                 }
             }
-        }
-    }
 
-    fn handle_xcb_event(&mut self, event: xcb::Event) {
-        match event {
+            self.conn.flush().expect("Flushed");
+
+            let now = Instant::now();
+            let ping_time_left = self.pinger.as_ref().map(|pinger| pinger.time_left(now));
+            let terminate_time_left = self
+                .terminating
+                .iter()
+                .map(|(_, _, deadline)| deadline.saturating_duration_since(now));
+            let timeout = self
+                .osd
+                .as_ref()
+                .and_then(Osd::time_left)
+                .into_iter()
+                .chain(ping_time_left)
+                .chain(terminate_time_left)
+                .chain(self.bars.time_left(now))
+                .min();
+
+            let fds: Vec<_> = std::iter::once(self.conn.as_raw_fd())
+                .chain(self.session.as_ref().map(Session::fd))
+                .chain(self.prompt.as_ref().map(Prompt::fd))
+                .collect();
+            if !wait_for_fds(&fds, timeout) {
+                if let Some(osd) = self
+                    .osd
+                    .as_mut()
+                    .filter(|osd| osd.time_left().is_some_and(|left| left.is_zero()))
+                {
+                    osd.hide(&self.conn);
+                }
+            }
+            self.ping_clients();
+            self.kill_terminating();
+            if self
+                .bars
+                .time_left(Instant::now())
+                .is_some_and(|left| left.is_zero())
+            {
+                self.bars.update(&self.config.bar_command, &self.monitors);
+            }
+            self.process_session();
+            self.process_prompt();
+            self.conn.flush().expect("Flushed");
+            if !self.running {
+                return;
+            }
+        }
+    }
+
+    fn process_session(&mut self) {
+        let requests = match self.session.as_mut().map(Session::process) {
+            Some(Ok(requests)) => requests,
+            Some(Err(e)) => {
+                warn!("{}", e);
+                self.session = None;
+                return;
+            }
+            None => return,
+        };
+
+        for request in requests {
+            match request {
+                session::Request::SaveYourself => {
+                    let saved = self.save_session();
+                    if let Err(e) = &saved {
+                        error!("Saving the session failed: {}", e);
+                    }
+                    if let Some(session) = &self.session {
+                        session.save_yourself_done(saved.is_ok());
+                    }
+                }
+                session::Request::Die => {
+                    info!("Session ended");
+                    self.shut_down();
+                    self.session = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn session_file(id: &str) -> anyhow::Result<PathBuf> {
+        snapshot::state_file(&format!("session-{}", id))
+    }
+
+    /// Saves the workspaces for the next session, to be restored with the client id the
+    /// session manager restarts the window manager with.
+    fn save_session(&self) -> anyhow::Result<()> {
+        let session = match &self.session {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+
+        let state = SessionState {
+            current: Some(self.qualified_name(self.current_workspace())),
+            workspaces: (0..self.workspaces.len())
+                .map(|index| (self.qualified_name(index), self.workspace_snapshot(index)))
+                .collect(),
+        };
+
+        let path = Self::session_file(session.client_id())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, state.to_string())?;
+
+        info!("Session saved to {:?}", path);
+        Ok(())
+    }
+
+    /// Restores the workspaces saved with the previous session, the session manager
+    /// starts their clients again.
+    fn restore_session(&mut self) {
+        let path = match &self.session {
+            Some(session) => Self::session_file(session.client_id()),
+            None => return,
+        };
+        let state: SessionState = match path
+            .and_then(|path| Ok(std::fs::read_to_string(path)?))
+            .and_then(|state| state.parse())
+        {
+            Ok(state) => state,
+            Err(e) => {
+                debug!("No session to restore: {}", e);
+                return;
+            }
+        };
+
+        for (name, snapshot) in &state.workspaces {
+            let index = self.qualified_workspace_index(name);
+            let workspace = &mut self.workspaces[index];
+            if !workspace.set_layout(&snapshot.layout) {
+                warn!("Session layout {} is not available", snapshot.layout);
+            }
+            workspace.params = snapshot.params;
+        }
+
+        if let Some(current) = &state.current {
+            let index = self.qualified_workspace_index(current);
+            if let Err(e) = self.view_workspace(index) {
+                error!("Restoring the session workspace failed: {}", e);
+            }
+        }
+
+        self.placements = Some(Placements::new(&state, Instant::now()));
+        info!("Session restored");
+    }
+
+    /// Leaves the clients to the window manager replacing this one, which only adopts
+    /// mapped windows, and stops the event loop. The grabs and the root event mask are
+    /// released before the selection window is destroyed, the replacement starts then.
+    fn shut_down(&mut self) {
+        for window in &self.client_list {
+            self.conn.send_request(&x::UngrabButton {
+                button: x::ButtonIndex::Any,
+                grab_window: *window,
+                modifiers: x::ModMask::ANY,
+            });
+            self.conn.send_request(&x::MapWindow { window: *window });
+        }
+        self.conn.send_request(&x::UngrabButton {
+            button: x::ButtonIndex::Any,
+            grab_window: self.root,
+            modifiers: x::ModMask::ANY,
+        });
+        self.conn.send_request(&x::UngrabKey {
+            key: x::GRAB_ANY,
+            grab_window: self.root,
+            modifiers: x::ModMask::ANY,
+        });
+        self.conn.send_request(&x::ChangeWindowAttributes {
+            window: self.root,
+            value_list: &[x::Cw::EventMask(EventMask::NO_EVENT)],
+        });
+        self.conn.send_request(&x::SetInputFocus {
+            revert_to: x::InputFocus::PointerRoot,
+            focus: x::Window::none(),
+            time: self.event_time,
+        });
+        self.conn.send_request(&x::DestroyWindow {
+            window: self.check_window,
+        });
+        self.conn.flush().expect("Flushed");
+
+        self.running = false;
+    }
+
+    fn handle_xcb_event(&mut self, event: xcb::Event) {
+        match event {
             xcb::Event::X(event) => {
                 self.handle_x11_event(event);
             }
@@ -193,66 +1167,284 @@ impl App {
     fn handle_x11_event(&mut self, event: xcb::x::Event) {
         use xcb::x::Event;
 
+        if let Some(time) = event_time(&event) {
+            self.event_time = time;
+        }
+
         match event {
             Event::ConfigureRequest(event) => {
                 trace!("{:?}", event);
-
-                let cookie = self.conn.send_request_checked(&xcb::x::ConfigureWindow {
-                    window: event.window(),
-                    value_list: &[
-                        x::ConfigWindow::X(event.x().into()),
-                        x::ConfigWindow::Y(event.y().into()),
-                        x::ConfigWindow::Width(event.width().into()),
-                        x::ConfigWindow::Height(event.height().into()),
-                        x::ConfigWindow::BorderWidth(event.border_width().into()),
-                        x::ConfigWindow::StackMode(event.stack_mode()),
-                    ],
-                });
-
-                self.conn.flush().unwrap();
-
-                let result = self.conn.check_request(cookie);
-                if result.is_err() {
-                    error!("ConfigureRequest failed {:?}", result);
-                }
+                self.handle_configure_request(&event);
             }
             Event::ConfigureNotify(event) => {
                 trace!("{:?}", event);
+
+                let geometry = Rect::new(
+                    event.x().into(),
+                    event.y().into(),
+                    event.width().into(),
+                    event.height().into(),
+                );
+                let monitor = self
+                    .monitors
+                    .iter()
+                    .position(|monitor| monitor.area.contains(geometry.center()));
+
+                let mut border_changed = false;
+                if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                    client.geometry = geometry;
+                    border_changed = client.border_width != u32::from(event.border_width());
+                    client.border_width = event.border_width().into();
+                    if let Some(monitor) = monitor {
+                        client.monitor = monitor;
+                    }
+                }
+                if border_changed && self.client_list.contains(&event.window()) {
+                    self.publish_frame_extents(event.window());
+                }
             }
             Event::CreateNotify(event) => {
                 trace!("{:?}", event);
 
-                self.clients.insert(
-                    event.window().resource_id(),
-                    Client {
-                        _window: event.window(),
-                    },
+                if event.override_redirect() {
+                    return;
+                }
+
+                let mut client = Client::new(event.window());
+                client.geometry = Rect::new(
+                    event.x().into(),
+                    event.y().into(),
+                    event.width().into(),
+                    event.height().into(),
                 );
+                client.border_width = event.border_width().into();
+                self.clients.insert(event.window().resource_id(), client);
+            }
+            Event::SelectionClear(event) if event.selection() == self.wm_selection => {
+                info!("Replaced by another window manager");
+                self.shut_down();
             }
             Event::DestroyNotify(event) => {
-                self.clients.remove(&event.window().resource_id());
+                trace!("Window destroyed: {:?}", event.window());
+                self.forget_window(event.window());
             }
-            Event::MapRequest(event) => {
-                trace!("MapRequest WindowId: {:?}", event.window());
+            Event::ReparentNotify(event) => {
+                trace!("{:?}", event);
+
+                if event.parent() != self.root {
+                    // Embedded into another client, e.g. a tray or a tabbing container.
+                    self.forget_window(event.window());
+                    return;
+                }
+
+                if event.override_redirect() || !self.is_manageable(event.window()) {
+                    return;
+                }
+
+                let cookie = self.conn.send_request(&x::GetGeometry {
+                    drawable: x::Drawable::Window(event.window()),
+                });
+                let mut client = Client::new(event.window());
+                if let Ok(reply) = self.conn.wait_for_reply(cookie) {
+                    client.geometry = Rect::new(
+                        event.x().into(),
+                        event.y().into(),
+                        reply.width().into(),
+                        reply.height().into(),
+                    );
+                    client.border_width = reply.border_width().into();
+                }
+                self.clients.insert(event.window().resource_id(), client);
 
-                let cookie = self.conn.send_request_checked(&xcb::x::MapWindow {
+                // Windows returned to the root still mapped send no MapRequest.
+                let cookie = self.conn.send_request(&x::GetWindowAttributes {
                     window: event.window(),
                 });
+                let viewable = self
+                    .conn
+                    .wait_for_reply(cookie)
+                    .is_ok_and(|attributes| attributes.map_state() == x::MapState::Viewable);
+                if viewable {
+                    debug!("Managing window returned to the root: {:?}", event.window());
+                    self.map_client(event.window());
+                    self.arrange();
+                }
+            }
+            Event::MapRequest(event) => {
+                trace!("MapRequest WindowId: {:?}", event.window());
+
+                if !self.is_manageable(event.window()) {
+                    debug!("Mapping unmanaged window {:?}", event.window());
+                    let popup = self.is_popup(event.window());
+                    match self.clients.get_mut(&event.window().resource_id()) {
+                        Some(client) if popup => client.popup = true,
+                        _ => {
+                            self.clients.remove(&event.window().resource_id());
+                        }
+                    }
+                    // Checked, the window may already be gone.
+                    let cookie = self.conn.send_request_checked(&x::MapWindow {
+                        window: event.window(),
+                    });
+                    if self.conn.check_request(cookie).is_err() {
+                        return;
+                    }
+                    if popup {
+                        self.raise(event.window());
+                    } else {
+                        // Not a client, stays on top until the next restack.
+                        self.conn.send_request(&x::ConfigureWindow {
+                            window: event.window(),
+                            value_list: &[x::ConfigWindow::StackMode(x::StackMode::Above)],
+                        });
+                    }
+                    return;
+                }
 
-                let result = self.conn.check_request(cookie);
-                if result.is_err() {
-                    error!("MapRequest failed {:?}", result);
+                if self.minimized.contains(&event.window()) {
+                    if let Err(e) = self.restore(event.window()) {
+                        error!("Restoring window failed: {}", e);
+                    }
                     return;
                 }
+
+                self.map_client(event.window());
+                self.arrange();
+            }
+            Event::Expose(event) => {
+                if let Some(osd) = self
+                    .osd
+                    .as_ref()
+                    .filter(|osd| osd.window() == event.window())
+                {
+                    osd.draw(&self.conn);
+                }
             }
             Event::ClientMessage(event) => {
                 trace!("{:?}", event);
+
+                let data = match event.data() {
+                    x::ClientMessageData::Data32(data) => data,
+                    _ => return,
+                };
+
+                if event.r#type() == self.atoms.net_current_desktop {
+                    self.view_desktop(data[0] as usize);
+                } else if event.r#type() == self.atoms.net_showing_desktop {
+                    self.set_showing_desktop(data[0] != 0);
+                } else if event.r#type() == self.atoms.net_wm_state {
+                    self.handle_wm_state_request(event.window(), &data);
+                } else if event.r#type() == self.atoms.net_wm_desktop {
+                    self.handle_desktop_request(event.window(), data[0]);
+                } else if event.r#type() == self.atoms.wm_change_state {
+                    if data[0] == properties::WM_STATE_ICONIC {
+                        self.minimize(event.window());
+                    }
+                } else if event.r#type() == self.atoms.wm_protocols
+                    && data[0] == self.atoms.net_wm_ping.resource_id()
+                {
+                    self.handle_pong(data[2]);
+                } else if event.r#type() == self.atoms.net_active_window {
+                    self.handle_activation_request(event.window(), &data);
+                } else if event.r#type() == self.atoms.net_request_frame_extents {
+                    self.estimate_frame_extents(event.window());
+                } else if event.r#type() == self.atoms.net_close_window {
+                    self.close_client(event.window(), data[0]);
+                } else if event.r#type() == self.atoms.net_wm_moveresize {
+                    self.handle_moveresize_request(event.window(), &data);
+                }
+            }
+            Event::PropertyNotify(event) => {
+                if event.atom() == x::ATOM_WM_NORMAL_HINTS {
+                    let size_hints = properties::get_size_hints(&self.conn, event.window());
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        client.size_hints = size_hints;
+                        self.arrange();
+                    }
+                } else if event.atom() == x::ATOM_WM_NAME || event.atom() == self.atoms.net_wm_name
+                {
+                    let title = properties::get_title(&self.conn, &self.atoms, event.window())
+                        .unwrap_or_default();
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        if client.title != title {
+                            let old_title = std::mem::replace(&mut client.title, title);
+                            self.apply_title_rules(event.window(), &old_title);
+                        }
+                    }
+                } else if event.atom() == self.atoms.net_wm_icon {
+                    let icon = properties::get_icon(
+                        &self.conn,
+                        &self.atoms,
+                        event.window(),
+                        osd::ICON_SIZE,
+                    );
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        client.icon = icon;
+                    }
+                } else if event.atom() == self.atoms.motif_wm_hints {
+                    let motif_hints =
+                        properties::get_motif_hints(&self.conn, &self.atoms, event.window());
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        client.motif_hints = motif_hints;
+                        self.arrange();
+                    }
+                } else if event.atom() == self.atoms.wm_protocols {
+                    let protocols =
+                        properties::get_atoms(&self.conn, event.window(), self.atoms.wm_protocols);
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        client.pingable = protocols.contains(&self.atoms.net_wm_ping);
+                        client.take_focus = protocols.contains(&self.atoms.wm_take_focus);
+                    }
+                } else if event.atom() == self.atoms.net_wm_user_time {
+                    if self.focused == Some(event.window()) {
+                        if let Some(time) =
+                            properties::get_user_time(&self.conn, &self.atoms, event.window())
+                        {
+                            self.note_user_time(time);
+                        }
+                    }
+                } else if event.atom() == x::ATOM_WM_HINTS
+                    || event.atom() == self.atoms.net_wm_state
+                {
+                    if event.atom() == x::ATOM_WM_HINTS {
+                        let input = properties::get_input_hint(&self.conn, event.window());
+                        if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                            client.input = input;
+                        }
+                    }
+
+                    // The focused client already has the attention it asks for.
+                    let urgent = properties::get_urgency(&self.conn, &self.atoms, event.window());
+                    if !urgent || self.focused != Some(event.window()) {
+                        self.set_urgent(event.window(), urgent);
+                    }
+                } else if event.atom() == self.atoms.net_wm_strut_partial
+                    || event.atom() == self.atoms.net_wm_strut
+                {
+                    let struts = properties::get_struts(&self.conn, &self.atoms, event.window());
+                    if let Some(client) = self.clients.get_mut(&event.window().resource_id()) {
+                        client.struts = struts;
+                        self.update_work_areas();
+                    }
+                }
             }
             Event::MapNotify(_) => {}
-            Event::UnmapNotify(_) => {
-                // Hide the window
+            Event::UnmapNotify(event) => {
+                let id = event.window().resource_id();
+                if let Some(count) = self.ignored_unmaps.get_mut(&id) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.ignored_unmaps.remove(&id);
+                    }
+                    return;
+                }
+
+                if self.clients.contains_key(&id) {
+                    self.withdraw(event.window());
+                }
             }
             Event::KeyPress(event) => {
+                self.note_user_time(event.time());
                 let keycode = event.detail();
 
                 let numlock_index = self.keyboard.get_mod_index(xkb::MOD_NAME_NUM);
@@ -268,24 +1460,50 @@ impl App {
                     modmask
                 );
 
-                for keybind in &self.config.keybinds {
-                    if keybind.matches(keycode, modmask) {
-                        keybind
-                            .command()
-                            .execute()
-                            .expect("Keybind command executed");
-                        break;
+                let command = self
+                    .config
+                    .keybinds
+                    .iter()
+                    .find(|keybind| keybind.matches(keycode, modmask))
+                    .map(|keybind| keybind.command().clone());
+
+                if let Some(command) = command {
+                    let focused = self.focused;
+                    let monitor = self.current_monitor;
+                    if let Err(e) = command.execute(self) {
+                        error!("Keybind command failed: {:?}", e);
                     }
+                    if self.config.warp_pointer
+                        && (self.focused != focused || self.current_monitor != monitor)
+                    {
+                        self.warp_pointer();
+                    }
+                }
+            }
+            Event::KeyRelease(event) => {
+                let keysym = self.keyboard.keycode_to_keysym(event.detail().into());
+                if (xkb::KEY_Shift_L..=xkb::KEY_Hyper_R).contains(&keysym) {
+                    self.finish_focus_cycle();
                 }
             }
-            Event::MotionNotify(_) => {
+            Event::MotionNotify(event) => {
                 // We don't want moves to be logged...
+                self.handle_motion(&event);
+            }
+            Event::ButtonPress(event) => {
+                self.note_user_time(event.time());
+                self.handle_button_press(&event);
+            }
+            Event::ButtonRelease(event) => {
+                self.handle_button_release(&event);
             }
             Event::MappingNotify(e) => {
                 error!("Keyboard mapping changed? {:?}", e);
                 panic!("Should we handle this?");
             }
-            Event::EnterNotify(_) => {}
+            Event::EnterNotify(event) => {
+                self.handle_enter(&event);
+            }
             Event::LeaveNotify(_) => {}
             e => {
                 trace!("Unhandled event: {:?}", e);
@@ -331,69 +1549,3435 @@ impl App {
                 trace!("{:?}", event);
             }
         }
+
+        match randr::apply_profiles(
+            &self.conn,
+            self.root,
+            &self.config.output_profiles,
+            &self.connected_outputs,
+        ) {
+            Ok(connected) => self.connected_outputs = connected,
+            Err(e) => error!("Applying the output profiles failed: {}", e),
+        }
+        self.update_monitors();
     }
 
-    fn ungrab_keybinds(&self) {
-        let cookie = self.conn.send_request_checked(&xcb::x::UngrabKey {
-            key: xcb::x::Grab::Any as u8,
-            grab_window: self.root,
-            modifiers: xcb::x::ModMask::ANY,
-        });
+    fn monitor(&self) -> &Monitor {
+        &self.monitors[self.current_monitor]
+    }
 
-        self.conn.check_request(cookie).expect("keys ungrabbed");
-        self.conn.flush().expect("Flushed");
+    /// Index of the workspace visible on the current monitor.
+    fn current_workspace(&self) -> usize {
+        self.monitor().workspace
     }
 
-    fn grab_keybinds(&mut self) {
-        let numlock_index = self.keyboard.get_mod_index(xkb::MOD_NAME_NUM);
-        let numlock_mask = xcb::x::ModMask::from_bits(1 << numlock_index)
-            .expect("Expected a valid numlock modmask");
+    fn workspace(&self) -> &Workspace {
+        &self.workspaces[self.current_workspace()]
+    }
 
-        for keybind in &mut self.config.keybinds {
-            let mask =
-                xcb::x::ModMask::from_bits_truncate(keybind.key_sequence().modifiers().bits());
+    fn workspace_mut(&mut self) -> &mut Workspace {
+        let index = self.current_workspace();
+        &mut self.workspaces[index]
+    }
 
-            let keycodes = self
-                .keyboard
-                .keysym_to_keycodes(&self.conn, keybind.key_sequence().keysym());
+    pub fn next_layout(&mut self) {
+        self.workspace_mut().next_layout();
 
-            debug!(
-                "Keysym: {:?}, keycodes: {:?}",
-                keybind.key_sequence().keysym(),
-                keycodes
-            );
+        debug!("Layout changed to {}", self.workspace().layout().name());
+        self.arrange();
+    }
 
-            for keycode in &keycodes {
-                for modifiers in [
-                    mask,
-                    mask | numlock_mask,
-                    mask | xcb::x::ModMask::LOCK,
-                    mask | xcb::x::ModMask::LOCK | numlock_mask,
-                ] {
-                    let cookie = self.conn.send_request_checked(&xcb::x::GrabKey {
-                        owner_events: true,
-                        grab_window: self.root,
-                        modifiers,
-                        key: *keycode,
-                        pointer_mode: xcb::x::GrabMode::Async,
-                        keyboard_mode: xcb::x::GrabMode::Async,
-                    });
+    pub fn balance_layout(&mut self) {
+        let master_factor = self.config.deck_master_factor;
+        self.workspace_mut().balance(master_factor);
 
-                    self.conn.check_request(cookie).expect("key grabbed");
+        debug!("Layout balanced");
+        self.arrange();
+    }
+
+    pub fn adjust_layout_params<F: FnOnce(&mut LayoutParams)>(&mut self, adjust: F) {
+        adjust(&mut self.workspace_mut().params);
+
+        debug!("Layout params changed to {:?}", self.workspace().params);
+        self.arrange();
+    }
+
+    fn layout_snapshot_path(&self, path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
+        match path {
+            Some(path) => Ok(path),
+            None => snapshot::state_file(&format!(
+                "layout-{}",
+                self.qualified_name(self.current_workspace())
+                    .replace('/', "-")
+            )),
+        }
+    }
+
+    fn window_identity(&self, window: x::Window) -> WindowIdentity {
+        match self.clients.get(&window.resource_id()) {
+            Some(client) => WindowIdentity {
+                class: client.class.clone(),
+                title: client.title.clone(),
+            },
+            None => WindowIdentity::default(),
+        }
+    }
+
+    fn workspace_snapshot(&self, index: usize) -> LayoutSnapshot {
+        let workspace = &self.workspaces[index];
+
+        LayoutSnapshot {
+            layout: workspace.layout().name().to_owned(),
+            params: workspace.params,
+            clients: workspace
+                .clients
+                .iter()
+                .map(|window| self.window_identity(*window))
+                .collect(),
+        }
+    }
+
+    pub fn save_layout_snapshot(&self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let path = self.layout_snapshot_path(path)?;
+        let snapshot = self.workspace_snapshot(self.current_workspace());
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, snapshot.to_string())?;
+
+        info!("Layout snapshot saved to {:?}", path);
+        Ok(())
+    }
+
+    pub fn restore_layout_snapshot(&mut self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let path = self.layout_snapshot_path(path)?;
+        let snapshot: LayoutSnapshot = std::fs::read_to_string(&path)?.parse()?;
+
+        let windows: Vec<_> = self
+            .workspace()
+            .clients
+            .iter()
+            .map(|window| (*window, self.window_identity(*window)))
+            .collect();
+        let clients = snapshot.order(&windows);
+
+        let workspace = self.workspace_mut();
+        if !workspace.set_layout(&snapshot.layout) {
+            warn!("Snapshot layout {} is not available", snapshot.layout);
+        }
+        workspace.params = snapshot.params;
+        workspace.clients = clients;
+
+        info!("Layout snapshot restored from {:?}", path);
+        self.arrange();
+        Ok(())
+    }
+
+    pub fn view_workspace(&mut self, index: usize) -> anyhow::Result<()> {
+        if index >= self.workspaces.len() {
+            anyhow::bail!("Workspace {} does not exist", index);
+        }
+
+        let current = self.current_workspace();
+        if index == current {
+            return Ok(());
+        }
+
+        let visible = self.visible_windows();
+
+        // Switching workspaces brings the clients back.
+        if self.showing_desktop {
+            self.showing_desktop = false;
+            self.publish_showing_desktop();
+        }
+
+        // A workspace already visible on another monitor swaps places with the current one.
+        if let Some(other) = self.monitors.iter().position(|m| m.workspace == index) {
+            self.monitors[other].workspace = current;
+            self.workspaces[current].monitor = other;
+        }
+        self.workspaces[index].monitor = self.current_monitor;
+
+        for monitor in &mut self.monitors {
+            monitor.combined.retain(|combined| *combined != index);
+        }
+
+        let monitor = &mut self.monitors[self.current_monitor];
+        monitor.workspace = index;
+        monitor.combined.clear();
+        monitor.history.visit(index);
+
+        let area = self.work_area(self.monitor());
+        let name = self.workspaces[index].name.clone();
+        if let Some(osd) = &mut self.osd {
+            osd.show(&self.conn, &self.config, &name, area);
+        }
+        self.publish_desktops();
+
+        self.update_visibility(&visible);
+
+        self.refocus();
+
+        self.destroy_unused_workspaces();
+        self.arrange();
+        Ok(())
+    }
+
+    /// Reads the properties of a window asking to be mapped and shows it, tiled unless it
+    /// is a dock.
+    fn map_client(&mut self, window: x::Window) {
+        self.conn.send_request(&xcb::x::ChangeWindowAttributes {
+            window,
+            value_list: &[xcb::x::Cw::EventMask(
+                EventMask::PROPERTY_CHANGE | EventMask::ENTER_WINDOW,
+            )],
+        });
+
+        let size_hints = properties::get_size_hints(&self.conn, window);
+        let motif_hints = properties::get_motif_hints(&self.conn, &self.atoms, window);
+        let struts = properties::get_struts(&self.conn, &self.atoms, window);
+        let urgent = properties::get_urgency(&self.conn, &self.atoms, window);
+        let (instance, class) = properties::get_wm_class(&self.conn, window).unwrap_or_default();
+        let title = properties::get_title(&self.conn, &self.atoms, window).unwrap_or_default();
+        let icon = properties::get_icon(&self.conn, &self.atoms, window, osd::ICON_SIZE);
+        let role = properties::get_string_property(&self.conn, window, self.atoms.wm_window_role)
+            .unwrap_or_default();
+        let pid = properties::get_pid(&self.conn, &self.atoms, window);
+        let transient_for = properties::get_transient_for(&self.conn, window);
+        let protocols = properties::get_atoms(&self.conn, window, self.atoms.wm_protocols);
+        let states = properties::get_atoms(&self.conn, window, self.atoms.net_wm_state);
+        let input = properties::get_input_hint(&self.conn, window);
+        let modal = states.contains(&self.atoms.net_wm_state_modal);
+        let fullscreen = states.contains(&self.atoms.net_wm_state_fullscreen);
+        let above = states.contains(&self.atoms.net_wm_state_above);
+        let types = properties::get_atoms(&self.conn, window, self.atoms.net_wm_window_type);
+        let dock = struts.is_some() || types.contains(&self.atoms.net_wm_window_type_dock);
+        let desktop = types.contains(&self.atoms.net_wm_window_type_desktop);
+        let below = states.contains(&self.atoms.net_wm_state_below);
+
+        let client = self
+            .clients
+            .entry(window.resource_id())
+            .or_insert_with(|| Client::new(window));
+        client.size_hints = size_hints;
+        client.motif_hints = motif_hints;
+        client.struts = struts;
+        client.dock = dock;
+        client.desktop = desktop;
+        client.instance = instance;
+        client.class = class;
+        client.title = title;
+        client.icon = icon;
+        client.role = role;
+        client.pid = pid;
+        client.pingable = protocols.contains(&self.atoms.net_wm_ping);
+        client.take_focus = protocols.contains(&self.atoms.wm_take_focus);
+        client.input = input;
+        client.transient_for = transient_for;
+        client.modal = modal;
+        if let Some(floating) = rules::floating(&self.config.rules, client) {
+            client.floating = floating;
+        }
+        client.floating |= modal;
+        if client.floating {
+            client.maximized_vert = states.contains(&self.atoms.net_wm_state_maximized_vert);
+            client.maximized_horz = states.contains(&self.atoms.net_wm_state_maximized_horz);
+            if client.maximized_vert || client.maximized_horz {
+                client.pre_maximized = Some(client.geometry);
+            }
+        }
+        client.border_override = rules::border_width(&self.config.rules, client);
+        client.opacity = rules::opacity(&self.config.rules, client);
+        let fullscreen = rules::fullscreen(&self.config.rules, client).unwrap_or(fullscreen);
+        let no_focus = rules::no_focus(&self.config.rules, client);
+        client.above = above;
+        client.below = below;
+        client.states = Some(states);
+        self.set_urgent(window, urgent);
+
+        // Mapped windows start on top.
+        self.stack.retain(|w| *w != window);
+        self.stack.push(window);
+        if !self.client_list.contains(&window) {
+            self.client_list.push(window);
+            self.publish_client_list();
+        }
+        self.publish_frame_extents(window);
+
+        let unmanaged = dock || desktop;
+        if !unmanaged && !self.workspaces.iter().any(|ws| ws.contains(window)) {
+            // New windows are not kept hidden behind the desktop.
+            self.set_showing_desktop(false);
+            self.grab_focus_button(window, true);
+            self.update_border(window);
+            self.update_opacity(window);
+            if !self.swallow(window) {
+                self.manage(window);
+            }
+        }
+
+        // Windows assigned to hidden workspaces are mapped once shown.
+        if unmanaged || self.visible_windows().contains(&window) {
+            let cookie = self
+                .conn
+                .send_request_checked(&xcb::x::MapWindow { window });
+
+            let result = self.conn.check_request(cookie);
+            if result.is_err() {
+                error!("MapRequest failed {:?}", result);
+                for workspace in &mut self.workspaces {
+                    workspace.remove(window);
                 }
+                return;
             }
+        }
 
-            keybind.update_keycodes(keycodes);
+        let state = if unmanaged || self.visible_windows().contains(&window) {
+            properties::WM_STATE_NORMAL
+        } else {
+            properties::WM_STATE_ICONIC
+        };
+        self.set_icccm_state(window, state);
+
+        if unmanaged {
+            debug!("Dock or desktop mapped: {:?} {:?}", window, struts);
+            if struts.is_some() {
+                self.update_work_areas();
+            }
+            self.restack();
+            return;
         }
 
-        self.conn.flush().expect("Flushed");
+        if fullscreen {
+            self.set_fullscreen(window, true);
+        }
+        if !no_focus && self.visible_windows().contains(&window) {
+            self.focus(window);
+        }
     }
-}
 
-fn main() {
-    env_logger::init();
-    info!("Welcome to {}", env!("CARGO_PKG_NAME"));
+    /// Applies the rules the client matches since its title changed from `old_title`.
+    fn apply_title_rules(&mut self, window: x::Window, old_title: &str) {
+        let client = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if !client.dock && !client.desktop => client,
+            _ => return,
+        };
+        let rules = rules::matching_new_title(&self.config.rules, client, old_title);
+        if rules.is_empty() {
+            return;
+        }
+
+        debug!("Applying rules to {:?} titled {:?}", window, client.title);
+        if let Some(floating) = rules::floating(&rules, client) {
+            client.floating = floating;
+        }
+        if let Some(border_width) = rules::border_width(&rules, client) {
+            client.border_override = Some(border_width);
+        }
+        if let Some(opacity) = rules::opacity(&rules, client) {
+            client.opacity = Some(opacity);
+        }
+        let fullscreen = rules::fullscreen(&rules, client);
+        let target = match rules::workspace(&rules, client) {
+            Some((name, switch)) => Some((WorkspaceTarget::Name(name.to_owned()), switch)),
+            None => rules::monitor(&rules, client)
+                .and_then(|output| self.output_monitor(output))
+                .map(|monitor| {
+                    (
+                        WorkspaceTarget::Index(self.monitors[monitor].workspace),
+                        false,
+                    )
+                }),
+        };
+
+        if let Some(fullscreen) = fullscreen {
+            self.set_fullscreen(window, fullscreen);
+        }
+        if let Some((target, switch)) = target {
+            match self.workspace_index(&target) {
+                Ok(index) => self.move_to_workspace(window, index, Some(switch)),
+                Err(e) => error!("Workspace rule failed: {}", e),
+            }
+        }
+        self.update_opacity(window);
+        self.arrange();
+    }
+
+    /// Index of the monitor showing the RandR primary output, if there is one.
+    fn primary_monitor(&self) -> Option<usize> {
+        self.monitors.iter().position(|monitor| monitor.primary)
+    }
+
+    /// Index of the monitor showing the RandR output.
+    fn output_monitor(&self, output: &str) -> Option<usize> {
+        self.monitors
+            .iter()
+            .position(|monitor| monitor.output.as_deref() == Some(output))
+    }
+
+    /// Lets a new client take the place of the terminal it was started from, when the rules
+    /// allow it. The terminal is hidden until the client goes away.
+    fn swallow(&mut self, window: x::Window) -> bool {
+        let pid = match self.clients.get(&window.resource_id()) {
+            Some(client)
+                if client.transient_for.is_none()
+                    && rules::swallows(&self.config.rules, client) =>
+            {
+                match client.pid {
+                    Some(pid) => pid,
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+
+        let terminal = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| ws.clients.iter().copied())
+            .filter(|terminal| !self.minimized.contains(terminal))
+            .find(|terminal| {
+                self.clients
+                    .get(&terminal.resource_id())
+                    .is_some_and(|client| {
+                        client.swallowed.is_none()
+                            && rules::is_terminal(&self.config.rules, client)
+                            && client
+                                .pid
+                                .is_some_and(|ancestor| process::is_descendant(pid, ancestor))
+                    })
+            });
+        let terminal = match terminal {
+            Some(terminal) => terminal,
+            None => return false,
+        };
+
+        debug!("Window {:?} swallows terminal {:?}", window, terminal);
+        let visible = self.visible_windows().contains(&terminal);
+        for workspace in &mut self.workspaces {
+            workspace.replace(terminal, window);
+        }
+        if let Some(client) = self.clients.get_mut(&window.resource_id()) {
+            client.swallowed = Some(terminal);
+        }
+        self.store_workspaces(window);
+        self.store_workspaces(terminal);
+
+        if visible {
+            self.hide_window(terminal);
+        }
+        true
+    }
+
+    /// Puts back the terminal swallowed by a client that is going away, in its place.
+    fn unswallow(&mut self, window: x::Window) -> bool {
+        let terminal = match self
+            .clients
+            .get_mut(&window.resource_id())
+            .and_then(|client| client.swallowed.take())
+        {
+            Some(terminal) if self.clients.contains_key(&terminal.resource_id()) => terminal,
+            _ => return false,
+        };
+
+        let mut replaced = false;
+        for workspace in &mut self.workspaces {
+            replaced |= workspace.replace(window, terminal);
+        }
+        if !replaced {
+            return false;
+        }
+
+        debug!("Terminal {:?} restored from {:?}", terminal, window);
+        self.store_workspaces(terminal);
+
+        if self.visible_windows().contains(&terminal) {
+            self.conn.send_request(&x::MapWindow { window: terminal });
+            self.set_icccm_state(terminal, properties::WM_STATE_NORMAL);
+            if self.focused == Some(window) {
+                self.focus(terminal);
+            }
+        }
+        true
+    }
+
+    /// Override-redirect windows (menus, tooltips), InputOnly windows and transient popups
+    /// like notifications are never managed.
+    fn is_manageable(&self, window: x::Window) -> bool {
+        let cookie = self.conn.send_request(&x::GetWindowAttributes { window });
+        match self.conn.wait_for_reply(cookie) {
+            Ok(attributes) => {
+                !attributes.override_redirect()
+                    && attributes.class() != x::WindowClass::InputOnly
+                    && !self.is_popup(window)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the _NET_WM_WINDOW_TYPE marks a short-lived window shown above the others.
+    fn is_popup(&self, window: x::Window) -> bool {
+        let popups = [
+            self.atoms.net_wm_window_type_notification,
+            self.atoms.net_wm_window_type_tooltip,
+            self.atoms.net_wm_window_type_dropdown_menu,
+            self.atoms.net_wm_window_type_popup_menu,
+            self.atoms.net_wm_window_type_splash,
+        ];
+        properties::get_atoms(&self.conn, window, self.atoms.net_wm_window_type)
+            .iter()
+            .any(|r#type| popups.contains(r#type))
+    }
+
+    /// Manages windows that existed before the window manager started, e.g. after a restart.
+    /// Viewable and iconic top-level windows are adopted, override-redirect, InputOnly and popup
+    /// ones are left alone.
+    fn adopt_windows(&mut self) {
+        let cookie = self.conn.send_request(&x::QueryTree { window: self.root });
+        let children = match self.conn.wait_for_reply(cookie) {
+            Ok(reply) => reply.children().to_vec(),
+            Err(e) => {
+                error!("QueryTree failed: {:?}", e);
+                return;
+            }
+        };
+
+        let mut adopted = HashSet::new();
+        for window in children {
+            let cookie = self.conn.send_request(&x::GetWindowAttributes { window });
+            let attributes = match self.conn.wait_for_reply(cookie) {
+                Ok(attributes) => attributes,
+                Err(_) => continue,
+            };
+
+            let iconic = properties::get_wm_state(&self.conn, &self.atoms, window)
+                == Some(properties::WM_STATE_ICONIC);
+            if attributes.override_redirect()
+                || attributes.class() == x::WindowClass::InputOnly
+                || self.is_popup(window)
+                || (attributes.map_state() != x::MapState::Viewable && !iconic)
+            {
+                continue;
+            }
+
+            let cookie = self.conn.send_request(&x::GetGeometry {
+                drawable: x::Drawable::Window(window),
+            });
+            let mut client = Client::new(window);
+            if let Ok(reply) = self.conn.wait_for_reply(cookie) {
+                client.geometry = Rect::new(
+                    reply.x().into(),
+                    reply.y().into(),
+                    reply.width().into(),
+                    reply.height().into(),
+                );
+                client.border_width = reply.border_width().into();
+            }
+            self.clients.insert(window.resource_id(), client);
+
+            debug!("Adopting window {:?}", window);
+            self.map_client(window);
+            if attributes.map_state() == x::MapState::Viewable {
+                adopted.insert(window);
+            }
+        }
+
+        // Viewable windows are already mapped, those on hidden workspaces get unmapped.
+        self.update_visibility(&adopted);
+        self.arrange();
+    }
+
+    /// Adds a new window to a workspace, following the rules matching its class.
+    fn manage(&mut self, window: x::Window) {
+        let client = match self.clients.get(&window.resource_id()) {
+            Some(client) => client,
+            None => return,
+        };
+        let transient_for = client.transient_for;
+        let policy = rules::insertion_policy(&self.config.rules, client);
+        let assigned = rules::workspace(&self.config.rules, client)
+            .map(|(name, switch)| (WorkspaceTarget::Name(name.to_owned()), switch));
+        let monitor = rules::monitor(&self.config.rules, client)
+            .and_then(|output| self.output_monitor(output));
+
+        // Windows managed before a restart go back to their workspaces.
+        let stored = properties::get_string_list(
+            &self.conn,
+            window,
+            self.atoms.rwm_workspaces,
+            self.atoms.utf8_string,
+        );
+        let mut restored = false;
+        for name in stored {
+            let index = self.qualified_workspace_index(&name);
+            let tiled = self.is_tiled(window);
+            self.workspaces[index].add(window, tiled, policy, None);
+            restored = true;
+        }
+
+        // Clients started again by the session manager too, workspace rules win over
+        // placements matched by class alone.
+        let by_class = assigned.is_none();
+        let identity = self.window_identity(window);
+        if let Some(name) = self
+            .placements
+            .as_mut()
+            .filter(|_| !restored)
+            .and_then(|placements| placements.take(&identity, by_class, Instant::now()))
+        {
+            let index = self.qualified_workspace_index(&name);
+            let tiled = self.is_tiled(window);
+            self.workspaces[index].add(window, tiled, policy, None);
+            restored = true;
+        }
+
+        if restored {
+            self.store_workspaces(window);
+            return;
+        }
+
+        let requested = properties::get_desktop(&self.conn, &self.atoms, window);
+        let launched = properties::get_startup_id(&self.conn, &self.atoms, window)
+            .filter(|_| assigned.is_none())
+            .and_then(|id| {
+                self.launches
+                    .workspace(&id, Instant::now())
+                    .map(str::to_owned)
+            })
+            .map(|name| self.qualified_workspace_index(&name));
+        let by_rule = assigned.is_some();
+        let (index, switch) = match assigned {
+            Some((target, switch)) => match self.workspace_index(&target) {
+                Ok(index) => (index, switch),
+                Err(e) => {
+                    error!("Workspace rule failed: {}", e);
+                    (self.current_workspace(), false)
+                }
+            },
+            // Dialogs open next to the window they belong to, applications launched by
+            // a keybind on the workspace they were launched from, other clients may ask
+            // for a desktop themselves and end up on the primary monitor otherwise.
+            None => {
+                let index = transient_for
+                    .and_then(|parent| self.workspaces.iter().position(|ws| ws.contains(parent)))
+                    .or(launched)
+                    .or(requested
+                        .map(|desktop| desktop as usize)
+                        .filter(|index| *index < self.workspaces.len()))
+                    .or(monitor.map(|monitor| self.monitors[monitor].workspace))
+                    .or(self
+                        .primary_monitor()
+                        .map(|monitor| self.monitors[monitor].workspace))
+                    .unwrap_or(self.current_workspace());
+                (index, false)
+            }
+        };
+
+        let focused = self.focused;
+        let tiled = self.is_tiled(window);
+        self.workspaces[index].add(window, tiled, policy, focused);
+        if requested == Some(ALL_DESKTOPS) && !by_rule {
+            if let Some(client) = self.clients.get_mut(&window.resource_id()) {
+                client.sticky = true;
+            }
+            self.set_wm_state(window, self.atoms.net_wm_state_sticky, true);
+        }
+        self.store_workspaces(window);
+
+        // Floating clients are moved to the middle of the assigned monitor.
+        if let Some(area) = monitor.map(|monitor| self.monitors[monitor].area) {
+            if let Some(client) = self.clients.get(&window.resource_id()) {
+                if client.floating && !area.contains(client.geometry.center()) {
+                    let (x, y) = area.center();
+                    let frame = client.frame();
+                    self.move_window(
+                        window,
+                        (x - frame.width as i32 / 2, y - frame.height as i32 / 2),
+                    );
+                }
+            }
+        }
+
+        if self.monitors.iter().any(|m| m.shows(index)) {
+            return;
+        }
+
+        let activation = if switch {
+            ActivationPolicy::Switch
+        } else {
+            let time = properties::get_user_time(&self.conn, &self.atoms, window).unwrap_or(0);
+            activation::decide(self.config.activation_policy, time, self.user_time)
+        };
+
+        match activation {
+            ActivationPolicy::Switch => {
+                if let Err(e) = self.view_workspace(index) {
+                    error!("Switching to assigned workspace failed: {}", e);
+                }
+            }
+            ActivationPolicy::Urgent => {
+                debug!("Window {:?} marked urgent", window);
+                self.set_urgent(window, true);
+            }
+            ActivationPolicy::Ignore => {}
+        }
+    }
+
+    /// Remembers the workspaces of the window on the window itself, to survive restarts.
+    fn store_workspaces(&self, window: x::Window) {
+        let names: Vec<u8> = (0..self.workspaces.len())
+            .filter(|index| self.workspaces[*index].contains(window))
+            .flat_map(|index| {
+                self.qualified_name(index)
+                    .into_bytes()
+                    .into_iter()
+                    .chain(std::iter::once(0))
+            })
+            .collect();
+
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.rwm_workspaces,
+            r#type: self.atoms.utf8_string,
+            data: &names,
+        });
+
+        self.publish_window_desktop(window);
+    }
+
+    /// Sets _NET_WM_DESKTOP of the window to the index of its (first) workspace.
+    fn publish_window_desktop(&self, window: x::Window) {
+        let sticky = self
+            .clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.sticky);
+        let desktop = match self.workspaces.iter().position(|ws| ws.contains(window)) {
+            _ if sticky => ALL_DESKTOPS,
+            Some(index) => index as u32,
+            None => return,
+        };
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.net_wm_desktop,
+            r#type: x::ATOM_CARDINAL,
+            data: &[desktop],
+        });
+    }
+
+    /// Sets _NET_FRAME_EXTENTS of the window, the border is the only decoration.
+    fn publish_frame_extents(&self, window: x::Window) {
+        if let Some(client) = self.clients.get(&window.resource_id()) {
+            self.set_frame_extents(window, client.border_width);
+        }
+    }
+
+    /// Answers _NET_REQUEST_FRAME_EXTENTS, sent before mapping by clients sizing themselves
+    /// for their frame, with the border the window would get if tiled on the current
+    /// workspace.
+    fn estimate_frame_extents(&self, window: x::Window) {
+        let types = properties::get_atoms(&self.conn, window, self.atoms.net_wm_window_type);
+        let undecorated = [
+            self.atoms.net_wm_window_type_dock,
+            self.atoms.net_wm_window_type_desktop,
+        ];
+        if types.iter().any(|t| undecorated.contains(t)) || self.is_popup(window) {
+            self.set_frame_extents(window, 0);
+            return;
+        }
+
+        let mut client = Client::new(window);
+        (client.instance, client.class) =
+            properties::get_wm_class(&self.conn, window).unwrap_or_default();
+        client.title = properties::get_title(&self.conn, &self.atoms, window).unwrap_or_default();
+        client.role =
+            properties::get_string_property(&self.conn, window, self.atoms.wm_window_role)
+                .unwrap_or_default();
+        client.motif_hints = properties::get_motif_hints(&self.conn, &self.atoms, window);
+        client.border_override = rules::border_width(&self.config.rules, &client);
+
+        let border_width = client
+            .forced_border_width()
+            .unwrap_or_else(|| self.workspace_border_width(self.workspace()));
+        self.set_frame_extents(window, border_width);
+    }
+
+    fn set_frame_extents(&self, window: x::Window, border_width: u32) {
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.net_frame_extents,
+            r#type: x::ATOM_CARDINAL,
+            data: &[border_width; 4],
+        });
+    }
+
+    /// Shows the previous (or next) workspace in the history of the current monitor,
+    /// skipping workspaces the monitor can no longer show.
+    pub fn navigate_workspace_history(&mut self, forward: bool) -> anyhow::Result<()> {
+        let workspaces = self.monitor_workspaces(self.current_monitor);
+
+        loop {
+            let history = &mut self.monitors[self.current_monitor].history;
+            let next = if forward {
+                history.forward()
+            } else {
+                history.back()
+            };
+
+            let index = match next {
+                Some(index) => index,
+                None => return Ok(()),
+            };
+
+            if workspaces.contains(&index) {
+                return self.view_workspace(index);
+            }
+        }
+    }
+
+    /// Shows a workspace by its global index, as requested by a pager.
+    fn view_desktop(&mut self, index: usize) {
+        let monitor = match self.workspaces.get(index) {
+            Some(workspace) => workspace.monitor,
+            None => return,
+        };
+
+        // Another monitor's workspace is shown there rather than pulled over.
+        if self.config.workspace_model == WorkspaceModel::PerMonitor
+            && monitor < self.monitors.len()
+        {
+            self.current_monitor = monitor;
+        }
+
+        self.set_group(self.workspaces[index].group);
+        if let Err(e) = self.view_workspace(index) {
+            error!("Switching desktop failed: {}", e);
+        }
+        self.publish_desktops();
+    }
+
+    /// Makes the group at `target` the active one.
+    pub fn switch_group(&mut self, target: &GroupTarget) -> anyhow::Result<()> {
+        let group = match target {
+            GroupTarget::Relative(offset) => (self.group as isize + offset)
+                .rem_euclid(self.config.group_count() as isize)
+                as usize,
+            GroupTarget::Name(name) => self
+                .config
+                .workspace_groups
+                .iter()
+                .position(|group| group == name)
+                .ok_or_else(|| anyhow::anyhow!("Group {} does not exist", name))?,
+        };
+
+        if group == self.group {
+            return Ok(());
+        }
+
+        self.set_group(group);
+
+        if let Some(name) = self.config.workspace_groups.get(group) {
+            debug!("Group {} activated", name);
+            let monitor = self.primary_monitor().unwrap_or(self.current_monitor);
+            let area = self.work_area(&self.monitors[monitor]);
+            if let Some(osd) = &mut self.osd {
+                osd.show(&self.conn, &self.config, name, area);
+            }
+        }
+
+        self.destroy_unused_workspaces();
+        self.publish_desktops();
+        self.arrange();
+        Ok(())
+    }
+
+    /// Activates `group`, every monitor showing the workspace of the group it showed last.
+    fn set_group(&mut self, group: usize) {
+        if group == self.group {
+            return;
+        }
+
+        let visible = self.visible_windows();
+        self.group = group;
+
+        for monitor in 0..self.monitors.len() {
+            let workspaces = self.monitor_workspaces(monitor);
+            let taken = |index: usize| {
+                self.monitors[..monitor]
+                    .iter()
+                    .any(|m| m.workspace == index)
+            };
+
+            let index = self.monitors[monitor]
+                .history
+                .latest(|index| workspaces.contains(&index) && !taken(index))
+                .or_else(|| workspaces.iter().copied().find(|index| !taken(*index)));
+            let index = match index {
+                Some(index) => index,
+                None => self.create_monitor_workspace(monitor),
+            };
+
+            self.workspaces[index].monitor = monitor;
+            let monitor = &mut self.monitors[monitor];
+            monitor.workspace = index;
+            monitor.combined.clear();
+            monitor.history.visit(index);
+        }
+
+        self.update_visibility(&visible);
+
+        self.refocus();
+    }
+
+    /// Name of the first number not used by any workspace of the current monitor.
+    pub fn unused_workspace_name(&self) -> String {
+        self.unused_monitor_workspace_name(self.current_monitor)
+    }
+
+    fn unused_monitor_workspace_name(&self, monitor: usize) -> String {
+        let workspaces = self.monitor_workspaces(monitor);
+
+        (1..)
+            .map(|number: usize| number.to_string())
+            .find(|name| {
+                workspaces
+                    .iter()
+                    .all(|ws| self.workspaces[*ws].name != *name)
+            })
+            .expect("Unused workspace number")
+    }
+
+    pub fn rename_workspace(&mut self, name: &str) -> anyhow::Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Workspace name cannot be empty");
+        }
+
+        let current = self.current_workspace();
+        let taken = self
+            .monitor_workspaces(self.current_monitor)
+            .into_iter()
+            .any(|index| index != current && self.workspaces[index].name == name);
+        if taken {
+            anyhow::bail!("Workspace {} already exists", name);
+        }
+
+        debug!("Workspace {} renamed to {}", self.workspace().name, name);
+        self.workspace_mut().name = name.to_owned();
+        for window in &self.workspace().clients {
+            self.store_workspaces(*window);
+        }
+        self.publish_desktops();
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Asks the user for a line of text with the prompt command, `on_answer` is called with
+    /// it unless the prompt is cancelled. Events are handled while the prompt is open.
+    pub fn prompt(
+        &mut self,
+        message: &str,
+        on_answer: impl FnOnce(&mut App, String) -> anyhow::Result<()> + 'static,
+    ) -> anyhow::Result<()> {
+        self.prompt_with_choices(message, &[], on_answer)
+    }
+
+    /// Like [`App::prompt`], offering `choices` (one per line) to pick from.
+    pub fn prompt_with_choices(
+        &mut self,
+        message: &str,
+        choices: &[String],
+        on_answer: impl FnOnce(&mut App, String) -> anyhow::Result<()> + 'static,
+    ) -> anyhow::Result<()> {
+        if self.prompt.is_some() {
+            anyhow::bail!("A prompt is already open");
+        }
+
+        self.prompt = Some(Prompt::spawn(
+            &self.config.prompt_command,
+            message,
+            choices,
+            Box::new(on_answer),
+        )?);
+        Ok(())
+    }
+
+    /// Reads the output of the open prompt, answering it once the command exits.
+    fn process_prompt(&mut self) {
+        if !self.prompt.as_mut().is_some_and(Prompt::read) {
+            return;
+        }
+
+        let (answer, on_answer) = self.prompt.take().expect("Open prompt").finish();
+        if let Some(answer) = answer {
+            if let Err(e) = on_answer(self, answer) {
+                error!("Prompt command failed: {:?}", e);
+            }
+        }
+    }
+
+    /// Starts `command` with a startup id (DESKTOP_STARTUP_ID), the windows it opens go to
+    /// the current workspace even if another one is shown by then.
+    pub fn spawn(&mut self, command: &mut std::process::Command) -> anyhow::Result<()> {
+        let workspace = self.qualified_name(self.current_workspace());
+        let id = self
+            .launches
+            .start(workspace, self.event_time, Instant::now());
+
+        command.env("DESKTOP_STARTUP_ID", &id).spawn()?;
+        Ok(())
+    }
+
+    /// Swaps the current workspace with its neighbour at `offset` in the workspace order.
+    pub fn reorder_workspace(&mut self, offset: isize) {
+        let current = self.current_workspace();
+        let other = match self.workspace_index(&WorkspaceTarget::Relative(offset)) {
+            Ok(other) if other != current => other,
+            _ => return,
+        };
+
+        self.workspaces.swap(current, other);
+
+        let swap = |index: &mut usize| {
+            if *index == current {
+                *index = other;
+            } else if *index == other {
+                *index = current;
+            }
+        };
+
+        for monitor in &mut self.monitors {
+            swap(&mut monitor.workspace);
+            monitor.combined.iter_mut().for_each(swap);
+            monitor.history.update(|mut entry| {
+                swap(&mut entry);
+                Some(entry)
+            });
+        }
+
+        self.publish_desktops();
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Rebuilds the monitors after the outputs changed. Monitors keep their workspaces by
+    /// output name, the workspaces of disappeared outputs move to the primary monitor.
+    fn update_monitors(&mut self) {
+        let screen_area = match screen_size(&self.conn, self.root) {
+            Ok(area) => area,
+            Err(e) => {
+                error!("Querying the screen size failed: {}", e);
+                return;
+            }
+        };
+
+        let mut outputs = query_outputs(&self.conn, self.root, &self.config);
+        if outputs.is_empty() {
+            outputs.push(randr::Output {
+                name: String::new(),
+                area: screen_area,
+                primary: false,
+                dpi: None,
+                rotation: 0,
+            });
+        }
+
+        let unchanged = screen_area == self.screen_area
+            && outputs.len() == self.monitors.len()
+            && outputs.iter().zip(&self.monitors).all(|(output, monitor)| {
+                monitor.output.as_deref().unwrap_or_default() == output.name
+                    && monitor.area == output.area
+                    && monitor.primary == output.primary
+                    && monitor.scale == output_scale(&self.config, output)
+                    && monitor.rotation == output.rotation
+            });
+        if unchanged {
+            return;
+        }
+
+        info!("Outputs changed: {:?}", outputs);
+        let visible = self.visible_windows();
+        let primary = |monitor: &Monitor| monitor.primary.then(|| monitor.output.clone());
+        let previous_primary = self.monitors.iter().find_map(primary);
+
+        // Monitors are matched by output name first, the remaining ones are reused in order.
+        let mut old: Vec<Option<Monitor>> = self.monitors.drain(..).map(Some).collect();
+        let mut matched: Vec<Option<usize>> = outputs
+            .iter()
+            .map(|output| {
+                old.iter().position(|monitor| {
+                    monitor.as_ref().and_then(|m| m.output.as_deref()) == Some(&output.name)
+                })
+            })
+            .collect();
+        for (index, output) in outputs.iter().enumerate() {
+            if matched[index].is_some() {
+                continue;
+            }
+            matched[index] = (0..old.len()).find(|old_index| {
+                !matched.contains(&Some(*old_index))
+                    && old[*old_index].as_ref().is_some_and(|m| {
+                        !outputs.iter().any(|o| m.output.as_ref() == Some(&o.name))
+                    })
+            });
+            trace!("Output {} takes monitor {:?}", output.name, matched[index]);
+        }
+
+        let mut remap = vec![None; old.len()];
+        let mut added = Vec::new();
+        let mut turned = Vec::new();
+        for (index, output) in outputs.into_iter().enumerate() {
+            let mut monitor = match matched[index] {
+                Some(old_index) => {
+                    remap[old_index] = Some(index);
+                    old[old_index].take().unwrap()
+                }
+                None => {
+                    added.push(index);
+                    Monitor::new(output.area, 0)
+                }
+            };
+            let portrait = monitor.portrait();
+            monitor.scale = output_scale(&self.config, &output);
+            monitor.output = (!output.name.is_empty()).then_some(output.name);
+            monitor.area = output.area;
+            monitor.primary = output.primary;
+            monitor.rotation = output.rotation;
+            if monitor.portrait() != portrait && !added.contains(&index) {
+                turned.push(index);
+            }
+            self.monitors.push(monitor);
+        }
+
+        let current_primary = self.monitors.iter().find_map(primary);
+        if current_primary != previous_primary {
+            info!("Primary output changed to {:?}", current_primary.flatten());
+        }
+
+        let fallback = self.primary_monitor().unwrap_or_default();
+        for workspace in &mut self.workspaces {
+            workspace.monitor = remap
+                .get(workspace.monitor)
+                .copied()
+                .flatten()
+                .unwrap_or(fallback);
+        }
+        self.current_monitor = remap
+            .get(self.current_monitor)
+            .copied()
+            .flatten()
+            .unwrap_or(fallback);
+        self.screen_area = screen_area;
+
+        // Rotated monitors switch between the portrait layout and the usual one.
+        for index in turned {
+            let monitor = &self.monitors[index];
+            debug!("Monitor {} rotated to {} degrees", index, monitor.rotation);
+
+            for workspace in &mut self.workspaces {
+                if workspace.monitor != index {
+                    continue;
+                }
+                if !monitor.portrait()
+                    && self.config.portrait_layout.as_deref() == Some(workspace.layout().name())
+                {
+                    workspace.reset_layout();
+                }
+                apply_output_layout(&self.config, workspace, monitor);
+            }
+        }
+
+        // New monitors show a workspace no other monitor shows.
+        for index in added {
+            let workspace = self
+                .monitor_workspaces(index)
+                .into_iter()
+                .find(|ws| !self.monitors.iter().any(|m| m.shows(*ws)))
+                .unwrap_or_else(|| self.create_monitor_workspace(index));
+            let monitor = &mut self.monitors[index];
+            monitor.workspace = workspace;
+            monitor.history = History::new(workspace);
+            self.workspaces[workspace].monitor = index;
+        }
+
+        self.rehome_workspaces();
+        self.rescue_floating_clients();
+        self.destroy_unused_workspaces();
+        self.update_visibility(&visible);
+        self.publish_desktops();
+        self.update_work_areas();
+        self.draw_backgrounds();
+        self.bars.update(&self.config.bar_command, &self.monitors);
+        self.refocus();
+    }
+
+    /// Draws the configured background of every monitor on the root window, leaving it to
+    /// other programs without any configured.
+    fn draw_backgrounds(&self) {
+        let background = |monitor: &Monitor| {
+            monitor
+                .output
+                .as_ref()
+                .and_then(|output| self.config.output_backgrounds.get(output))
+                .or(self.config.background.as_ref())
+        };
+        let backgrounds: Vec<(Rect, Background)> = self
+            .monitors
+            .iter()
+            .filter_map(|monitor| Some((monitor.area, background(monitor)?.clone())))
+            .collect();
+        if backgrounds.is_empty() {
+            return;
+        }
+
+        if let Err(e) = wallpaper::set(&backgrounds) {
+            warn!("Failed to set the root background: {}", e);
+        }
+    }
+
+    /// Sets the background of the current monitor's output, or the default background of
+    /// every monitor for unnamed outputs.
+    pub fn set_background(&mut self, background: Background) {
+        match self.monitors[self.current_monitor].output.clone() {
+            Some(output) => {
+                self.config.output_backgrounds.insert(output, background);
+            }
+            None => self.config.background = Some(background),
+        }
+        self.draw_backgrounds();
+    }
+
+    /// Moves floating clients left outside of every monitor to the middle of the monitor
+    /// of their workspace.
+    fn rescue_floating_clients(&self) {
+        for workspace in &self.workspaces {
+            let area = self.monitors[workspace.monitor].area;
+
+            for window in &workspace.clients {
+                let client = match self.clients.get(&window.resource_id()) {
+                    Some(client) if client.floating => client,
+                    _ => continue,
+                };
+                let center = client.frame().center();
+                if self.monitors.iter().any(|m| m.area.contains(center)) {
+                    continue;
+                }
+
+                let (x, y) = area.center();
+                let frame = client.frame();
+                debug!("Floating window {:?} moved back on screen", window);
+                self.move_window(
+                    *window,
+                    (x - frame.width as i32 / 2, y - frame.height as i32 / 2),
+                );
+            }
+        }
+    }
+
+    /// Moves workspaces pinned to an output onto the monitor showing that output,
+    /// or onto the primary monitor while the output is disconnected.
+    fn rehome_workspaces(&mut self) {
+        for index in 0..self.workspaces.len() {
+            let output = match self
+                .config
+                .workspace_outputs
+                .get(&self.workspaces[index].name)
+            {
+                Some(output) => output,
+                None => continue,
+            };
+
+            let monitor = self
+                .monitors
+                .iter()
+                .position(|m| m.output.as_ref() == Some(output))
+                .or(self.primary_monitor())
+                .unwrap_or_default();
+
+            if self.workspaces[index].monitor != monitor {
+                debug!(
+                    "Workspace {} moved to monitor {} ({})",
+                    self.workspaces[index].name, monitor, output
+                );
+                self.move_workspace_to_monitor(index, monitor, false);
+            }
+        }
+    }
+
+    /// Makes the monitor at `target` the current one and focuses the client focused there
+    /// last.
+    pub fn focus_monitor(&mut self, target: &MonitorTarget) -> anyhow::Result<()> {
+        let monitor = match target {
+            MonitorTarget::Direction(direction) => {
+                match monitor::adjacent(&self.monitors, self.current_monitor, *direction) {
+                    Some(monitor) => monitor,
+                    None => return Ok(()),
+                }
+            }
+            MonitorTarget::Relative(offset) => (self.current_monitor as isize + offset)
+                .rem_euclid(self.monitors.len() as isize)
+                as usize,
+            MonitorTarget::Output(name) => self
+                .output_monitor(name)
+                .ok_or_else(|| anyhow::anyhow!("Output {} is not connected", name))?,
+        };
+
+        if monitor == self.current_monitor {
+            return Ok(());
+        }
+
+        debug!("Monitor {} focused", monitor);
+        self.current_monitor = monitor;
+        self.publish_desktops();
+        self.refocus();
+        Ok(())
+    }
+
+    /// Moves the current workspace to the monitor at `offset`, keeping it focused there.
+    pub fn move_workspace_to_adjacent_monitor(&mut self, offset: isize) {
+        let count = self.monitors.len() as isize;
+        let monitor = (self.current_monitor as isize + offset).rem_euclid(count) as usize;
+        if monitor == self.current_monitor {
+            return;
+        }
+
+        let index = self.current_workspace();
+        self.move_workspace_to_monitor(index, monitor, true);
+        self.current_monitor = monitor;
+        self.publish_desktops();
+
+        self.refocus();
+        self.arrange();
+    }
+
+    /// Gives the workspace to another monitor, shown there if `show` is set.
+    /// Monitors that were showing it switch to one of their hidden workspaces.
+    fn move_workspace_to_monitor(&mut self, index: usize, monitor: usize, show: bool) {
+        let visible = self.visible_windows();
+
+        self.workspaces[index].monitor = monitor;
+
+        for other in 0..self.monitors.len() {
+            self.monitors[other]
+                .combined
+                .retain(|combined| *combined != index);
+
+            if other == monitor || self.monitors[other].workspace != index {
+                continue;
+            }
+
+            let replacement = self
+                .monitor_workspaces(other)
+                .into_iter()
+                .find(|ws| *ws != index && !self.monitors.iter().any(|m| m.shows(*ws)));
+
+            let replacement = match replacement {
+                Some(replacement) => replacement,
+                None => self.create_monitor_workspace(other),
+            };
+            self.monitors[other].workspace = replacement;
+            self.workspaces[replacement].monitor = other;
+        }
+
+        if show {
+            self.monitors[monitor].workspace = index;
+            self.monitors[monitor].combined.clear();
+        }
+
+        self.update_visibility(&visible);
+        self.destroy_unused_workspaces();
+        self.publish_desktops();
+        self.arrange();
+    }
+
+    /// Adds a non-persistent workspace with an unused name to `monitor` in the active group.
+    fn create_monitor_workspace(&mut self, monitor: usize) -> usize {
+        let name = self.unused_monitor_workspace_name(monitor);
+        let position = self.monitor_workspaces(monitor).len();
+        let mut workspace = create_workspace(&self.config, monitor, self.group, position, name);
+        workspace.persistent = false;
+        apply_output_layout(&self.config, &mut workspace, &self.monitors[monitor]);
+        self.workspaces.push(workspace);
+        self.workspaces.len() - 1
+    }
+
+    /// Indices of the workspaces of the active group `monitor` can show.
+    fn monitor_workspaces(&self, monitor: usize) -> Vec<usize> {
+        self.group_workspaces(monitor, self.group)
+    }
+
+    fn group_workspaces(&self, monitor: usize, group: usize) -> Vec<usize> {
+        (0..self.workspaces.len())
+            .filter(|index| {
+                let workspace = &self.workspaces[*index];
+                workspace.group == group
+                    && (self.config.workspace_model == WorkspaceModel::Shared
+                        || workspace.monitor == monitor)
+            })
+            .collect()
+    }
+
+    /// Name identifying the workspace across groups, prefixed by its group if there are any.
+    fn qualified_name(&self, index: usize) -> String {
+        let workspace = &self.workspaces[index];
+        match self.config.workspace_groups.get(workspace.group) {
+            Some(group) => format!("{}/{}", group, workspace.name),
+            None => workspace.name.clone(),
+        }
+    }
+
+    /// Resolves a name returned by [`App::qualified_name`], creating the workspace if needed.
+    fn qualified_workspace_index(&mut self, name: &str) -> usize {
+        let group = name.split_once('/').and_then(|(group, name)| {
+            self.config
+                .workspace_groups
+                .iter()
+                .position(|g| g == group)
+                .map(|group| (group, name))
+        });
+
+        match group {
+            Some((group, name)) => self.named_workspace(group, name),
+            None => self.named_workspace(self.group, name),
+        }
+    }
+
+    /// Finds the workspace called `name` in `group` on the current monitor, creating it
+    /// if it does not exist yet.
+    fn named_workspace(&mut self, group: usize, name: &str) -> usize {
+        let workspaces = self.group_workspaces(self.current_monitor, group);
+
+        if let Some(index) = workspaces
+            .iter()
+            .copied()
+            .find(|index| self.workspaces[*index].name == name)
+        {
+            return index;
+        }
+
+        let index = self.workspaces.len();
+        let mut workspace = create_workspace(
+            &self.config,
+            self.current_monitor,
+            group,
+            workspaces.len(),
+            name.to_owned(),
+        );
+        workspace.persistent = false;
+        self.workspaces.push(workspace);
+        self.publish_desktops();
+
+        debug!("Workspace {} created", name);
+        index
+    }
+
+    /// Resolves `target` among the workspaces of the current monitor, creating named
+    /// workspaces that do not exist yet.
+    pub fn workspace_index(&mut self, target: &WorkspaceTarget) -> anyhow::Result<usize> {
+        let workspaces = self.monitor_workspaces(self.current_monitor);
+
+        match target {
+            WorkspaceTarget::Index(index) => match workspaces.get(*index) {
+                Some(index) => Ok(*index),
+                None => anyhow::bail!("Workspace {} does not exist", index),
+            },
+            WorkspaceTarget::Relative(offset) => {
+                let current = self.current_workspace();
+                let position = workspaces
+                    .iter()
+                    .position(|index| *index == current)
+                    .unwrap_or_default();
+                let position = (position as isize + offset).rem_euclid(workspaces.len() as isize);
+                Ok(workspaces[position as usize])
+            }
+            WorkspaceTarget::Name(name) => Ok(self.named_workspace(self.group, name)),
+        }
+    }
+
+    /// Sends the window to the workspace, switching there too if `follow` (or the config
+    /// when None) says so.
+    pub fn move_to_workspace(&mut self, window: x::Window, index: usize, follow: Option<bool>) {
+        let current = self.current_workspace();
+        let source = if self.workspaces[current].contains(window) {
+            Some(current)
+        } else {
+            self.workspaces.iter().position(|ws| ws.contains(window))
+        };
+        match source {
+            Some(source) if source != index => self.workspaces[source].remove(window),
+            _ => return,
+        };
+
+        let visible = self.visible_windows();
+        let tiled = self.is_tiled(window);
+        self.workspaces[index].add(window, tiled, None, None);
+        self.store_workspaces(window);
+
+        if follow.unwrap_or(self.config.follow_moved_window) {
+            // Focused first, so the view switch keeps it focused.
+            self.workspaces[index].focus(window);
+            if let Err(e) = self.view_workspace(index) {
+                error!("Following moved window failed: {}", e);
+            }
+            return;
+        }
+        self.update_visibility(&visible);
+
+        if !self.visible_windows().contains(&window) {
+            self.refocus();
+        }
+
+        self.destroy_unused_workspaces();
+        self.arrange();
+    }
+
+    /// Moves the window to the desktop (workspace index) asked for with _NET_WM_DESKTOP,
+    /// or makes it sticky for all of them.
+    fn handle_desktop_request(&mut self, window: x::Window, desktop: u32) {
+        if desktop == ALL_DESKTOPS {
+            self.set_sticky(window, true);
+            return;
+        }
+
+        let index = desktop as usize;
+        if index >= self.workspaces.len() {
+            return;
+        }
+
+        self.set_sticky(window, false);
+        self.move_to_workspace(window, index, Some(false));
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Adds the window to the workspace, or removes it unless it is its only workspace.
+    pub fn toggle_tag(&mut self, window: x::Window, index: usize) {
+        let tags = self
+            .workspaces
+            .iter()
+            .filter(|ws| ws.contains(window))
+            .count();
+        if tags == 0 {
+            return;
+        }
+
+        let visible = self.visible_windows();
+        let tiled = self.is_tiled(window);
+
+        let workspace = &mut self.workspaces[index];
+        if !workspace.contains(window) {
+            workspace.add(window, tiled, None, None);
+        } else if tags > 1 {
+            workspace.remove(window);
+        }
+
+        self.store_workspaces(window);
+        self.update_visibility(&visible);
+
+        if !self.visible_windows().contains(&window) {
+            self.refocus();
+        }
+
+        self.arrange();
+    }
+
+    /// Shows or hides the clients of another workspace along with the current one.
+    pub fn toggle_view(&mut self, index: usize) -> anyhow::Result<()> {
+        if index >= self.workspaces.len() {
+            anyhow::bail!("Workspace {} does not exist", index);
+        }
+
+        if index == self.current_workspace()
+            || self
+                .monitors
+                .iter()
+                .enumerate()
+                .any(|(i, m)| i != self.current_monitor && m.shows(index))
+        {
+            return Ok(());
+        }
+
+        let visible = self.visible_windows();
+
+        let combined = &mut self.monitors[self.current_monitor].combined;
+        match combined.iter().position(|combined| *combined == index) {
+            Some(position) => {
+                combined.remove(position);
+            }
+            None => combined.push(index),
+        }
+
+        self.update_visibility(&visible);
+        self.destroy_unused_workspaces();
+        self.arrange();
+        Ok(())
+    }
+
+    /// Clients shown on the monitor: those of its workspace, then those of the combined ones,
+    /// then the sticky ones. Minimized clients are left out, all of them while showing the
+    /// desktop.
+    fn monitor_clients(&self, monitor: &Monitor) -> Vec<x::Window> {
+        let mut clients: Vec<x::Window> = Vec::new();
+        if self.showing_desktop {
+            return clients;
+        }
+
+        let sticky = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| ws.clients.iter())
+            .filter(|window| self.is_sticky_on(**window, monitor));
+
+        for window in monitor
+            .workspaces()
+            .flat_map(|index| self.workspaces[index].clients.iter())
+            .chain(sticky)
+        {
+            if !clients.contains(window) && !self.minimized.contains(window) {
+                clients.push(*window);
+            }
+        }
+
+        clients
+    }
+
+    fn is_sticky_on(&self, window: x::Window, monitor: &Monitor) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| {
+                client.sticky
+                    && self
+                        .monitors
+                        .get(client.monitor)
+                        .is_some_and(|m| m.area == monitor.area)
+            })
+    }
+
+    fn visible_windows(&self) -> HashSet<x::Window> {
+        self.monitors
+            .iter()
+            .flat_map(|monitor| self.monitor_clients(monitor))
+            .collect()
+    }
+
+    /// Hides all clients to show the desktop, or shows them again.
+    fn set_showing_desktop(&mut self, showing: bool) {
+        if self.showing_desktop == showing {
+            return;
+        }
+
+        debug!("Showing the desktop: {}", showing);
+        let before = self.visible_windows();
+        self.showing_desktop = showing;
+        self.publish_showing_desktop();
+        self.update_visibility(&before);
+        self.refocus();
+        self.arrange();
+    }
+
+    /// Maps and unmaps clients whose visibility changed since `before`.
+    fn update_visibility(&mut self, before: &HashSet<x::Window>) {
+        let after = self.visible_windows();
+
+        for window in before.difference(&after) {
+            self.hide_window(*window);
+        }
+
+        for window in after.difference(before) {
+            self.conn
+                .send_request(&xcb::x::MapWindow { window: *window });
+            self.set_icccm_state(*window, properties::WM_STATE_NORMAL);
+        }
+    }
+
+    /// Unmaps a client without giving up managing it.
+    fn hide_window(&mut self, window: x::Window) {
+        *self.ignored_unmaps.entry(window.resource_id()).or_default() += 1;
+        self.conn.send_request(&xcb::x::UnmapWindow { window });
+        self.set_icccm_state(window, properties::WM_STATE_ICONIC);
+    }
+
+    /// Stops managing a client that unmapped its window.
+    fn withdraw(&mut self, window: x::Window) {
+        debug!("Window withdrawn: {:?}", window);
+
+        let mut removed = self.unswallow(window);
+        self.minimized.retain(|w| *w != window);
+        self.client_list.retain(|w| *w != window);
+        self.publish_client_list();
+
+        let mut had_struts = false;
+        if let Some(client) = self.clients.get_mut(&window.resource_id()) {
+            had_struts = client.struts.take().is_some();
+            client.dock = false;
+            client.popup = false;
+            client.states = None;
+        }
+
+        for workspace in &mut self.workspaces {
+            removed |= workspace.remove(window);
+        }
+        self.set_icccm_state(window, properties::WM_STATE_WITHDRAWN);
+        // A window mapped again starts over, only restarts keep its workspaces.
+        self.conn.send_request(&x::DeleteProperty {
+            window,
+            property: self.atoms.rwm_workspaces,
+        });
+
+        if self.focused == Some(window) {
+            self.refocus();
+        }
+
+        if removed {
+            self.destroy_unused_workspaces();
+        }
+        if had_struts {
+            self.publish_workarea();
+        }
+        self.arrange();
+    }
+
+    /// Drops every trace of a window that is gone or no longer a top-level window.
+    fn forget_window(&mut self, window: x::Window) {
+        let mut removed = self.unswallow(window);
+
+        self.ignored_unmaps.remove(&window.resource_id());
+        self.minimized.retain(|w| *w != window);
+        self.stack.retain(|w| *w != window);
+        if self.client_list.contains(&window) {
+            self.client_list.retain(|w| *w != window);
+            self.publish_client_list();
+        }
+        if let Some(pinger) = &mut self.pinger {
+            pinger.forget(window);
+        }
+        self.terminating.retain(|(w, ..)| *w != window);
+        let mut had_struts = false;
+        if let Some(client) = self.clients.remove(&window.resource_id()) {
+            trace!("Client forgotten: {:?} ({})", client.window, client.class);
+            had_struts = client.struts.is_some();
+        }
+        for client in self.clients.values_mut() {
+            if client.swallowed == Some(window) {
+                client.swallowed = None;
+            }
+        }
+
+        for workspace in &mut self.workspaces {
+            removed |= workspace.remove(window);
+        }
+
+        if self.focused == Some(window) {
+            self.refocus();
+        }
+
+        if removed {
+            self.destroy_unused_workspaces();
+        }
+        if had_struts {
+            self.update_work_areas();
+        } else if removed {
+            self.arrange();
+        }
+    }
+
+    /// Sets the ICCCM WM_STATE of a client.
+    fn set_icccm_state(&self, window: x::Window, state: u32) {
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.wm_state,
+            r#type: self.atoms.wm_state,
+            // No icon window.
+            data: &[state, 0],
+        });
+    }
+
+    /// Tells clients which EWMH hints are supported, and by which window manager.
+    fn publish_support(&self) {
+        self.set_root_property(
+            self.atoms.net_supporting_wm_check,
+            x::ATOM_WINDOW,
+            &[self.check_window],
+        );
+        self.set_root_property(
+            self.atoms.net_supported,
+            x::ATOM_ATOM,
+            &self.atoms.supported(),
+        );
+    }
+
+    fn publish_showing_desktop(&self) {
+        self.set_root_property(
+            self.atoms.net_showing_desktop,
+            x::ATOM_CARDINAL,
+            &[self.showing_desktop as u32],
+        );
+    }
+
+    /// Exports the workspaces as EWMH desktops for bars and pagers.
+    fn publish_desktops(&self) {
+        let names: Vec<u8> = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| ws.name.bytes().chain(std::iter::once(0)))
+            .collect();
+
+        // Workspaces are shown at the origin of their monitor.
+        let viewports: Vec<u32> = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| {
+                let area = self
+                    .monitors
+                    .get(ws.monitor)
+                    .map(|monitor| monitor.area)
+                    .unwrap_or_default();
+                [area.x as u32, area.y as u32]
+            })
+            .collect();
+
+        self.set_root_property(
+            self.atoms.net_number_of_desktops,
+            x::ATOM_CARDINAL,
+            &[self.workspaces.len() as u32],
+        );
+        self.set_root_property(
+            self.atoms.net_current_desktop,
+            x::ATOM_CARDINAL,
+            &[self.current_workspace() as u32],
+        );
+        self.set_root_property(self.atoms.net_desktop_names, self.atoms.utf8_string, &names);
+
+        // Indices change when workspaces are reordered or destroyed.
+        for window in &self.client_list {
+            self.publish_window_desktop(*window);
+        }
+
+        // Not part of EWMH, lets bars highlight workspaces with urgent clients.
+        let urgent: Vec<u32> = (0..self.workspaces.len())
+            .filter(|index| self.workspace_urgent(*index))
+            .map(|index| index as u32)
+            .collect();
+        self.set_root_property(self.atoms.rwm_urgent_desktops, x::ATOM_CARDINAL, &urgent);
+        self.set_root_property(
+            self.atoms.net_desktop_viewport,
+            x::ATOM_CARDINAL,
+            &viewports,
+        );
+        self.publish_workarea();
+    }
+
+    fn set_root_property<P: x::PropEl>(&self, property: x::Atom, r#type: x::Atom, data: &[P]) {
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: self.root,
+            property,
+            r#type,
+            data,
+        });
+    }
+
+    /// Removes non-persistent workspaces that are empty and not visible.
+    fn destroy_unused_workspaces(&mut self) {
+        let mut index = 0;
+        while index < self.workspaces.len() {
+            let workspace = &self.workspaces[index];
+            let visible = self.monitors.iter().any(|m| m.shows(index));
+
+            if workspace.persistent || visible || !workspace.clients.is_empty() {
+                index += 1;
+                continue;
+            }
+
+            debug!("Workspace {} destroyed", workspace.name);
+            self.workspaces.remove(index);
+
+            for monitor in &mut self.monitors {
+                if monitor.workspace > index {
+                    monitor.workspace -= 1;
+                }
+
+                for combined in &mut monitor.combined {
+                    if *combined > index {
+                        *combined -= 1;
+                    }
+                }
+
+                monitor.history.update(|entry| match entry {
+                    entry if entry == index => None,
+                    entry if entry > index => Some(entry - 1),
+                    entry => Some(entry),
+                });
+            }
+
+            self.publish_desktops();
+        }
+    }
+
+    pub fn focused(&self) -> Option<x::Window> {
+        self.focused
+    }
+
+    pub fn resize_tiled(&mut self, window: x::Window, direction: Direction, delta: f32) {
+        if self.workspace_mut().resize(window, direction, delta) {
+            self.arrange();
+        }
+    }
+
+    pub fn preselect(&mut self, window: x::Window, direction: Direction, ratio: f32) {
+        if self.workspace_mut().preselect(window, direction, ratio) {
+            self.arrange();
+        }
+    }
+
+    fn focus(&mut self, window: x::Window) {
+        let window = match self.modal_dialog(window) {
+            Some(dialog) if self.config.focus_modal_dialogs => dialog,
+            _ => window,
+        };
+
+        let previous = self.focused.replace(window);
+        if let Some(previous) = previous.filter(|previous| *previous != window) {
+            self.release_focus(previous);
+        }
+        self.grab_focus_button(window, false);
+
+        // ICCCM input models: passive and locally active clients are given the focus,
+        // globally active ones take it themselves when sent WM_TAKE_FOCUS. Toolkits like
+        // Java's only accept keyboard input after this handshake, which must not use
+        // CurrentTime. Keyboard input goes to the root meanwhile, or for good with clients
+        // never taking it, instead of the previously focused client.
+        let (input, take_focus) = self
+            .clients
+            .get(&window.resource_id())
+            .map_or((true, false), |client| (client.input, client.take_focus));
+        self.conn.send_request(&xcb::x::SetInputFocus {
+            revert_to: x::InputFocus::PointerRoot,
+            focus: if input {
+                window
+            } else {
+                x::INPUTFOCUS_POINTER_ROOT
+            },
+            time: x::CURRENT_TIME,
+        });
+        if take_focus {
+            self.send_protocol(window, self.atoms.wm_take_focus, self.event_time);
+        }
+        self.set_root_property(self.atoms.net_active_window, x::ATOM_WINDOW, &[window]);
+
+        self.workspace_mut().focus(window);
+        self.set_urgent(window, false);
+        self.set_wm_state(window, self.atoms.net_wm_state_demands_attention, false);
+        self.update_border(window);
+        self.update_opacity(window);
+    }
+
+    /// Returns the innermost visible modal dialog blocking the window, if any.
+    fn modal_dialog(&self, window: x::Window) -> Option<x::Window> {
+        let visible = self.visible_windows();
+        let mut chain = vec![window];
+        while let Some(dialog) = visible.iter().copied().find(|child| {
+            !chain.contains(child)
+                && self
+                    .clients
+                    .get(&child.resource_id())
+                    .is_some_and(|client| {
+                        client.modal && client.transient_for == chain.last().copied()
+                    })
+        }) {
+            chain.push(dialog);
+        }
+        chain[1..].last().copied()
+    }
+
+    /// Puts the window, followed by the modal dialogs belonging to it, on top of its layer.
+    pub(crate) fn raise(&mut self, window: x::Window) {
+        let mut raised = vec![window];
+        let mut index = 0;
+        while let Some(parent) = raised.get(index).copied() {
+            let dialogs: Vec<x::Window> = self
+                .clients
+                .values()
+                .filter(|client| client.modal && client.transient_for == Some(parent))
+                .map(|client| client.window)
+                .filter(|dialog| !raised.contains(dialog))
+                .collect();
+            raised.extend(dialogs);
+            index += 1;
+        }
+
+        self.stack.retain(|w| !raised.contains(w));
+        self.stack.extend(raised);
+        self.restack();
+    }
+
+    /// Puts the window at the bottom of its layer.
+    fn lower(&mut self, window: x::Window) {
+        self.stack.retain(|w| *w != window);
+        self.stack.insert(0, window);
+        self.restack();
+    }
+
+    /// Applies the stacking order: the layers from the bottom up (see [`Layer`]), in each the
+    /// most recently raised client on top. Tiles raised by their layout go above the other
+    /// tiles and the window manager's own windows above everything.
+    fn restack(&mut self) {
+        let mut windows: Vec<x::Window> = self
+            .stack
+            .iter()
+            .copied()
+            .filter(|window| self.clients.contains_key(&window.resource_id()))
+            .collect();
+
+        for monitor in &self.monitors {
+            let workspace = &self.workspaces[monitor.workspace];
+            let tiled = self.tiled_clients(monitor);
+            let combined = self.combined_layout(monitor, &tiled);
+            let layout = combined.as_deref().unwrap_or(workspace.layout());
+            let clients: Vec<LayoutClient> = tiled
+                .iter()
+                .map(|window| workspace.layout_client(*window, self.focused))
+                .collect();
+
+            if let Some(index) = layout.raised(&workspace.params, &clients) {
+                windows.retain(|window| *window != tiled[index]);
+                windows.push(tiled[index]);
+            }
+        }
+
+        let layered: Vec<(x::Window, Layer)> = windows
+            .iter()
+            .filter_map(|window| {
+                let client = self.clients.get(&window.resource_id())?;
+                Some((*window, Layer::of(client)))
+            })
+            .collect();
+
+        let order = stacking::stacking_order(&layered);
+        if order != self.stacked {
+            let own =
+                std::iter::once(self.preselection_window).chain(self.osd.as_ref().map(Osd::window));
+            for window in order.iter().copied().chain(own) {
+                self.conn.send_request(&x::ConfigureWindow {
+                    window,
+                    value_list: &[x::ConfigWindow::StackMode(x::StackMode::Above)],
+                });
+            }
+            self.stacked = order.clone();
+        }
+
+        let stacking: Vec<x::Window> = order
+            .into_iter()
+            .filter(|window| self.client_list.contains(window))
+            .collect();
+        self.set_root_property(
+            self.atoms.net_client_list_stacking,
+            x::ATOM_WINDOW,
+            &stacking,
+        );
+    }
+
+    /// Publishes the mapped clients for task lists, see [`App::restack`] for the stacking
+    /// order.
+    fn publish_client_list(&self) {
+        self.set_root_property(
+            self.atoms.net_client_list,
+            x::ATOM_WINDOW,
+            &self.client_list,
+        );
+    }
+
+    /// Updates a client that lost the focus, unless it is already gone.
+    fn release_focus(&self, window: x::Window) {
+        if self.clients.contains_key(&window.resource_id()) {
+            self.grab_focus_button(window, true);
+            self.update_border(window);
+            self.update_opacity(window);
+        }
+    }
+
+    /// Sets the opacity for compositors after the client's own one and its focus.
+    fn update_opacity(&self, window: x::Window) {
+        let client = match self.clients.get(&window.resource_id()) {
+            Some(client) if !client.dock && !client.desktop => client,
+            _ => return,
+        };
+
+        let focused = self.focused == Some(window);
+        let percent = opacity::effective(client.opacity, focused, self.config.inactive_opacity);
+        if percent == 100 {
+            self.conn.send_request(&x::DeleteProperty {
+                window,
+                property: self.atoms.net_wm_window_opacity,
+            });
+        } else {
+            self.conn.send_request(&x::ChangeProperty {
+                mode: x::PropMode::Replace,
+                window,
+                property: self.atoms.net_wm_window_opacity,
+                r#type: x::ATOM_CARDINAL,
+                data: &[opacity::cardinal(percent)],
+            });
+        }
+    }
+
+    /// Changes the own opacity of the client by `delta` percent.
+    pub fn adjust_opacity(&mut self, window: x::Window, delta: i8) {
+        let client = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) => client,
+            None => return,
+        };
+
+        let opacity = (i16::from(client.opacity.unwrap_or(100)) + i16::from(delta)).clamp(10, 100);
+        debug!("Window {:?} opacity: {}%", window, opacity);
+        client.opacity = Some(opacity as u8);
+        self.update_opacity(window);
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Colors the border of a client after its urgency and focus.
+    fn update_border(&self, window: x::Window) {
+        let client = match self.clients.get(&window.resource_id()) {
+            Some(client) if !client.dock && !client.desktop => client,
+            _ => return,
+        };
+
+        let color = if client.unresponsive {
+            self.config.border_color_unresponsive
+        } else if client.urgent {
+            self.config.border_color_urgent
+        } else if self.focused == Some(window) {
+            self.config.border_color_focused
+        } else {
+            self.config.border_color_normal
+        };
+
+        self.conn.send_request(&x::ChangeWindowAttributes {
+            window,
+            value_list: &[x::Cw::BorderPixel(color)],
+        });
+    }
+
+    pub fn minimized(&self) -> &[x::Window] {
+        &self.minimized
+    }
+
+    /// Title, class and workspace of a client, for menus.
+    pub fn describe_client(&self, window: x::Window) -> String {
+        let workspace = self
+            .workspaces
+            .iter()
+            .find(|ws| ws.contains(window))
+            .map(|ws| ws.name.as_str())
+            .unwrap_or_default();
+
+        match self.clients.get(&window.resource_id()) {
+            Some(client) if client.unresponsive => format!(
+                "{} ({}) [{}] (not responding)",
+                client.title, client.class, workspace
+            ),
+            Some(client) => format!("{} ({}) [{}]", client.title, client.class, workspace),
+            None => format!("{:?} [{}]", window, workspace),
+        }
+    }
+
+    /// Pings the managed clients supporting it if it is time, marking those that did not
+    /// answer the previous ping as unresponsive.
+    fn ping_clients(&mut self) {
+        let now = Instant::now();
+        if self
+            .pinger
+            .as_ref()
+            .is_none_or(|pinger| !pinger.time_left(now).is_zero())
+        {
+            return;
+        }
+
+        let clients: Vec<x::Window> = self
+            .clients
+            .values()
+            .filter(|client| client.pingable)
+            .map(|client| client.window)
+            .filter(|window| self.workspaces.iter().any(|ws| ws.contains(*window)))
+            .collect();
+
+        let unanswered = match &mut self.pinger {
+            Some(pinger) => match pinger.round(now, &clients) {
+                Some(unanswered) => unanswered,
+                None => return,
+            },
+            None => return,
+        };
+
+        for window in unanswered {
+            self.set_unresponsive(window, true);
+        }
+
+        for window in clients {
+            self.send_protocol(window, self.atoms.net_wm_ping, x::CURRENT_TIME);
+        }
+    }
+
+    /// Sends a WM_PROTOCOLS message, `protocol` being one the client listed in its
+    /// WM_PROTOCOLS property.
+    fn send_protocol(&self, window: x::Window, protocol: x::Atom, time: u32) {
+        let event = x::ClientMessageEvent::new(
+            window,
+            self.atoms.wm_protocols,
+            x::ClientMessageData::Data32([
+                protocol.resource_id(),
+                time,
+                window.resource_id(),
+                0,
+                0,
+            ]),
+        );
+        self.conn.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(window),
+            event_mask: EventMask::NO_EVENT,
+            event: &event,
+        });
+    }
+
+    /// Handles the answer to a ping, sent back to the root window by the client `id`.
+    fn handle_pong(&mut self, id: u32) {
+        let window = match self.clients.get(&id) {
+            Some(client) => client.window,
+            None => return,
+        };
+
+        if let Some(pinger) = &mut self.pinger {
+            pinger.answer(window);
+        }
+        self.set_unresponsive(window, false);
+    }
+
+    fn set_unresponsive(&mut self, window: x::Window, unresponsive: bool) {
+        match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if client.unresponsive != unresponsive => {
+                client.unresponsive = unresponsive;
+            }
+            _ => return,
+        }
+
+        if unresponsive {
+            warn!("Window {:?} is not responding", window);
+        } else {
+            info!("Window {:?} is responding again", window);
+        }
+        self.update_border(window);
+    }
+
+    /// Clients that did not answer the last ping.
+    pub fn unresponsive(&self) -> Vec<x::Window> {
+        let mut windows = Vec::new();
+        for window in self.workspaces.iter().flat_map(|ws| ws.clients.iter()) {
+            let unresponsive = self
+                .clients
+                .get(&window.resource_id())
+                .is_some_and(|client| client.unresponsive);
+            if unresponsive && !windows.contains(window) {
+                windows.push(*window);
+            }
+        }
+        windows
+    }
+
+    /// Asks the client to close its window with WM_DELETE_WINDOW, clients not supporting it
+    /// are disconnected from the X server, leaving other windows of their process alone.
+    pub fn close_client(&mut self, window: x::Window, time: u32) {
+        if !self.clients.contains_key(&window.resource_id()) {
+            return;
+        }
+
+        let protocols = properties::get_atoms(&self.conn, window, self.atoms.wm_protocols);
+        if protocols.contains(&self.atoms.wm_delete_window) {
+            debug!("Closing {:?}", window);
+            self.send_protocol(window, self.atoms.wm_delete_window, time);
+            self.conn.flush().expect("Flushed");
+        } else {
+            self.disconnect_client(window);
+        }
+    }
+
+    /// Process id of the client from _NET_WM_PID, if it runs on this host (WM_CLIENT_MACHINE).
+    /// Values naming process groups, all processes or the window manager itself are ignored.
+    fn local_pid(&self, window: x::Window) -> Option<u32> {
+        let pid = self
+            .clients
+            .get(&window.resource_id())?
+            .pid
+            .filter(|pid| (1..=i32::MAX as u32).contains(pid) && *pid != std::process::id())?;
+        let machine =
+            properties::get_string_property(&self.conn, window, x::ATOM_WM_CLIENT_MACHINE);
+        (machine.is_some() && machine == process::hostname()).then_some(pid)
+    }
+
+    /// Terminates the process of the client if it runs on this host, it is killed if the
+    /// window is still there after a while or when asked again. Other clients are
+    /// disconnected from the X server.
+    pub fn kill_client(&mut self, window: x::Window) {
+        if !self.clients.contains_key(&window.resource_id()) {
+            return;
+        }
+
+        match self.local_pid(window) {
+            Some(pid) if !self.terminating.iter().any(|(w, ..)| *w == window) => {
+                info!("Terminating process {} of {:?}", pid, window);
+                unsafe {
+                    libc::kill(pid as libc::pid_t, libc::SIGTERM);
+                }
+                self.terminating
+                    .push((window, pid, Instant::now() + TERMINATE_TIMEOUT));
+            }
+            _ => self.force_kill(window),
+        }
+    }
+
+    /// Kills the process of the client sent SIGTERM, if any, and disconnects the client.
+    fn force_kill(&mut self, window: x::Window) {
+        if let Some(index) = self.terminating.iter().position(|(w, ..)| *w == window) {
+            let (_, pid, _) = self.terminating.remove(index);
+            info!("Killing process {} of {:?}", pid, window);
+            unsafe {
+                libc::kill(pid as libc::pid_t, libc::SIGKILL);
+            }
+        }
+
+        self.disconnect_client(window);
+    }
+
+    /// Closes the connection of the client with XKillClient.
+    fn disconnect_client(&mut self, window: x::Window) {
+        info!("Killing client {:?}", window);
+        self.conn.send_request(&x::KillClient {
+            resource: window.resource_id(),
+        });
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Kills the terminated processes whose windows outlived the timeout.
+    fn kill_terminating(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<x::Window> = self
+            .terminating
+            .iter()
+            .filter(|(_, _, deadline)| *deadline <= now)
+            .map(|(window, ..)| *window)
+            .collect();
+
+        for window in expired {
+            self.force_kill(window);
+        }
+    }
+
+    /// Hides the client until restored, it keeps its place in its workspace.
+    pub fn minimize(&mut self, window: x::Window) {
+        if self.minimized.contains(&window) || !self.workspaces.iter().any(|ws| ws.contains(window))
+        {
+            return;
+        }
+
+        debug!("Minimizing {:?}", window);
+        let before = self.visible_windows();
+        self.minimized.push(window);
+        self.set_wm_state(window, self.atoms.net_wm_state_hidden, true);
+        self.update_visibility(&before);
+
+        if self.focused == Some(window) {
+            self.refocus();
+        }
+        self.arrange();
+    }
+
+    /// Shows a minimized client again and focuses it, switching to its workspace if hidden.
+    pub fn restore(&mut self, window: x::Window) -> anyhow::Result<()> {
+        if !self.minimized.contains(&window) {
+            return Ok(());
+        }
+
+        debug!("Restoring {:?}", window);
+        let before = self.visible_windows();
+        self.minimized.retain(|w| *w != window);
+        self.set_wm_state(window, self.atoms.net_wm_state_hidden, false);
+        self.update_visibility(&before);
+
+        self.activate(window)
+    }
+
+    /// Focuses and raises the client, switching to its monitor, group and workspace if hidden.
+    fn activate(&mut self, window: x::Window) -> anyhow::Result<()> {
+        self.set_showing_desktop(false);
+
+        if let Some(index) = self.workspaces.iter().position(|ws| ws.contains(window)) {
+            if self.workspaces[index].monitor != self.current_monitor
+                && self.config.workspace_model == WorkspaceModel::PerMonitor
+            {
+                self.current_monitor = self.workspaces[index].monitor;
+            }
+
+            self.set_group(self.workspaces[index].group);
+            if !self.visible_windows().contains(&window) {
+                self.view_workspace(index)?;
+            }
+        }
+
+        self.focus(window);
+        self.raise(window);
+        self.arrange();
+        Ok(())
+    }
+
+    /// Handles a client asking for the focus (_NET_ACTIVE_WINDOW): the source, time of the
+    /// request and the requestor's active window. Task lists and pagers act for the user and
+    /// are always obeyed, as is the focused client activating another of its windows.
+    fn handle_activation_request(&mut self, window: x::Window, data: &[u32]) {
+        const SOURCE_PAGER: u32 = 2;
+
+        let (source, time, requestor) = (data[0], data[1], data[2]);
+        let managed = self.workspaces.iter().any(|ws| ws.contains(window));
+        if !managed || self.focused == Some(window) {
+            return;
+        }
+
+        // Any client can name the focused window, it only counts for its own windows.
+        let requestor_focused = requestor != 0
+            && self.focused.is_some_and(|focused| {
+                focused.resource_id() == requestor && self.same_application(focused, window)
+            });
+
+        let activation = if source == SOURCE_PAGER || requestor_focused {
+            ActivationPolicy::Switch
+        } else {
+            activation::decide(self.config.activation_policy, time, self.user_time)
+        };
+        debug!("Activation of {:?} requested: {:?}", window, activation);
+
+        let result = match activation {
+            ActivationPolicy::Switch if self.minimized.contains(&window) => self.restore(window),
+            ActivationPolicy::Switch => self.activate(window),
+            ActivationPolicy::Urgent => {
+                self.set_wm_state(window, self.atoms.net_wm_state_demands_attention, true);
+                self.set_urgent(window, true);
+                Ok(())
+            }
+            ActivationPolicy::Ignore => Ok(()),
+        };
+        if let Err(e) = result {
+            error!("Activating window failed: {}", e);
+        }
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Whether both windows belong to the same application, sharing their client leader
+    /// (WM_CLIENT_LEADER) or process (_NET_WM_PID).
+    fn same_application(&self, window: x::Window, other: x::Window) -> bool {
+        let leader = |window| properties::get_client_leader(&self.conn, &self.atoms, window);
+        let pid = |window: x::Window| {
+            self.clients
+                .get(&window.resource_id())
+                .and_then(|client| client.pid)
+        };
+
+        leader(window).is_some_and(|first| Some(first) == leader(other))
+            || pid(window).is_some_and(|first| Some(first) == pid(other))
+    }
+
+    /// Remembers user input at `time`, unless newer input was already seen.
+    fn note_user_time(&mut self, time: u32) {
+        if activation::decide(ActivationPolicy::Ignore, time, self.user_time)
+            == ActivationPolicy::Switch
+        {
+            self.user_time = Some(time);
+        }
+    }
+
+    /// Makes the client cover its monitor, or puts it back where it was before.
+    pub fn set_fullscreen(&mut self, window: x::Window, enabled: bool) {
+        let client = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if client.fullscreen != enabled => client,
+            _ => return,
+        };
+
+        debug!("Window {:?} fullscreen: {}", window, enabled);
+        client.fullscreen = enabled;
+        if enabled {
+            client.pre_fullscreen = Some((client.geometry, client.border_width));
+        } else if let Some((geometry, border_width)) = client.pre_fullscreen.take() {
+            // Tiled clients get their geometry back from the layout.
+            if client.floating {
+                self.conn.send_request(&x::ConfigureWindow {
+                    window,
+                    value_list: &[
+                        x::ConfigWindow::X(geometry.x),
+                        x::ConfigWindow::Y(geometry.y),
+                        x::ConfigWindow::Width(geometry.width),
+                        x::ConfigWindow::Height(geometry.height),
+                        x::ConfigWindow::BorderWidth(border_width),
+                    ],
+                });
+            }
+        }
+
+        self.set_wm_state(window, self.atoms.net_wm_state_fullscreen, enabled);
+        self.raise(window);
+        self.arrange();
+    }
+
+    /// Makes a floating client span the work area vertically or horizontally, or puts it back
+    /// along that axis. Tiled clients keep the geometry given by the layout.
+    pub fn set_maximized(&mut self, window: x::Window, vertical: bool, enabled: bool) {
+        let client = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if client.floating && !client.fullscreen => client,
+            _ => return,
+        };
+        if enabled && !client.motif_hints.resizable {
+            return;
+        }
+        let maximized = if vertical {
+            &mut client.maximized_vert
+        } else {
+            &mut client.maximized_horz
+        };
+        if *maximized == enabled {
+            return;
+        }
+        *maximized = enabled;
+
+        debug!(
+            "Window {:?} maximized {}: {}",
+            window,
+            if vertical {
+                "vertically"
+            } else {
+                "horizontally"
+            },
+            enabled
+        );
+        if enabled {
+            client.pre_maximized.get_or_insert(client.geometry);
+        } else if let Some(previous) = client.pre_maximized {
+            let mut geometry = client.geometry;
+            if vertical {
+                geometry.y = previous.y;
+                geometry.height = previous.height;
+            } else {
+                geometry.x = previous.x;
+                geometry.width = previous.width;
+            }
+            if !client.maximized_vert && !client.maximized_horz {
+                client.pre_maximized = None;
+            }
+            self.configure_window(window, geometry);
+        }
+
+        let state = if vertical {
+            self.atoms.net_wm_state_maximized_vert
+        } else {
+            self.atoms.net_wm_state_maximized_horz
+        };
+        self.set_wm_state(window, state, enabled);
+        self.arrange();
+    }
+
+    pub fn is_maximized(&self, window: x::Window, vertical: bool) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| {
+                if vertical {
+                    client.maximized_vert
+                } else {
+                    client.maximized_horz
+                }
+            })
+    }
+
+    /// Shows the client on every workspace of its monitor, or only on its own again.
+    pub fn set_sticky(&mut self, window: x::Window, enabled: bool) {
+        if self.is_sticky(window) == enabled {
+            return;
+        }
+
+        let visible = self.visible_windows();
+        let monitor = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) => {
+                client.sticky = enabled;
+                client.monitor
+            }
+            None => return,
+        };
+        debug!("Window {:?} sticky: {}", window, enabled);
+
+        // Stays where it is shown instead of disappearing with its hidden workspace.
+        if !enabled && visible.contains(&window) && !self.visible_windows().contains(&window) {
+            if let Some(target) = self.monitors.get(monitor).map(|m| m.workspace) {
+                for workspace in &mut self.workspaces {
+                    workspace.remove(window);
+                }
+                let tiled = self.is_tiled(window);
+                self.workspaces[target].add(window, tiled, None, None);
+            }
+        }
+
+        self.store_workspaces(window);
+        self.set_wm_state(window, self.atoms.net_wm_state_sticky, enabled);
+        self.update_visibility(&visible);
+        self.destroy_unused_workspaces();
+        self.arrange();
+    }
+
+    /// Keeps the client above the others, or lets it be covered again.
+    pub fn set_above(&mut self, window: x::Window, enabled: bool) {
+        match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if client.above != enabled => client.above = enabled,
+            _ => return,
+        }
+
+        debug!("Window {:?} above: {}", window, enabled);
+        self.set_wm_state(window, self.atoms.net_wm_state_above, enabled);
+        self.raise(window);
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Keeps the client below the others, or lets it be raised again.
+    pub fn set_below(&mut self, window: x::Window, enabled: bool) {
+        match self.clients.get_mut(&window.resource_id()) {
+            Some(client) if client.below != enabled => client.below = enabled,
+            _ => return,
+        }
+
+        debug!("Window {:?} below: {}", window, enabled);
+        self.set_wm_state(window, self.atoms.net_wm_state_below, enabled);
+        self.restack();
+        self.conn.flush().expect("Flushed");
+    }
+
+    pub fn is_below(&self, window: x::Window) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.below)
+    }
+
+    pub fn is_above(&self, window: x::Window) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.above)
+    }
+
+    pub fn is_sticky(&self, window: x::Window) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.sticky)
+    }
+
+    pub fn is_fullscreen(&self, window: x::Window) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.fullscreen)
+    }
+
+    /// Takes the client out of the layout, keeping its geometry, or tiles it again.
+    pub fn toggle_floating(&mut self, window: x::Window) {
+        let client = match self.clients.get_mut(&window.resource_id()) {
+            Some(client) => client,
+            None => return,
+        };
+
+        client.floating = !client.floating;
+        debug!("Window {:?} floating: {}", window, client.floating);
+
+        if client.floating {
+            self.raise(window);
+        }
+        self.arrange();
+    }
+
+    /// Focuses the client at `offset` in the focus order of the current workspace, counting
+    /// from the client focused when the walk started.
+    pub fn cycle_focus(&mut self, offset: isize) {
+        if self.focus_cycle.is_none() {
+            let visible = self.visible_windows();
+            let mut order = self.workspace().focus_order();
+            if self.config.cycle_sticky_clients {
+                for window in self.monitor_clients(self.monitor()) {
+                    if self.is_sticky(window) && !order.contains(&window) {
+                        order.push(window);
+                    }
+                }
+            } else {
+                order.retain(|window| !self.is_sticky(*window));
+            }
+            order.retain(|window| visible.contains(window));
+            if order.len() < 2 {
+                return;
+            }
+
+            // Key releases only reach the window manager while the keyboard is grabbed, the
+            // walk would never end without them.
+            let cookie = self.conn.send_request(&x::GrabKeyboard {
+                owner_events: false,
+                grab_window: self.root,
+                time: x::CURRENT_TIME,
+                pointer_mode: x::GrabMode::Async,
+                keyboard_mode: x::GrabMode::Async,
+            });
+            match self.conn.wait_for_reply(cookie).map(|reply| reply.status()) {
+                Ok(x::GrabStatus::Success) => {}
+                status => {
+                    warn!(
+                        "Grabbing the keyboard to cycle the focus failed: {:?}",
+                        status
+                    );
+                    return;
+                }
+            }
+            self.focus_cycle = Some(FocusCycle { order, position: 0 });
+        }
+
+        let visible = self.visible_windows();
+        let cycle = self.focus_cycle.as_mut().expect("Focus cycle started");
+        cycle.order.retain(|window| visible.contains(window));
+        if cycle.order.is_empty() {
+            self.finish_focus_cycle();
+            return;
+        }
+
+        let count = cycle.order.len() as isize;
+        cycle.position = (cycle.position as isize + offset).rem_euclid(count) as usize;
+        let window = cycle.order[cycle.position];
+
+        self.focus(window);
+        self.raise(window);
+        self.arrange();
+
+        if let Some(client) = self.clients.get(&window.resource_id()) {
+            let area = self.work_area(self.monitor());
+            if let Some(osd) = &mut self.osd {
+                osd.show_with_icon(
+                    &self.conn,
+                    &self.config,
+                    &client.title,
+                    client.icon.clone(),
+                    area,
+                );
+            }
+        }
+    }
+
+    /// Ends the focus walk, only the finally picked client counts as recently focused.
+    fn finish_focus_cycle(&mut self) {
+        let cycle = match self.focus_cycle.take() {
+            Some(cycle) => cycle,
+            None => return,
+        };
+
+        self.conn.send_request(&x::UngrabKeyboard {
+            time: x::CURRENT_TIME,
+        });
+
+        let workspace = self.workspace_mut();
+        workspace.set_focus_order(&cycle.order);
+        if let Some(window) = cycle.order.get(cycle.position) {
+            workspace.focus(*window);
+        }
+    }
+
+    /// Focuses the client last focused on the current workspace, if any.
+    fn refocus(&mut self) {
+        let visible = self.visible_windows();
+        let window = self
+            .workspace()
+            .focus_order()
+            .into_iter()
+            .find(|window| visible.contains(window));
+
+        match window {
+            Some(window) => self.focus(window),
+            None => self.unfocus(),
+        }
+    }
+
+    /// Gives the focus back to the root window, so keybinds keep working.
+    fn unfocus(&mut self) {
+        if let Some(previous) = self.focused.take() {
+            self.release_focus(previous);
+        }
+
+        self.conn.send_request(&xcb::x::SetInputFocus {
+            revert_to: x::InputFocus::PointerRoot,
+            focus: x::INPUTFOCUS_POINTER_ROOT,
+            time: x::CURRENT_TIME,
+        });
+        self.set_root_property(
+            self.atoms.net_active_window,
+            x::ATOM_WINDOW,
+            &[x::Window::none()],
+        );
+    }
+
+    fn set_urgent(&mut self, window: x::Window, urgent: bool) {
+        if let Some(client) = self.clients.get_mut(&window.resource_id()) {
+            if client.urgent != urgent {
+                client.urgent = urgent;
+                self.update_border(window);
+                self.publish_desktops();
+            }
+        }
+    }
+
+    /// Applies a _NET_WM_STATE request: an action (remove, add or toggle) and up to two states.
+    fn handle_wm_state_request(&mut self, window: x::Window, data: &[u32]) {
+        const REMOVE: u32 = 0;
+        const ADD: u32 = 1;
+
+        let demands_attention = self.atoms.net_wm_state_demands_attention;
+        if data[1..3].contains(&demands_attention.resource_id()) {
+            let urgent = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.is_urgent(window),
+            };
+
+            // The focused client already has the attention it asks for.
+            if !urgent || self.focused != Some(window) {
+                self.set_wm_state(window, demands_attention, urgent);
+                self.set_urgent(window, urgent);
+            }
+        }
+
+        for (state, vertical) in [
+            (self.atoms.net_wm_state_maximized_vert, true),
+            (self.atoms.net_wm_state_maximized_horz, false),
+        ] {
+            if data[1..3].contains(&state.resource_id()) {
+                let enabled = match data[0] {
+                    REMOVE => false,
+                    ADD => true,
+                    _ => !self.is_maximized(window, vertical),
+                };
+                self.set_maximized(window, vertical, enabled);
+            }
+        }
+
+        let above = self.atoms.net_wm_state_above;
+        if data[1..3].contains(&above.resource_id()) {
+            let enabled = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.is_above(window),
+            };
+            self.set_above(window, enabled);
+        }
+
+        let below = self.atoms.net_wm_state_below;
+        if data[1..3].contains(&below.resource_id()) {
+            let enabled = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.is_below(window),
+            };
+            self.set_below(window, enabled);
+        }
+
+        let sticky = self.atoms.net_wm_state_sticky;
+        if data[1..3].contains(&sticky.resource_id()) {
+            let enabled = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.is_sticky(window),
+            };
+            self.set_sticky(window, enabled);
+        }
+
+        let fullscreen = self.atoms.net_wm_state_fullscreen;
+        if data[1..3].contains(&fullscreen.resource_id()) {
+            let enabled = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.is_fullscreen(window),
+            };
+            self.set_fullscreen(window, enabled);
+        }
+
+        let hidden = self.atoms.net_wm_state_hidden;
+        if data[1..3].contains(&hidden.resource_id()) {
+            let minimize = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !self.minimized.contains(&window),
+            };
+
+            if minimize {
+                self.minimize(window);
+            } else if let Err(e) = self.restore(window) {
+                error!("Restoring window failed: {}", e);
+            }
+        }
+
+        let modal = self.atoms.net_wm_state_modal;
+        if data[1..3].contains(&modal.resource_id()) {
+            let client = match self.clients.get_mut(&window.resource_id()) {
+                Some(client) => client,
+                None => return,
+            };
+            client.modal = match data[0] {
+                REMOVE => false,
+                ADD => true,
+                _ => !client.modal,
+            };
+            client.floating |= client.modal;
+            let enabled = client.modal;
+
+            self.set_wm_state(window, modal, enabled);
+            if enabled {
+                self.raise(window);
+            }
+            self.arrange();
+        }
+    }
+
+    /// Applies the fields a client asked to change. Managed clients keep the border set by the
+    /// window manager, their position is shifted after their gravity instead.
+    fn handle_configure_request(&mut self, event: &x::ConfigureRequestEvent) {
+        let mask = event.value_mask();
+        let window = event.window();
+
+        let managed = self.workspaces.iter().any(|ws| ws.contains(window));
+        let known = self.clients.contains_key(&window.resource_id());
+        let mut value_list = Vec::new();
+
+        // Clients only move within their layer, siblings are ignored.
+        if known && mask.contains(x::ConfigWindowMask::STACK_MODE) {
+            match event.stack_mode() {
+                x::StackMode::Above | x::StackMode::TopIf => self.raise(window),
+                x::StackMode::Below | x::StackMode::BottomIf => self.lower(window),
+                x::StackMode::Opposite => {}
+            }
+        }
+
+        match self.clients.get(&window.resource_id()) {
+            // The layout decides, the client only learns where it actually is.
+            Some(client) if managed && (client.is_tiled() || client.fullscreen) => {
+                self.send_configure_notify(window, client.geometry, client.border_width);
+                self.conn.flush().unwrap();
+                return;
+            }
+            Some(client) if managed => {
+                let requested_border = if mask.contains(x::ConfigWindowMask::BORDER_WIDTH) {
+                    event.border_width().into()
+                } else {
+                    client.border_width
+                };
+                let (dx, dy) = client
+                    .size_hints
+                    .gravity_offset(client.border_width as i32 - requested_border as i32);
+
+                let mut geometry = client.geometry;
+                if mask.contains(x::ConfigWindowMask::X) {
+                    geometry.x = event.x().into();
+                }
+                if mask.contains(x::ConfigWindowMask::Y) {
+                    geometry.y = event.y().into();
+                }
+                if mask.contains(x::ConfigWindowMask::WIDTH) {
+                    geometry.width = event.width().into();
+                }
+                if mask.contains(x::ConfigWindowMask::HEIGHT) {
+                    geometry.height = event.height().into();
+                }
+                let (width, height) = client
+                    .size_hints
+                    .limits()
+                    .constrain(geometry.width, geometry.height);
+
+                value_list.extend([
+                    x::ConfigWindow::X(geometry.x + dx),
+                    x::ConfigWindow::Y(geometry.y + dy),
+                    x::ConfigWindow::Width(width),
+                    x::ConfigWindow::Height(height),
+                ]);
+            }
+            _ => {
+                if mask.contains(x::ConfigWindowMask::X) {
+                    value_list.push(x::ConfigWindow::X(event.x().into()));
+                }
+                if mask.contains(x::ConfigWindowMask::Y) {
+                    value_list.push(x::ConfigWindow::Y(event.y().into()));
+                }
+                if mask.contains(x::ConfigWindowMask::WIDTH) {
+                    value_list.push(x::ConfigWindow::Width(event.width().into()));
+                }
+                if mask.contains(x::ConfigWindowMask::HEIGHT) {
+                    value_list.push(x::ConfigWindow::Height(event.height().into()));
+                }
+                if mask.contains(x::ConfigWindowMask::BORDER_WIDTH) {
+                    value_list.push(x::ConfigWindow::BorderWidth(event.border_width().into()));
+                }
+            }
+        }
+
+        if !known && mask.contains(x::ConfigWindowMask::SIBLING) {
+            value_list.push(x::ConfigWindow::Sibling(event.sibling()));
+        }
+        if !known && mask.contains(x::ConfigWindowMask::STACK_MODE) {
+            value_list.push(x::ConfigWindow::StackMode(event.stack_mode()));
+        }
+
+        let cookie = self.conn.send_request_checked(&x::ConfigureWindow {
+            window,
+            value_list: &value_list,
+        });
+
+        self.conn.flush().unwrap();
+
+        let result = self.conn.check_request(cookie);
+        if result.is_err() {
+            error!("ConfigureRequest failed {:?}", result);
+        }
+    }
+
+    /// Tells the client its geometry with a synthetic ConfigureNotify (ICCCM 4.1.5), needed when
+    /// it was moved without being resized or its request was denied.
+    fn send_configure_notify(&self, window: x::Window, geometry: Rect, border_width: u32) {
+        let event = x::ConfigureNotifyEvent::new(
+            window,
+            window,
+            x::Window::none(),
+            geometry.x as i16,
+            geometry.y as i16,
+            geometry.width as u16,
+            geometry.height as u16,
+            border_width as u16,
+            false,
+        );
+
+        self.conn.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(window),
+            event_mask: EventMask::STRUCTURE_NOTIFY,
+            event: &event,
+        });
+    }
+
+    /// Adds or removes `state` in the _NET_WM_STATE of the client.
+    fn set_wm_state(&mut self, window: x::Window, state: x::Atom, enabled: bool) {
+        let states = match self
+            .clients
+            .get_mut(&window.resource_id())
+            .and_then(|client| client.states.as_mut())
+        {
+            Some(states) if states.contains(&state) != enabled => states,
+            _ => return,
+        };
+
+        if enabled {
+            states.push(state);
+        } else {
+            states.retain(|s| *s != state);
+        }
+
+        self.conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property: self.atoms.net_wm_state,
+            r#type: x::ATOM_ATOM,
+            data: states,
+        });
+    }
+
+    fn is_urgent(&self, window: x::Window) -> bool {
+        self.clients
+            .get(&window.resource_id())
+            .is_some_and(|client| client.urgent)
+    }
+
+    /// Whether any client of the workspace needs attention.
+    fn workspace_urgent(&self, index: usize) -> bool {
+        self.workspaces[index]
+            .clients
+            .iter()
+            .any(|window| self.is_urgent(*window))
+    }
+
+    /// Shows the workspace of an urgent client (the current monitor's first) and focuses it.
+    pub fn jump_to_urgent(&mut self) -> anyhow::Result<()> {
+        let mut candidates = self.monitor_workspaces(self.current_monitor);
+        candidates.extend(0..self.workspaces.len());
+
+        let found = candidates.into_iter().find_map(|index| {
+            self.workspaces[index]
+                .clients
+                .iter()
+                .copied()
+                .find(|window| self.is_urgent(*window))
+        });
+
+        match found {
+            Some(window) => self.activate(window),
+            None => Ok(()),
+        }
+    }
+
+    /// Area of the monitor not reserved by docks.
+    fn work_area(&self, monitor: &Monitor) -> Rect {
+        let struts: Vec<Struts> = self
+            .clients
+            .values()
+            .filter_map(|client| client.struts)
+            .collect();
+
+        struts::work_area(&struts, self.screen_area, monitor.area)
+    }
+
+    /// Publishes _NET_WORKAREA, the work area of the monitor of each workspace.
+    fn publish_workarea(&self) {
+        let areas: Vec<u32> = self
+            .workspaces
+            .iter()
+            .flat_map(|ws| {
+                let area = self
+                    .monitors
+                    .get(ws.monitor)
+                    .map(|monitor| self.work_area(monitor))
+                    .unwrap_or_default();
+                [area.x as u32, area.y as u32, area.width, area.height]
+            })
+            .collect();
+
+        self.set_root_property(self.atoms.net_workarea, x::ATOM_CARDINAL, &areas);
+    }
+
+    /// Recomputes the work areas after a dock reserved or released space.
+    fn update_work_areas(&mut self) {
+        self.publish_workarea();
+        self.arrange();
+    }
+
+    /// Whether the client takes part in the layout of its workspaces. Sticky clients stay
+    /// where they are, as they are shown along other workspaces.
+    fn is_tiled(&self, window: x::Window) -> bool {
+        !self.minimized.contains(&window)
+            && self
+                .clients
+                .get(&window.resource_id())
+                .is_none_or(|client| client.is_tiled() && !client.sticky)
+    }
+
+    /// Takes the clients that floated, got minimized... out of the layouts and puts
+    /// the others back in.
+    fn update_tiling(&mut self) {
+        let app = &*self;
+        let changes: Vec<(usize, x::Window, bool)> = app
+            .workspaces
+            .iter()
+            .enumerate()
+            .flat_map(|(index, workspace)| {
+                workspace
+                    .clients
+                    .iter()
+                    .map(move |window| (index, *window, app.is_tiled(*window)))
+            })
+            .collect();
+
+        for (index, window, tiled) in changes {
+            self.workspaces[index].set_tiled(window, tiled);
+        }
+    }
+
+    /// Clients of the monitor that take part in the layout of its workspace.
+    fn tiled_clients(&self, monitor: &Monitor) -> Vec<x::Window> {
+        let mut clients: Vec<x::Window> = Vec::new();
+        if self.showing_desktop {
+            return clients;
+        }
+
+        for window in monitor
+            .workspaces()
+            .flat_map(|index| self.workspaces[index].tiled())
+        {
+            if !clients.contains(&window) {
+                clients.push(window);
+            }
+        }
+
+        clients
+    }
+
+    /// Fresh layout of the kind the workspace of the monitor uses, for a combined view
+    /// showing `tiled`. The workspace's own layout keeps state about its own clients only.
+    fn combined_layout(&self, monitor: &Monitor, tiled: &[x::Window]) -> Option<BoxLayout> {
+        let workspace = &self.workspaces[monitor.workspace];
+        if tiled == workspace.tiled() {
+            return None;
+        }
+
+        let name = workspace.layout().name();
+        let mut layouts = create_layouts(&self.config);
+        let index = layouts
+            .iter()
+            .position(|layout| layout.name() == name)
+            .unwrap_or(0);
+
+        let mut layout = layouts.swap_remove(index);
+        for index in 0..tiled.len() {
+            layout.insert(index, index + 1);
+        }
+        Some(layout)
+    }
+
+    /// Arranges the workspaces visible on all monitors.
+    fn arrange(&mut self) {
+        self.update_tiling();
+
+        for monitor in &self.monitors {
+            let workspace = &self.workspaces[monitor.workspace];
+            let tiled = self.tiled_clients(monitor);
+            let combined = self.combined_layout(monitor, &tiled);
+            self.arrange_workspace(
+                workspace,
+                combined.as_deref().unwrap_or(workspace.layout()),
+                &tiled,
+                self.work_area(monitor),
+            );
+
+            for window in self.monitor_clients(monitor) {
+                let client = match self.clients.get(&window.resource_id()) {
+                    Some(client) => client,
+                    None => continue,
+                };
+
+                if client.floating && !client.fullscreen {
+                    if let Some(geometry) = client.maximized_geometry(self.work_area(monitor)) {
+                        self.configure_window(window, geometry);
+                    }
+                    if !client.motif_hints.decorated && client.border_width != 0 {
+                        self.conn.send_request(&x::ConfigureWindow {
+                            window,
+                            value_list: &[x::ConfigWindow::BorderWidth(0)],
+                        });
+                    }
+                }
+
+                if client.fullscreen {
+                    self.conn.send_request(&x::ConfigureWindow {
+                        window,
+                        value_list: &[
+                            x::ConfigWindow::X(monitor.area.x),
+                            x::ConfigWindow::Y(monitor.area.y),
+                            x::ConfigWindow::Width(monitor.area.width),
+                            x::ConfigWindow::Height(monitor.area.height),
+                            x::ConfigWindow::BorderWidth(0),
+                        ],
+                    });
+                }
+            }
+        }
+
+        self.restack();
+
+        let workspace = self.workspace();
+        let tiled = self.tiled_clients(self.monitor());
+        let area = self
+            .workspace_gaps(workspace)
+            .area(tiled.len(), self.work_area(self.monitor()));
+
+        match workspace.preselection_area(area) {
+            Some(geometry) => {
+                self.conn.send_request(&xcb::x::ConfigureWindow {
+                    window: self.preselection_window,
+                    value_list: &[
+                        x::ConfigWindow::X(geometry.x),
+                        x::ConfigWindow::Y(geometry.y),
+                        x::ConfigWindow::Width(geometry.width),
+                        x::ConfigWindow::Height(geometry.height),
+                    ],
+                });
+                self.conn.send_request(&xcb::x::MapWindow {
+                    window: self.preselection_window,
+                });
+            }
+            None => {
+                self.conn.send_request(&xcb::x::UnmapWindow {
+                    window: self.preselection_window,
+                });
+            }
+        }
+
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Scale of the monitor the workspace is shown on, or was shown on last.
+    fn workspace_scale(&self, workspace: &Workspace) -> f32 {
+        self.monitors
+            .get(workspace.monitor)
+            .map_or(1.0, |monitor| monitor.scale)
+    }
+
+    fn workspace_gaps(&self, workspace: &Workspace) -> Gaps {
+        self.config
+            .workspace_gaps(&workspace.name)
+            .scaled(self.workspace_scale(workspace))
+    }
+
+    fn workspace_border_width(&self, workspace: &Workspace) -> u32 {
+        let width = self.config.workspace_border_width(&workspace.name);
+        (width as f32 * self.workspace_scale(workspace)).round() as u32
+    }
+
+    /// Arranges `windows` with the layout of `workspace`.
+    fn arrange_workspace(
+        &self,
+        workspace: &Workspace,
+        layout: &dyn Layout,
+        windows: &[x::Window],
+        work_area: Rect,
+    ) {
+        let clients: Vec<LayoutClient> = windows
+            .iter()
+            .map(|window| workspace.layout_client(*window, self.focused))
+            .collect();
+
+        let geometries =
+            self.workspace_gaps(workspace)
+                .arrange(layout, &workspace.params, &clients, work_area);
+
+        let border_width =
+            if self.config.workspace_smart_borders(&workspace.name) && clients.len() == 1 {
+                0
+            } else {
+                self.workspace_border_width(workspace)
+            };
+
+        for (window, geometry) in windows.iter().zip(geometries) {
+            let border_width = self
+                .clients
+                .get(&window.resource_id())
+                .and_then(Client::forced_border_width)
+                .unwrap_or(border_width);
+            let mut geometry = geometry.without_border(border_width);
+
+            let client = self.clients.get(&window.resource_id());
+            if let Some(client) = client {
+                let hints = if self.config.tiled_size_hints {
+                    client.size_hints
+                } else {
+                    client.size_hints.limits()
+                };
+                geometry = hints.fit(geometry, self.config.size_hints_alignment);
+            }
+
+            // The server only notifies the client when the window actually changes.
+            if client.is_some_and(|client| {
+                client.geometry == geometry && client.border_width == border_width
+            }) {
+                self.send_configure_notify(*window, geometry, border_width);
+                continue;
+            }
+
+            self.conn.send_request(&xcb::x::ConfigureWindow {
+                window: *window,
+                value_list: &[
+                    x::ConfigWindow::X(geometry.x),
+                    x::ConfigWindow::Y(geometry.y),
+                    x::ConfigWindow::Width(geometry.width),
+                    x::ConfigWindow::Height(geometry.height),
+                    x::ConfigWindow::BorderWidth(border_width),
+                ],
+            });
+        }
+    }
+
+    fn ungrab_keybinds(&self) {
+        let cookie = self.conn.send_request_checked(&xcb::x::UngrabKey {
+            key: xcb::x::Grab::Any as u8,
+            grab_window: self.root,
+            modifiers: xcb::x::ModMask::ANY,
+        });
+
+        self.conn.check_request(cookie).expect("keys ungrabbed");
+        self.conn.flush().expect("Flushed");
+    }
+
+    fn grab_keybinds(&mut self) {
+        let numlock_index = self.keyboard.get_mod_index(xkb::MOD_NAME_NUM);
+        let numlock_mask = xcb::x::ModMask::from_bits(1 << numlock_index)
+            .expect("Expected a valid numlock modmask");
+
+        for keybind in &mut self.config.keybinds {
+            let mask =
+                xcb::x::ModMask::from_bits_truncate(keybind.key_sequence().modifiers().bits());
+
+            let keycodes = self
+                .keyboard
+                .keysym_to_keycodes(&self.conn, keybind.key_sequence().keysym());
+
+            debug!(
+                "Keysym: {:?}, keycodes: {:?}",
+                keybind.key_sequence().keysym(),
+                keycodes
+            );
+
+            for keycode in &keycodes {
+                for modifiers in [
+                    mask,
+                    mask | numlock_mask,
+                    mask | xcb::x::ModMask::LOCK,
+                    mask | xcb::x::ModMask::LOCK | numlock_mask,
+                ] {
+                    let cookie = self.conn.send_request_checked(&xcb::x::GrabKey {
+                        owner_events: true,
+                        grab_window: self.root,
+                        modifiers,
+                        key: *keycode,
+                        pointer_mode: xcb::x::GrabMode::Async,
+                        keyboard_mode: xcb::x::GrabMode::Async,
+                    });
+
+                    self.conn.check_request(cookie).expect("key grabbed");
+                }
+            }
+
+            keybind.update_keycodes(keycodes);
+        }
+
+        self.conn.flush().expect("Flushed");
+    }
+}
+
+/// Command line options.
+#[derive(Debug, Default)]
+struct Options {
+    /// Take over from the running window manager.
+    replace: bool,
+    /// Client id of the previous session, given by the session manager on restart.
+    session_id: Option<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replace" => options.replace = true,
+                "--sm-client-id" => options.session_id = args.next(),
+                _ => warn!("Unknown argument: {}", arg),
+            }
+        }
+
+        options
+    }
+}
+
+fn main() {
+    env_logger::init();
+    info!("Welcome to {}", env!("CARGO_PKG_NAME"));
+
+    let options = Options::parse(std::env::args().skip(1));
 
-    let mut app = App::new();
+    let mut app = App::new(&options);
     app.run();
 }
@@ -0,0 +1,145 @@
+use crate::geometry::{Direction, Rect};
+use crate::history::History;
+
+/// Monitor addressed by a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorTarget {
+    /// Closest monitor in the direction from the current one.
+    Direction(Direction),
+    /// Monitor at the given offset from the current one, wrapping around.
+    Relative(isize),
+    /// Monitor showing the RandR output with the given name.
+    Output(String),
+}
+
+/// Output area showing one workspace at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    /// Name of the RandR output, if known.
+    pub output: Option<String>,
+    /// Shows the RandR primary output.
+    pub primary: bool,
+    pub area: Rect,
+    /// Factor applied to borders and gaps, from the resolution of the output.
+    pub scale: f32,
+    /// Counterclockwise rotation of the output in degrees.
+    pub rotation: u16,
+    /// Index of the visible workspace.
+    pub workspace: usize,
+    /// Other workspaces whose clients are shown along with it, like a dwm tag selection.
+    pub combined: Vec<usize>,
+    /// Workspaces shown on the monitor, for going back and forward.
+    pub history: History<usize>,
+}
+
+impl Monitor {
+    pub fn new(area: Rect, workspace: usize) -> Self {
+        Self {
+            output: None,
+            primary: false,
+            area,
+            scale: 1.0,
+            rotation: 0,
+            workspace,
+            combined: Vec::new(),
+            history: History::new(workspace),
+        }
+    }
+
+    /// Indices of all workspaces shown on the monitor, the main one first.
+    pub fn workspaces(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::once(self.workspace).chain(self.combined.iter().copied())
+    }
+
+    pub fn shows(&self, workspace: usize) -> bool {
+        self.workspaces().any(|index| index == workspace)
+    }
+
+    /// Taller than wide, usually a rotated output.
+    pub fn portrait(&self) -> bool {
+        self.area.height > self.area.width
+    }
+}
+
+/// Scale of an output with `dpi`, relative to 96 DPI in steps of a half. Outputs are never
+/// scaled down.
+pub fn scale_for_dpi(dpi: f32) -> f32 {
+    ((dpi / 96.0 * 2.0).round() / 2.0).max(1.0)
+}
+
+/// Index of the monitor closest to `current` in the `direction`, preferring the ones
+/// lined up with it.
+pub fn adjacent(monitors: &[Monitor], current: usize, direction: Direction) -> Option<usize> {
+    let from = monitors.get(current)?.area;
+    let (x, y) = from.center();
+
+    monitors
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != current)
+        .filter_map(|(index, monitor)| {
+            let area = monitor.area;
+            let (dx, dy) = (area.center().0 - x, area.center().1 - y);
+            let (distance, lined_up) = match direction {
+                Direction::Left if dx < 0 => {
+                    (-dx, area.y < from.bottom() && from.y < area.bottom())
+                }
+                Direction::Right if dx > 0 => {
+                    (dx, area.y < from.bottom() && from.y < area.bottom())
+                }
+                Direction::Up if dy < 0 => (-dy, area.x < from.right() && from.x < area.right()),
+                Direction::Down if dy > 0 => (dy, area.x < from.right() && from.x < area.right()),
+                _ => return None,
+            };
+            Some((index, !lined_up, distance))
+        })
+        .min_by_key(|(_, not_lined_up, distance)| (*not_lined_up, *distance))
+        .map(|(index, _, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Monitor;
+    use crate::geometry::{Direction, Rect};
+
+    #[test]
+    fn shows_main_and_combined_workspaces() {
+        let mut monitor = Monitor::new(Rect::new(0, 0, 800, 600), 2);
+        monitor.combined = vec![0, 4];
+
+        assert_eq!(monitor.workspaces().collect::<Vec<_>>(), vec![2, 0, 4]);
+        assert!(monitor.shows(4));
+        assert!(!monitor.shows(1));
+    }
+
+    #[test]
+    fn scales_by_resolution() {
+        assert_eq!(super::scale_for_dpi(96.0), 1.0);
+        // 27" 1440p and 24" 1080p desktop monitors.
+        assert_eq!(super::scale_for_dpi(109.0), 1.0);
+        assert_eq!(super::scale_for_dpi(92.0), 1.0);
+        // 14" 1440p and 13" 4K laptop panels.
+        assert_eq!(super::scale_for_dpi(210.0), 2.0);
+        assert_eq!(super::scale_for_dpi(340.0), 3.5);
+        assert_eq!(super::scale_for_dpi(150.0), 1.5);
+        assert_eq!(super::scale_for_dpi(60.0), 1.0);
+    }
+
+    #[test]
+    fn finds_adjacent_monitors() {
+        // Laptop panel below the middle of two external monitors.
+        let monitors = [
+            Monitor::new(Rect::new(0, 0, 1920, 1080), 0),
+            Monitor::new(Rect::new(1920, 0, 1920, 1080), 1),
+            Monitor::new(Rect::new(1280, 1080, 1280, 800), 2),
+        ];
+
+        assert_eq!(super::adjacent(&monitors, 0, Direction::Right), Some(1));
+        assert_eq!(super::adjacent(&monitors, 0, Direction::Left), None);
+        assert_eq!(super::adjacent(&monitors, 1, Direction::Left), Some(0));
+        assert_eq!(super::adjacent(&monitors, 0, Direction::Down), Some(2));
+        assert_eq!(super::adjacent(&monitors, 2, Direction::Up), Some(0));
+        // Not lined up with the panel, but still in that direction.
+        assert_eq!(super::adjacent(&monitors, 2, Direction::Right), Some(1));
+    }
+}
@@ -0,0 +1,83 @@
+const MWM_HINTS_FUNCTIONS: u32 = 1 << 0;
+const MWM_HINTS_DECORATIONS: u32 = 1 << 1;
+
+const MWM_FUNC_ALL: u32 = 1 << 0;
+const MWM_FUNC_RESIZE: u32 = 1 << 1;
+const MWM_FUNC_MOVE: u32 = 1 << 2;
+
+const MWM_DECOR_ALL: u32 = 1 << 0;
+const MWM_DECOR_BORDER: u32 = 1 << 1;
+
+/// Decorations and functions a client wants from the window manager (_MOTIF_WM_HINTS),
+/// clients drawing their own decorations usually turn them all off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MotifHints {
+    /// Gets a border.
+    pub decorated: bool,
+    /// Can be resized with the pointer and maximized.
+    pub resizable: bool,
+    /// Can be moved with the pointer.
+    pub movable: bool,
+}
+
+impl Default for MotifHints {
+    fn default() -> Self {
+        Self {
+            decorated: true,
+            resizable: true,
+            movable: true,
+        }
+    }
+}
+
+impl MotifHints {
+    /// Parses the 32-bit values of a _MOTIF_WM_HINTS property.
+    pub fn from_values(values: &[u32]) -> Self {
+        let value = |index: usize| values.get(index).copied().unwrap_or(0);
+        // With the ALL bit set, the other bits list the exceptions.
+        let allows = |mask: u32, all: u32, bit: u32| (mask & all != 0) != (mask & bit != 0);
+
+        let mut hints = Self::default();
+        if value(0) & MWM_HINTS_FUNCTIONS != 0 {
+            let functions = value(1);
+            hints.resizable = allows(functions, MWM_FUNC_ALL, MWM_FUNC_RESIZE);
+            hints.movable = allows(functions, MWM_FUNC_ALL, MWM_FUNC_MOVE);
+        }
+        if value(0) & MWM_HINTS_DECORATIONS != 0 {
+            hints.decorated = allows(value(2), MWM_DECOR_ALL, MWM_DECOR_BORDER);
+        }
+        hints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MotifHints;
+
+    #[test]
+    fn parses_decorations_and_functions() {
+        // Client side decorations: no decorations, all functions.
+        assert_eq!(
+            MotifHints::from_values(&[2, 0, 0, 0, 0]),
+            MotifHints {
+                decorated: false,
+                resizable: true,
+                movable: true,
+            }
+        );
+
+        // All functions but resizing, border only.
+        assert_eq!(
+            MotifHints::from_values(&[3, 1 | 2, 2, 0, 0]),
+            MotifHints {
+                decorated: true,
+                resizable: false,
+                movable: true,
+            }
+        );
+
+        // All decorations but the border.
+        assert!(!MotifHints::from_values(&[2, 0, 1 | 2]).decorated);
+        assert_eq!(MotifHints::from_values(&[]), MotifHints::default());
+    }
+}
@@ -0,0 +1,849 @@
+use crate::geometry::{Direction, Rect};
+use crate::size_hints::SizeHints;
+use crate::App;
+use log::{debug, trace};
+use std::time::{Duration, Instant};
+use xcb::{x, Xid};
+use xkbcommon::xkb;
+
+/// Pointer drag in progress, started by a modifier+button press on a client or by the
+/// client itself with _NET_WM_MOVERESIZE.
+#[derive(Debug)]
+pub enum Drag {
+    /// Moves the shared edges of a tiled client nearest to where the drag started,
+    /// None leaves the edges along that axis alone.
+    ResizeTiled {
+        window: x::Window,
+        horizontal: Option<Direction>,
+        vertical: Option<Direction>,
+        last: (i16, i16),
+    },
+    /// Moves a floating client along with the pointer.
+    Move {
+        window: x::Window,
+        /// Pointer position when the drag started.
+        start: (i16, i16),
+        /// Client position when the drag started.
+        origin: (i32, i32),
+        /// When the client was last moved.
+        moved_at: Instant,
+        /// Position not applied yet because of the rate limit.
+        pending: Option<(i32, i32)>,
+    },
+    /// Swaps a tiled client with the one it is dropped on.
+    Swap {
+        window: x::Window,
+        /// Client under the pointer, highlighted as the drop target.
+        target: Option<x::Window>,
+    },
+    /// Resizes a floating client by the corner nearest to where the drag started, or by
+    /// a single edge.
+    ResizeFloating {
+        window: x::Window,
+        horizontal: Option<Direction>,
+        vertical: Option<Direction>,
+        start: (i16, i16),
+        /// Client geometry when the drag started.
+        origin: Rect,
+        moved_at: Instant,
+        pending: Option<Rect>,
+    },
+}
+
+impl Drag {
+    /// Client being dragged.
+    fn window(&self) -> x::Window {
+        match self {
+            Drag::ResizeTiled { window, .. }
+            | Drag::Move { window, .. }
+            | Drag::Swap { window, .. }
+            | Drag::ResizeFloating { window, .. } => *window,
+        }
+    }
+}
+
+/// Offset moving the span `start..end` so that one of its ends touches one of `edges`,
+/// the smallest one within `threshold`.
+fn snap_offset(start: i32, end: i32, edges: &[i32], threshold: u32) -> i32 {
+    edges
+        .iter()
+        .flat_map(|edge| [edge - start, edge - end])
+        .filter(|offset| offset.unsigned_abs() <= threshold)
+        .min_by_key(|offset| offset.abs())
+        .unwrap_or(0)
+}
+
+/// Moves `frame` so its edges stick to the nearby edges of `targets` (screen areas and
+/// other windows).
+fn snap(frame: Rect, targets: &[Rect], threshold: u32) -> Rect {
+    let horizontal: Vec<i32> = targets.iter().flat_map(|r| [r.x, r.right()]).collect();
+    let vertical: Vec<i32> = targets.iter().flat_map(|r| [r.y, r.bottom()]).collect();
+
+    Rect::new(
+        frame.x + snap_offset(frame.x, frame.right(), &horizontal, threshold),
+        frame.y + snap_offset(frame.y, frame.bottom(), &vertical, threshold),
+        frame.width,
+        frame.height,
+    )
+}
+
+/// Geometry of a client resized from `origin` by dragging the corner facing `horizontal`
+/// and `vertical` by `delta`, keeping the opposite corner in place. A missing direction
+/// drags a single edge.
+fn resize_by_corner(
+    origin: Rect,
+    horizontal: Option<Direction>,
+    vertical: Option<Direction>,
+    (dx, dy): (i32, i32),
+    hints: &SizeHints,
+) -> Rect {
+    let grow = |size: u32, delta: i32| (size as i32 + delta).max(1) as u32;
+
+    let width = match horizontal {
+        Some(Direction::Right) => grow(origin.width, dx),
+        Some(_) => grow(origin.width, -dx),
+        None => origin.width,
+    };
+    let height = match vertical {
+        Some(Direction::Down) => grow(origin.height, dy),
+        Some(_) => grow(origin.height, -dy),
+        None => origin.height,
+    };
+    // The axis the pointer moved along the most decides the proportions.
+    let (width, height) = hints.keep_aspect(width, height, dx.abs() >= dy.abs());
+    let (width, height) = hints.constrain(width, height);
+
+    let x = match horizontal {
+        Some(Direction::Right) | None => origin.x,
+        Some(_) => origin.right() - width as i32,
+    };
+    let y = match vertical {
+        Some(Direction::Down) | None => origin.y,
+        Some(_) => origin.bottom() - height as i32,
+    };
+
+    Rect::new(x, y, width, height)
+}
+
+/// Edges dragged for a _NET_WM_MOVERESIZE direction, None for the directions that do not
+/// resize with the pointer.
+fn moveresize_edges(direction: u32) -> Option<(Option<Direction>, Option<Direction>)> {
+    use Direction::{Down, Left, Right, Up};
+
+    match direction {
+        MOVERESIZE_SIZE_TOPLEFT => Some((Some(Left), Some(Up))),
+        MOVERESIZE_SIZE_TOP => Some((None, Some(Up))),
+        MOVERESIZE_SIZE_TOPRIGHT => Some((Some(Right), Some(Up))),
+        MOVERESIZE_SIZE_RIGHT => Some((Some(Right), None)),
+        MOVERESIZE_SIZE_BOTTOMRIGHT => Some((Some(Right), Some(Down))),
+        MOVERESIZE_SIZE_BOTTOM => Some((None, Some(Down))),
+        MOVERESIZE_SIZE_BOTTOMLEFT => Some((Some(Left), Some(Down))),
+        MOVERESIZE_SIZE_LEFT => Some((Some(Left), None)),
+        _ => None,
+    }
+}
+
+/// How the pointer moves the input focus.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FocusModel {
+    /// Focus follows the pointer into windows and stays put over the desktop.
+    #[default]
+    Sloppy,
+    /// Focus follows the pointer, leaving all windows removes it.
+    Strict,
+    /// Clicking a window focuses and raises it, the click still reaches the window.
+    Click,
+}
+
+const FOCUS_BUTTON: x::ButtonIndex = x::ButtonIndex::N1;
+
+const MOVE_BUTTON: x::ButtonIndex = x::ButtonIndex::N1;
+const RESIZE_BUTTON: x::ButtonIndex = x::ButtonIndex::N3;
+
+/// Minimal time between two moves of a dragged client, about one frame.
+const DRAG_INTERVAL: Duration = Duration::from_millis(16);
+
+// _NET_WM_MOVERESIZE directions, the keyboard driven ones are not supported.
+const MOVERESIZE_SIZE_TOPLEFT: u32 = 0;
+const MOVERESIZE_SIZE_TOP: u32 = 1;
+const MOVERESIZE_SIZE_TOPRIGHT: u32 = 2;
+const MOVERESIZE_SIZE_RIGHT: u32 = 3;
+const MOVERESIZE_SIZE_BOTTOMRIGHT: u32 = 4;
+const MOVERESIZE_SIZE_BOTTOM: u32 = 5;
+const MOVERESIZE_SIZE_BOTTOMLEFT: u32 = 6;
+const MOVERESIZE_SIZE_LEFT: u32 = 7;
+const MOVERESIZE_MOVE: u32 = 8;
+const MOVERESIZE_CANCEL: u32 = 11;
+
+impl App {
+    pub(crate) fn grab_buttons(&self) {
+        let numlock_index = self.keyboard.get_mod_index(xkb::MOD_NAME_NUM);
+        let numlock_mask =
+            x::ModMask::from_bits(1 << numlock_index).expect("Expected a valid numlock modmask");
+
+        let mask = x::ModMask::N4;
+        for modifiers in [
+            mask,
+            mask | numlock_mask,
+            mask | x::ModMask::LOCK,
+            mask | x::ModMask::LOCK | numlock_mask,
+        ] {
+            for button in [MOVE_BUTTON, RESIZE_BUTTON] {
+                self.conn.send_request(&x::GrabButton {
+                    owner_events: false,
+                    grab_window: self.root,
+                    event_mask: x::EventMask::BUTTON_PRESS
+                        | x::EventMask::BUTTON_RELEASE
+                        | x::EventMask::POINTER_MOTION,
+                    pointer_mode: x::GrabMode::Async,
+                    keyboard_mode: x::GrabMode::Async,
+                    confine_to: x::Window::none(),
+                    cursor: x::Cursor::none(),
+                    button,
+                    modifiers,
+                });
+            }
+        }
+
+        self.conn.flush().expect("Flushed");
+    }
+
+    /// Grabs (or releases) the focus button on a client, so with click to focus the first
+    /// click on an unfocused client focuses it before being replayed.
+    pub(crate) fn grab_focus_button(&self, window: x::Window, grab: bool) {
+        if self.config.focus_model != FocusModel::Click {
+            return;
+        }
+
+        if grab {
+            self.conn.send_request(&x::GrabButton {
+                owner_events: false,
+                grab_window: window,
+                event_mask: x::EventMask::BUTTON_PRESS,
+                pointer_mode: x::GrabMode::Sync,
+                keyboard_mode: x::GrabMode::Async,
+                confine_to: x::Window::none(),
+                cursor: x::Cursor::none(),
+                button: FOCUS_BUTTON,
+                modifiers: x::ModMask::ANY,
+            });
+        } else {
+            self.conn.send_request(&x::UngrabButton {
+                button: FOCUS_BUTTON,
+                grab_window: window,
+                modifiers: x::ModMask::ANY,
+            });
+        }
+    }
+
+    pub(crate) fn handle_button_press(&mut self, event: &x::ButtonPressEvent) {
+        trace!("{:?}", event);
+
+        self.pointer = (event.root_x(), event.root_y());
+
+        // Only the focus button is grabbed on clients, the others are grabbed on the root.
+        if event.event() != self.root {
+            let window = event.event();
+            if self.focus_pointer_window(window) {
+                self.raise(window);
+            }
+
+            self.conn.send_request(&x::AllowEvents {
+                mode: x::Allow::ReplayPointer,
+                time: x::CURRENT_TIME,
+            });
+            return;
+        }
+
+        let window = event.child();
+        let frame = match self.clients.get(&window.resource_id()) {
+            Some(client) => client.frame(),
+            None => return,
+        };
+
+        // The corner or edges to drag are in the quadrant of the press.
+        let (center_x, center_y) = frame.center();
+        let pointer = (event.root_x(), event.root_y());
+        let button = event.detail();
+
+        let horizontal = if (pointer.0 as i32) < center_x {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        let vertical = if (pointer.1 as i32) < center_y {
+            Direction::Up
+        } else {
+            Direction::Down
+        };
+
+        if button == MOVE_BUTTON as u8 || button == RESIZE_BUTTON as u8 {
+            self.start_drag(
+                window,
+                pointer,
+                button == MOVE_BUTTON as u8,
+                (Some(horizontal), Some(vertical)),
+            );
+        }
+    }
+
+    /// Starts moving the client with the pointer at `pointer`, or resizing it by the given
+    /// edges. Returns false if the client cannot be dragged.
+    fn start_drag(
+        &mut self,
+        window: x::Window,
+        pointer: (i16, i16),
+        moving: bool,
+        (horizontal, vertical): (Option<Direction>, Option<Direction>),
+    ) -> bool {
+        let (geometry, floating, motif_hints) = match self.clients.get(&window.resource_id()) {
+            Some(client) if self.visible_windows().contains(&window) => {
+                (client.geometry, client.floating, client.motif_hints)
+            }
+            _ => return false,
+        };
+
+        // Floating clients may refuse to be moved or resized through their Motif hints.
+        let allowed = if moving {
+            motif_hints.movable
+        } else {
+            motif_hints.resizable
+        };
+        if floating && !allowed {
+            return false;
+        }
+
+        if moving && floating {
+            self.drag = Some(Drag::Move {
+                window,
+                start: pointer,
+                origin: (geometry.x, geometry.y),
+                moved_at: Instant::now(),
+                pending: None,
+            });
+        } else if floating {
+            self.drag = Some(Drag::ResizeFloating {
+                window,
+                horizontal,
+                vertical,
+                start: pointer,
+                origin: geometry,
+                moved_at: Instant::now(),
+                pending: None,
+            });
+        } else if moving && self.workspace().contains(window) {
+            self.drag = Some(Drag::Swap {
+                window,
+                target: None,
+            });
+        } else if self.workspace().contains(window) {
+            self.drag = Some(Drag::ResizeTiled {
+                window,
+                horizontal,
+                vertical,
+                last: pointer,
+            });
+        } else {
+            return false;
+        }
+
+        if floating {
+            self.focus_pointer_window(window);
+            self.raise(window);
+        }
+
+        debug!("Drag started: {:?}", self.drag);
+        true
+    }
+
+    /// Handles _NET_WM_MOVERESIZE, sent by clients drawing their own title bar or borders
+    /// when the user presses a button on them.
+    pub(crate) fn handle_moveresize_request(&mut self, window: x::Window, data: &[u32; 5]) {
+        let direction = data[2];
+
+        if direction == MOVERESIZE_CANCEL {
+            if self.drag.as_ref().map(Drag::window) == Some(window) {
+                self.finish_drag();
+            }
+            return;
+        }
+
+        let edges = match direction {
+            MOVERESIZE_MOVE => (None, None),
+            direction => match moveresize_edges(direction) {
+                Some(edges) => edges,
+                None => return,
+            },
+        };
+
+        // The button may be released by the time the request arrives, the grab would
+        // then never end.
+        let buttons = x::KeyButMask::BUTTON1
+            | x::KeyButMask::BUTTON2
+            | x::KeyButMask::BUTTON3
+            | x::KeyButMask::BUTTON4
+            | x::KeyButMask::BUTTON5;
+        let cookie = self
+            .conn
+            .send_request(&x::QueryPointer { window: self.root });
+        match self.conn.wait_for_reply(cookie) {
+            Ok(reply) if reply.mask().intersects(buttons) => {}
+            _ => return,
+        }
+
+        let pointer = (data[0] as i16, data[1] as i16);
+        if self.drag.is_some()
+            || !self.start_drag(window, pointer, direction == MOVERESIZE_MOVE, edges)
+        {
+            return;
+        }
+
+        // The client gave up its own grab, the motion has to reach the root now.
+        let cookie = self.conn.send_request(&x::GrabPointer {
+            owner_events: false,
+            grab_window: self.root,
+            event_mask: x::EventMask::BUTTON_RELEASE | x::EventMask::POINTER_MOTION,
+            pointer_mode: x::GrabMode::Async,
+            keyboard_mode: x::GrabMode::Async,
+            confine_to: x::Window::none(),
+            cursor: x::Cursor::none(),
+            time: x::CURRENT_TIME,
+        });
+        match self.conn.wait_for_reply(cookie) {
+            Ok(reply) if reply.status() == x::GrabStatus::Success => {}
+            result => {
+                debug!("Pointer grab for {:?} failed: {:?}", window, result);
+                self.drag = None;
+            }
+        }
+    }
+
+    pub(crate) fn handle_motion(&mut self, event: &x::MotionNotifyEvent) {
+        let pointer = (event.root_x(), event.root_y());
+        self.pointer = pointer;
+
+        match &self.drag {
+            Some(Drag::ResizeTiled { .. }) => self.drag_resize_tiled(pointer),
+            Some(Drag::Move { .. }) => self.drag_move(pointer),
+            Some(Drag::ResizeFloating { .. }) => self.drag_resize_floating(pointer),
+            Some(Drag::Swap { .. }) => self.drag_swap(pointer),
+            None => {}
+        }
+    }
+
+    fn drag_move(&mut self, pointer: (i16, i16)) {
+        let (size, targets) = match &self.drag {
+            Some(Drag::Move { window, .. }) => (
+                self.clients
+                    .get(&window.resource_id())
+                    .map(|client| client.frame())
+                    .unwrap_or_default(),
+                self.snap_targets(*window),
+            ),
+            _ => return,
+        };
+        let threshold = self.config.snap_threshold;
+
+        let (window, position) = match &mut self.drag {
+            Some(Drag::Move {
+                window,
+                start,
+                origin,
+                moved_at,
+                pending,
+            }) => {
+                let frame = snap(
+                    Rect::new(
+                        origin.0 + (pointer.0 - start.0) as i32,
+                        origin.1 + (pointer.1 - start.1) as i32,
+                        size.width,
+                        size.height,
+                    ),
+                    &targets,
+                    threshold,
+                );
+                let position = (frame.x, frame.y);
+
+                if moved_at.elapsed() < DRAG_INTERVAL {
+                    *pending = Some(position);
+                    return;
+                }
+
+                *moved_at = Instant::now();
+                *pending = None;
+                (*window, position)
+            }
+            _ => return,
+        };
+
+        self.move_window(window, position);
+    }
+
+    /// Areas a moved window snaps to: the work areas and the other visible clients.
+    fn snap_targets(&self, window: x::Window) -> Vec<Rect> {
+        let areas = self.monitors.iter().map(|monitor| self.work_area(monitor));
+        let clients = self
+            .visible_windows()
+            .into_iter()
+            .filter(|other| *other != window)
+            .filter_map(|other| self.clients.get(&other.resource_id()))
+            .map(|client| client.frame());
+
+        areas.chain(clients).collect()
+    }
+
+    pub(crate) fn move_window(&self, window: x::Window, (x, y): (i32, i32)) {
+        self.conn.send_request(&x::ConfigureWindow {
+            window,
+            value_list: &[x::ConfigWindow::X(x), x::ConfigWindow::Y(y)],
+        });
+    }
+
+    fn drag_swap(&mut self, pointer: (i16, i16)) {
+        let (window, previous) = match &self.drag {
+            Some(Drag::Swap { window, target }) => (*window, *target),
+            _ => return,
+        };
+
+        let point = (pointer.0.into(), pointer.1.into());
+        let target = self.workspace().clients.iter().copied().find(|other| {
+            *other != window
+                && self
+                    .clients
+                    .get(&other.resource_id())
+                    .is_some_and(|client| client.is_tiled() && client.frame().contains(point))
+        });
+
+        if target == previous {
+            return;
+        }
+
+        if let Some(previous) = previous {
+            self.update_border(previous);
+        }
+        if let Some(target) = target {
+            self.conn.send_request(&x::ChangeWindowAttributes {
+                window: target,
+                value_list: &[x::Cw::BorderPixel(self.config.border_color_drop)],
+            });
+        }
+
+        self.drag = Some(Drag::Swap { window, target });
+    }
+
+    fn drag_resize_floating(&mut self, pointer: (i16, i16)) {
+        let hints = match &self.drag {
+            Some(Drag::ResizeFloating { window, .. }) => self
+                .clients
+                .get(&window.resource_id())
+                .map(|client| client.size_hints)
+                .unwrap_or_default(),
+            _ => return,
+        };
+
+        let (window, geometry) = match &mut self.drag {
+            Some(Drag::ResizeFloating {
+                window,
+                horizontal,
+                vertical,
+                start,
+                origin,
+                moved_at,
+                pending,
+            }) => {
+                let delta = ((pointer.0 - start.0).into(), (pointer.1 - start.1).into());
+                let geometry = resize_by_corner(*origin, *horizontal, *vertical, delta, &hints);
+
+                if moved_at.elapsed() < DRAG_INTERVAL {
+                    *pending = Some(geometry);
+                    return;
+                }
+
+                *moved_at = Instant::now();
+                *pending = None;
+                (*window, geometry)
+            }
+            _ => return,
+        };
+
+        self.configure_window(window, geometry);
+    }
+
+    pub(crate) fn configure_window(&self, window: x::Window, geometry: Rect) {
+        self.conn.send_request(&x::ConfigureWindow {
+            window,
+            value_list: &[
+                x::ConfigWindow::X(geometry.x),
+                x::ConfigWindow::Y(geometry.y),
+                x::ConfigWindow::Width(geometry.width),
+                x::ConfigWindow::Height(geometry.height),
+            ],
+        });
+    }
+
+    fn drag_resize_tiled(&mut self, pointer: (i16, i16)) {
+        // Deltas are relative to the monitor of the dragged client.
+        let area = match &self.drag {
+            Some(Drag::ResizeTiled { window, .. }) => self
+                .clients
+                .get(&window.resource_id())
+                .and_then(|client| self.monitors.get(client.monitor))
+                .map_or_else(|| self.work_area(self.monitor()), |m| self.work_area(m)),
+            _ => return,
+        };
+
+        if let Some(Drag::ResizeTiled {
+            window,
+            horizontal,
+            vertical,
+            last,
+        }) = &mut self.drag
+        {
+            let (window, horizontal, vertical) = (*window, *horizontal, *vertical);
+            let dx = (pointer.0 - last.0) as f32 / area.width as f32;
+            let dy = (pointer.1 - last.1) as f32 / area.height as f32;
+            *last = pointer;
+
+            // Moving the pointer towards the dragged edge grows the client.
+            if let Some(horizontal) = horizontal {
+                let dx = if horizontal == Direction::Right {
+                    dx
+                } else {
+                    -dx
+                };
+                self.resize_tiled(window, horizontal, dx);
+            }
+            if let Some(vertical) = vertical {
+                let dy = if vertical == Direction::Down { dy } else { -dy };
+                self.resize_tiled(window, vertical, dy);
+            }
+        }
+    }
+
+    /// Focuses the client the pointer entered. Windows moved under a still pointer by
+    /// the layout only take the focus if the config allows it.
+    pub(crate) fn handle_enter(&mut self, event: &x::EnterNotifyEvent) {
+        if self.config.focus_model == FocusModel::Click
+            || event.mode() != x::NotifyMode::Normal
+            || self.drag.is_some()
+        {
+            return;
+        }
+
+        let pointer = (event.root_x(), event.root_y());
+        let moved = pointer != self.pointer;
+        self.pointer = pointer;
+        if !moved && !self.config.focus_on_layout_change {
+            return;
+        }
+
+        let window = event.event();
+        if window == self.root {
+            if self.config.focus_model == FocusModel::Strict
+                && event.detail() == x::NotifyDetail::Inferior
+            {
+                self.unfocus();
+            }
+            return;
+        }
+
+        trace!("Pointer entered {:?}", window);
+        self.focus_pointer_window(window);
+    }
+
+    /// Focuses a visible client picked with the pointer, making its monitor the current one.
+    /// Returns false for windows that are not tiled or floating clients, like docks.
+    fn focus_pointer_window(&mut self, window: x::Window) -> bool {
+        let monitor = match self
+            .monitors
+            .iter()
+            .position(|monitor| self.monitor_clients(monitor).contains(&window))
+        {
+            Some(monitor) => monitor,
+            None => return false,
+        };
+
+        if self.focused == Some(window) {
+            return true;
+        }
+
+        if monitor != self.current_monitor {
+            self.current_monitor = monitor;
+            self.publish_desktops();
+        }
+        self.focus(window);
+        self.arrange();
+        true
+    }
+
+    /// Moves the pointer to the center of the focused client, or of the current monitor if
+    /// nothing is focused there. Left alone if it already points at the target.
+    pub(crate) fn warp_pointer(&mut self) {
+        let target = match self
+            .focused
+            .and_then(|window| self.clients.get(&window.resource_id()))
+        {
+            Some(client)
+                if self
+                    .monitor_clients(self.monitor())
+                    .contains(&client.window) =>
+            {
+                client.geometry.with_border(client.border_width)
+            }
+            _ => self.monitor().area,
+        };
+
+        let cookie = self
+            .conn
+            .send_request(&x::QueryPointer { window: self.root });
+        if let Ok(reply) = self.conn.wait_for_reply(cookie) {
+            if target.contains((reply.root_x().into(), reply.root_y().into())) {
+                return;
+            }
+        }
+
+        let (x, y) = target.center();
+        trace!("Warping pointer to {}x{}", x, y);
+        self.conn.send_request(&x::WarpPointer {
+            src_window: x::Window::none(),
+            dst_window: self.root,
+            src_x: 0,
+            src_y: 0,
+            src_width: 0,
+            src_height: 0,
+            dst_x: x as i16,
+            dst_y: y as i16,
+        });
+        // The warp is not a pointer move of the user's.
+        self.pointer = (x as i16, y as i16);
+        self.conn.flush().expect("Flushed");
+    }
+
+    pub(crate) fn handle_button_release(&mut self, event: &x::ButtonReleaseEvent) {
+        trace!("{:?}", event);
+
+        self.finish_drag();
+    }
+
+    /// Applies the last pending step of the drag and ends it.
+    fn finish_drag(&mut self) {
+        if self.drag.is_some() {
+            // Drags started by a client hold an active grab.
+            self.conn.send_request(&x::UngrabPointer {
+                time: x::CURRENT_TIME,
+            });
+        }
+
+        match self.drag.take() {
+            Some(Drag::Move {
+                window,
+                pending: Some(position),
+                ..
+            }) => {
+                self.move_window(window, position);
+                debug!("Drag finished");
+            }
+            Some(Drag::ResizeFloating {
+                window,
+                pending: Some(geometry),
+                ..
+            }) => {
+                self.configure_window(window, geometry);
+                debug!("Drag finished");
+            }
+            Some(Drag::Swap {
+                window,
+                target: Some(target),
+            }) => {
+                self.update_border(target);
+                if self.workspace_mut().swap(window, target) {
+                    debug!("Swapped {:?} with {:?}", window, target);
+                    self.arrange();
+                }
+            }
+            Some(_) => debug!("Drag finished"),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resize_by_corner, snap};
+    use crate::geometry::{Direction, Rect};
+    use crate::size_hints::SizeHints;
+
+    #[test]
+    fn resizing_keeps_opposite_corner() {
+        let origin = Rect::new(100, 100, 200, 100);
+        let hints = SizeHints::default();
+
+        assert_eq!(
+            resize_by_corner(
+                origin,
+                Some(Direction::Right),
+                Some(Direction::Down),
+                (50, 20),
+                &hints
+            ),
+            Rect::new(100, 100, 250, 120)
+        );
+        assert_eq!(
+            resize_by_corner(
+                origin,
+                Some(Direction::Left),
+                Some(Direction::Up),
+                (50, 20),
+                &hints
+            ),
+            Rect::new(150, 120, 150, 80)
+        );
+        assert_eq!(
+            resize_by_corner(origin, None, Some(Direction::Up), (50, 20), &hints),
+            Rect::new(100, 120, 200, 80)
+        );
+    }
+
+    #[test]
+    fn resizing_respects_size_hints() {
+        let origin = Rect::new(0, 0, 200, 100);
+        let hints = SizeHints {
+            min: Some((150, 50)),
+            increment: Some((10, 10)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            resize_by_corner(
+                origin,
+                Some(Direction::Left),
+                Some(Direction::Down),
+                (100, 37),
+                &hints
+            ),
+            Rect::new(50, 0, 150, 130)
+        );
+    }
+
+    #[test]
+    fn snaps_to_nearby_edges() {
+        let screen = Rect::new(0, 0, 1000, 800);
+        let other = Rect::new(500, 0, 200, 200);
+
+        // Left edge to the screen, right edge to the other window.
+        assert_eq!(
+            snap(Rect::new(6, 300, 100, 100), &[screen, other], 10),
+            Rect::new(0, 300, 100, 100)
+        );
+        assert_eq!(
+            snap(Rect::new(395, 205, 100, 100), &[screen, other], 10),
+            Rect::new(400, 200, 100, 100)
+        );
+
+        // Too far from any edge.
+        assert_eq!(
+            snap(Rect::new(50, 300, 100, 100), &[screen, other], 10),
+            Rect::new(50, 300, 100, 100)
+        );
+    }
+}
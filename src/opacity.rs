@@ -0,0 +1,33 @@
+/// Opacity in percent of a client that is `focused` or not, given the one set for it by a
+/// rule or a command and the one of unfocused clients. Both apply to unfocused clients.
+pub fn effective(own: Option<u8>, focused: bool, inactive: Option<u8>) -> u8 {
+    let own = own.unwrap_or(100).min(100);
+    match inactive {
+        Some(inactive) if !focused => (u16::from(own) * u16::from(inactive.min(100)) / 100) as u8,
+        _ => own,
+    }
+}
+
+/// Value of _NET_WM_WINDOW_OPACITY, where 0xFFFFFFFF is fully opaque.
+pub fn cardinal(percent: u8) -> u32 {
+    (u64::from(percent.min(100)) * u64::from(u32::MAX) / 100) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn dims_unfocused_clients() {
+        assert_eq!(super::effective(None, true, Some(80)), 100);
+        assert_eq!(super::effective(None, false, Some(80)), 80);
+        assert_eq!(super::effective(Some(50), true, Some(80)), 50);
+        assert_eq!(super::effective(Some(50), false, Some(80)), 40);
+        assert_eq!(super::effective(Some(50), false, None), 50);
+    }
+
+    #[test]
+    fn scales_to_cardinal() {
+        assert_eq!(super::cardinal(100), u32::MAX);
+        assert_eq!(super::cardinal(0), 0);
+        assert_eq!(super::cardinal(50), u32::MAX / 2);
+    }
+}
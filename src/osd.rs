@@ -0,0 +1,242 @@
+use crate::config::Config;
+use crate::geometry::Rect;
+use crate::icon::Icon;
+use std::time::{Duration, Instant};
+use xcb::x;
+
+const PADDING: u32 = 16;
+
+/// Size of the client icons shown next to the text.
+pub const ICON_SIZE: u32 = 32;
+
+/// Where the OSD appears on the monitor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OsdPosition {
+    #[allow(dead_code)] // The built-in config keeps the default position.
+    Top,
+    #[default]
+    Center,
+    #[allow(dead_code)] // The built-in config keeps the default position.
+    Bottom,
+}
+
+/// Short-lived on-screen message, such as the name of the workspace switched to or the
+/// client picked while cycling the focus.
+pub struct Osd {
+    window: x::Window,
+    gc: x::Gcontext,
+    depth: u8,
+    background: u32,
+    char_width: u32,
+    ascent: u32,
+    descent: u32,
+    text: String,
+    icon: Option<Icon>,
+    hide_at: Option<Instant>,
+}
+
+impl Osd {
+    pub fn new(
+        conn: &xcb::Connection,
+        screen: &x::Screen,
+        config: &Config,
+    ) -> anyhow::Result<Self> {
+        let font: x::Font = conn.generate_id();
+        conn.check_request(conn.send_request_checked(&x::OpenFont {
+            fid: font,
+            name: config.osd_font.as_bytes(),
+        }))?;
+
+        let info = conn.wait_for_reply(conn.send_request(&x::QueryFont {
+            font: x::Fontable::Font(font),
+        }))?;
+
+        let window: x::Window = conn.generate_id();
+        conn.send_request(&x::CreateWindow {
+            depth: x::COPY_FROM_PARENT as u8,
+            wid: window,
+            parent: screen.root(),
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            border_width: 0,
+            class: x::WindowClass::InputOutput,
+            visual: screen.root_visual(),
+            value_list: &[
+                x::Cw::BackPixel(config.osd_background),
+                x::Cw::OverrideRedirect(true),
+                x::Cw::EventMask(x::EventMask::EXPOSURE),
+            ],
+        });
+
+        let gc: x::Gcontext = conn.generate_id();
+        conn.send_request(&x::CreateGc {
+            cid: gc,
+            drawable: x::Drawable::Window(window),
+            value_list: &[
+                x::Gc::Foreground(config.osd_foreground),
+                x::Gc::Background(config.osd_background),
+                x::Gc::Font(font),
+            ],
+        });
+
+        conn.send_request(&x::CloseFont { font });
+
+        Ok(Self {
+            window,
+            gc,
+            depth: screen.root_depth(),
+            background: config.osd_background,
+            char_width: info.max_bounds().character_width.max(1) as u32,
+            ascent: info.font_ascent().max(0) as u32,
+            descent: info.font_descent().max(0) as u32,
+            text: String::new(),
+            icon: None,
+            hide_at: None,
+        })
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    /// Shows `text` on top of everything within `area`, for the configured time.
+    pub fn show(&mut self, conn: &xcb::Connection, config: &Config, text: &str, area: Rect) {
+        self.show_with_icon(conn, config, text, None, area);
+    }
+
+    /// Like [`Osd::show`], with an icon before the text.
+    pub fn show_with_icon(
+        &mut self,
+        conn: &xcb::Connection,
+        config: &Config,
+        text: &str,
+        icon: Option<Icon>,
+        area: Rect,
+    ) {
+        let timeout = match config.osd_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+
+        self.text = text.to_owned();
+        // Pixels are sent as 32-bit words, only fitting 24 and 32-bit visuals.
+        self.icon = icon.filter(|_| self.depth == 24 || self.depth == 32);
+        self.hide_at = Some(Instant::now() + timeout);
+
+        let icon_width = self.icon.as_ref().map_or(0, |icon| icon.width + PADDING);
+        let icon_height = self.icon.as_ref().map_or(0, |icon| icon.height);
+        let width = icon_width + glyphs(&self.text).len() as u32 * self.char_width + 2 * PADDING;
+        let height = (self.ascent + self.descent).max(icon_height) + 2 * PADDING;
+
+        let x = area.x + (area.width.saturating_sub(width) / 2) as i32;
+        let y = match config.osd_position {
+            OsdPosition::Top => area.y + PADDING as i32,
+            OsdPosition::Center => area.y + (area.height.saturating_sub(height) / 2) as i32,
+            OsdPosition::Bottom => area.bottom() - (height + PADDING) as i32,
+        };
+
+        conn.send_request(&x::ConfigureWindow {
+            window: self.window,
+            value_list: &[
+                x::ConfigWindow::X(x),
+                x::ConfigWindow::Y(y),
+                x::ConfigWindow::Width(width),
+                x::ConfigWindow::Height(height),
+                x::ConfigWindow::StackMode(x::StackMode::Above),
+            ],
+        });
+        conn.send_request(&x::MapWindow {
+            window: self.window,
+        });
+
+        self.draw(conn);
+    }
+
+    /// Redraws the text, needed after every Expose.
+    pub fn draw(&self, conn: &xcb::Connection) {
+        conn.send_request(&x::ClearArea {
+            exposures: false,
+            window: self.window,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        });
+
+        let line_height = self.ascent + self.descent;
+        let mut text_x = PADDING;
+        let mut text_y = PADDING;
+
+        if let Some(icon) = &self.icon {
+            let data: Vec<u8> = icon
+                .blend(self.background)
+                .iter()
+                .flat_map(|pixel| pixel.to_ne_bytes())
+                .collect();
+            conn.send_request(&x::PutImage {
+                format: x::ImageFormat::ZPixmap,
+                drawable: x::Drawable::Window(self.window),
+                gc: self.gc,
+                width: icon.width as u16,
+                height: icon.height as u16,
+                dst_x: PADDING as i16,
+                dst_y: (PADDING + line_height.saturating_sub(icon.height) / 2) as i16,
+                left_pad: 0,
+                depth: self.depth,
+                data: &data,
+            });
+
+            text_x += icon.width + PADDING;
+            text_y += icon.height.saturating_sub(line_height) / 2;
+        }
+
+        conn.send_request(&x::ImageText16 {
+            drawable: x::Drawable::Window(self.window),
+            gc: self.gc,
+            x: text_x as i16,
+            y: (text_y + self.ascent) as i16,
+            string: &glyphs(&self.text),
+        });
+    }
+
+    /// Time left until the OSD hides itself, None when it is not shown.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.hide_at
+            .map(|hide_at| hide_at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn hide(&mut self, conn: &xcb::Connection) {
+        self.hide_at = None;
+        conn.send_request(&x::UnmapWindow {
+            window: self.window,
+        });
+    }
+}
+
+/// Characters of `text` as UCS-2 for ImageText16, which is limited to 255 of them. Those
+/// outside the basic multilingual plane are replaced.
+fn glyphs(text: &str) -> Vec<x::Char2b> {
+    text.chars()
+        .take(255)
+        .map(|c| {
+            let [byte1, byte2] = u16::try_from(u32::from(c)).unwrap_or(0xfffd).to_be_bytes();
+            x::Char2b { byte1, byte2 }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn glyphs_are_ucs2() {
+        let glyphs: Vec<_> = super::glyphs("aż😀")
+            .iter()
+            .map(|glyph| (glyph.byte1, glyph.byte2))
+            .collect();
+        assert_eq!(glyphs, vec![(0x00, 0x61), (0x01, 0x7c), (0xff, 0xfd)]);
+
+        assert_eq!(super::glyphs(&"x".repeat(300)).len(), 255);
+    }
+}
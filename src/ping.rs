@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Pings clients (_NET_WM_PING) in rounds, those that did not answer by the next round
+/// are unresponsive.
+pub struct Pinger<W> {
+    interval: Duration,
+    next_round: Instant,
+    /// Clients pinged in the last round that have not answered yet.
+    pending: HashSet<W>,
+}
+
+impl<W: Copy + Eq + Hash> Pinger<W> {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            next_round: now + interval,
+            pending: HashSet::new(),
+        }
+    }
+
+    pub fn time_left(&self, now: Instant) -> Duration {
+        self.next_round.saturating_duration_since(now)
+    }
+
+    /// Starts a round pinging `clients` if it is time. Returns the clients that did not answer
+    /// the previous round, or None if it is not time yet.
+    pub fn round(&mut self, now: Instant, clients: &[W]) -> Option<Vec<W>> {
+        if now < self.next_round {
+            return None;
+        }
+
+        self.next_round = now + self.interval;
+        let unanswered = clients
+            .iter()
+            .copied()
+            .filter(|client| self.pending.contains(client))
+            .collect();
+        self.pending = clients.iter().copied().collect();
+        Some(unanswered)
+    }
+
+    pub fn answer(&mut self, client: W) {
+        self.pending.remove(&client);
+    }
+
+    pub fn forget(&mut self, client: W) {
+        self.pending.remove(&client);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pinger;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn clients_not_answering_by_next_round_are_reported() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut pinger = Pinger::new(5 * second, start);
+
+        assert_eq!(pinger.round(start + second, &[1, 2, 3]), None);
+        assert_eq!(pinger.time_left(start + second), 4 * second);
+
+        assert_eq!(pinger.round(start + 5 * second, &[1, 2, 3]), Some(vec![]));
+        pinger.answer(1);
+        pinger.forget(3);
+
+        assert_eq!(pinger.round(start + 10 * second, &[1, 2, 4]), Some(vec![2]));
+        pinger.answer(2);
+        assert_eq!(
+            pinger.round(start + 15 * second, &[1, 2, 4]),
+            Some(vec![1, 4])
+        );
+    }
+}
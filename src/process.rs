@@ -0,0 +1,48 @@
+use std::fs;
+
+/// Reads the parent process id from /proc.
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_parent_pid(&stat)
+}
+
+/// Parses the ppid field of /proc/<pid>/stat, which follows the state after the command name.
+/// The name is in parentheses and may itself contain spaces and parentheses.
+fn parse_parent_pid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Name of this host, as in WM_CLIENT_MACHINE of local clients.
+pub fn hostname() -> Option<String> {
+    let hostname = fs::read_to_string("/proc/sys/kernel/hostname").ok()?;
+    Some(hostname.trim().to_owned())
+}
+
+/// Whether `pid` was started (directly or not) by the process `ancestor`.
+pub fn is_descendant(pid: u32, ancestor: u32) -> bool {
+    let mut pid = pid;
+    while pid > 1 {
+        pid = match parent_pid(pid) {
+            Some(parent) => parent,
+            None => return false,
+        };
+        if pid == ancestor {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn parses_parent_pid_after_command_name() {
+        assert_eq!(
+            super::parse_parent_pid("4242 (mpv) S 4100 4242 4100 34817"),
+            Some(4100)
+        );
+        assert_eq!(super::parse_parent_pid("77 (a) (b c) R 1 77 77 0"), Some(1));
+        assert_eq!(super::parse_parent_pid("garbage"), None);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::App;
+use anyhow::{anyhow, Result};
+use log::warn;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+/// Called with the text entered in a prompt.
+pub type Answer = Box<dyn FnOnce(&mut App, String) -> Result<()>>;
+
+/// Prompt command running while the event loop goes on, answered once it exits.
+pub struct Prompt {
+    child: Child,
+    stdout: ChildStdout,
+    output: Vec<u8>,
+    on_answer: Answer,
+}
+
+impl Prompt {
+    /// Starts `command` with `message` as its last argument, offering `choices` on its
+    /// standard input.
+    pub fn spawn(
+        command: &[String],
+        message: &str,
+        choices: &[String],
+        on_answer: Answer,
+    ) -> Result<Self> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| anyhow!("Prompt command is not configured"))?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .arg(message)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().expect("Piped stdout");
+
+        // Long lists may not fit in the pipe before the command reads them.
+        let mut stdin = child.stdin.take().expect("Piped stdin");
+        let input = choices.join("\n");
+        std::thread::spawn(move || {
+            if let Err(e) = stdin.write_all(input.as_bytes()) {
+                warn!("Writing the prompt choices failed: {}", e);
+            }
+        });
+
+        // The output is read as it arrives, without waiting for more.
+        let fd = stdout.as_raw_fd();
+        unsafe {
+            libc::fcntl(
+                fd,
+                libc::F_SETFL,
+                libc::fcntl(fd, libc::F_GETFL) | libc::O_NONBLOCK,
+            );
+        }
+
+        Ok(Self {
+            child,
+            stdout,
+            output: Vec::new(),
+            on_answer,
+        })
+    }
+
+    /// File descriptor to poll for the output of the command.
+    pub fn fd(&self) -> RawFd {
+        self.stdout.as_raw_fd()
+    }
+
+    /// Reads the available output, returns true once the command closed it.
+    pub fn read(&mut self) -> bool {
+        let mut buffer = [0; 4096];
+        loop {
+            match self.stdout.read(&mut buffer) {
+                Ok(0) => return true,
+                Ok(read) => self.output.extend_from_slice(&buffer[..read]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return false,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    warn!("Reading the prompt output failed: {}", e);
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Waits for the command to exit, returns the entered text (None if cancelled) and the
+    /// callback to answer.
+    pub fn finish(mut self) -> (Option<String>, Answer) {
+        let success = match self.child.wait() {
+            Ok(status) => status.success(),
+            Err(e) => {
+                warn!("Waiting for the prompt failed: {}", e);
+                false
+            }
+        };
+
+        let text = String::from_utf8_lossy(&self.output).trim().to_owned();
+        (
+            (success && !text.is_empty()).then_some(text),
+            self.on_answer,
+        )
+    }
+}
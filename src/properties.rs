@@ -0,0 +1,219 @@
+use crate::atoms::Atoms;
+use crate::icon::Icon;
+use crate::motif::MotifHints;
+use crate::size_hints::SizeHints;
+use crate::struts::Struts;
+use xcb::{x, Xid};
+
+/// Reads the property if it has the given type and format (8 for strings, 32 for lists of
+/// CARDINAL, ATOM and WINDOW values).
+fn get_property(
+    conn: &xcb::Connection,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+    format: u8,
+) -> Option<x::GetPropertyReply> {
+    let cookie = conn.send_request(&x::GetProperty {
+        delete: false,
+        window,
+        property,
+        r#type,
+        long_offset: 0,
+        long_length: u32::MAX / 4,
+    });
+
+    let reply = conn.wait_for_reply(cookie).ok()?;
+    (reply.r#type() == r#type)
+        .then_some(reply)
+        .and_then(|reply| with_format(reply, format))
+}
+
+/// Returns the reply if its value has the given format, reading the value of another format
+/// panics.
+pub fn with_format(reply: x::GetPropertyReply, format: u8) -> Option<x::GetPropertyReply> {
+    // The format is the second byte of the reply, xcb keeps its accessor private.
+    let (raw, actual) = unsafe {
+        let raw = xcb::Reply::into_raw(reply);
+        (raw, *raw.add(1))
+    };
+    let reply = unsafe { <x::GetPropertyReply as xcb::Reply>::from_raw(raw) };
+    (actual == format).then_some(reply)
+}
+
+pub fn get_string_property(
+    conn: &xcb::Connection,
+    window: x::Window,
+    property: x::Atom,
+) -> Option<String> {
+    let reply = get_property(conn, window, property, x::ATOM_STRING, 8)?;
+    Some(String::from_utf8_lossy(reply.value::<u8>()).into_owned())
+}
+
+/// Returns the title of the window from the UTF-8 _NET_WM_NAME, falling back to WM_NAME.
+pub fn get_title(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<String> {
+    get_property(conn, window, atoms.net_wm_name, atoms.utf8_string, 8)
+        .map(|reply| String::from_utf8_lossy(reply.value::<u8>()).into_owned())
+        .or_else(|| get_string_property(conn, window, x::ATOM_WM_NAME))
+}
+
+/// Reads a list of null-separated strings of the given type.
+pub fn get_string_list(
+    conn: &xcb::Connection,
+    window: x::Window,
+    property: x::Atom,
+    r#type: x::Atom,
+) -> Vec<String> {
+    get_property(conn, window, property, r#type, 8)
+        .map(|reply| {
+            String::from_utf8_lossy(reply.value::<u8>())
+                .split('\0')
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the (instance, class) pair stored in WM_CLASS.
+pub fn get_wm_class(conn: &xcb::Connection, window: x::Window) -> Option<(String, String)> {
+    let value = get_string_property(conn, window, x::ATOM_WM_CLASS)?;
+
+    let mut parts = value.split('\0');
+    let instance = parts.next()?.to_owned();
+    let class = parts.next().unwrap_or_default().to_owned();
+
+    Some((instance, class))
+}
+
+/// WM_STATE values of windows that are unmanaged, shown, or hidden (iconified).
+pub const WM_STATE_WITHDRAWN: u32 = 0;
+pub const WM_STATE_NORMAL: u32 = 1;
+pub const WM_STATE_ICONIC: u32 = 3;
+
+/// Reads the state set by the previous window manager in WM_STATE.
+pub fn get_wm_state(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<u32> {
+    get_property(conn, window, atoms.wm_state, atoms.wm_state, 32)
+        .and_then(|reply| reply.value::<u32>().first().copied())
+}
+
+pub fn get_atoms(conn: &xcb::Connection, window: x::Window, property: x::Atom) -> Vec<x::Atom> {
+    get_property(conn, window, property, x::ATOM_ATOM, 32)
+        .map(|reply| reply.value::<x::Atom>().to_vec())
+        .unwrap_or_default()
+}
+
+/// Returns the process id of the client from _NET_WM_PID.
+pub fn get_pid(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<u32> {
+    get_property(conn, window, atoms.net_wm_pid, x::ATOM_CARDINAL, 32)
+        .and_then(|reply| reply.value::<u32>().first().copied())
+        .filter(|pid| *pid > 0)
+}
+
+/// Returns the server time of the latest user input in the client, from _NET_WM_USER_TIME.
+pub fn get_user_time(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<u32> {
+    get_property(conn, window, atoms.net_wm_user_time, x::ATOM_CARDINAL, 32)
+        .and_then(|reply| reply.value::<u32>().first().copied())
+}
+
+/// Returns the desktop (workspace index) in _NET_WM_DESKTOP, 0xFFFFFFFF meaning all of them.
+pub fn get_desktop(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<u32> {
+    get_property(conn, window, atoms.net_wm_desktop, x::ATOM_CARDINAL, 32)
+        .and_then(|reply| reply.value::<u32>().first().copied())
+}
+
+/// Returns the window the dialog belongs to, from WM_TRANSIENT_FOR.
+pub fn get_transient_for(conn: &xcb::Connection, window: x::Window) -> Option<x::Window> {
+    get_property(conn, window, x::ATOM_WM_TRANSIENT_FOR, x::ATOM_WINDOW, 32)
+        .and_then(|reply| reply.value::<x::Window>().first().copied())
+        .filter(|parent| *parent != window && !parent.is_none())
+}
+
+/// Returns the icon of the window from _NET_WM_ICON, scaled to fit a `size` square.
+pub fn get_icon(
+    conn: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+    size: u32,
+) -> Option<Icon> {
+    let reply = get_property(conn, window, atoms.net_wm_icon, x::ATOM_CARDINAL, 32)?;
+    Icon::best(&Icon::parse_all(reply.value::<u32>()), size)
+}
+
+/// Returns the startup id (_NET_STARTUP_ID) of the window, or of its client leader
+/// (WM_CLIENT_LEADER) if the window has none.
+pub fn get_startup_id(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<String> {
+    let startup_id = |window| {
+        get_property(conn, window, atoms.net_startup_id, atoms.utf8_string, 8)
+            .map(|reply| String::from_utf8_lossy(reply.value::<u8>()).into_owned())
+    };
+
+    startup_id(window).or_else(|| {
+        get_client_leader(conn, atoms, window)
+            .filter(|leader| *leader != window)
+            .and_then(startup_id)
+    })
+}
+
+/// Returns the leader of the windows of the client, from WM_CLIENT_LEADER.
+pub fn get_client_leader(
+    conn: &xcb::Connection,
+    atoms: &Atoms,
+    window: x::Window,
+) -> Option<x::Window> {
+    get_property(conn, window, atoms.wm_client_leader, x::ATOM_WINDOW, 32)
+        .and_then(|reply| reply.value::<x::Window>().first().copied())
+        .filter(|leader| !leader.is_none())
+}
+
+/// Returns whether the urgency flag of WM_HINTS is set or the window demands attention
+/// through _NET_WM_STATE.
+pub fn get_urgency(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> bool {
+    const URGENCY_HINT: u32 = 1 << 8;
+
+    get_property(conn, window, x::ATOM_WM_HINTS, x::ATOM_WM_HINTS, 32)
+        .and_then(|reply| reply.value::<u32>().first().copied())
+        .is_some_and(|flags| flags & URGENCY_HINT != 0)
+        || get_atoms(conn, window, atoms.net_wm_state)
+            .contains(&atoms.net_wm_state_demands_attention)
+}
+
+/// Returns the input field of WM_HINTS, whether the client relies on the window manager to
+/// set the input focus. Clients without the hint are given the focus.
+pub fn get_input_hint(conn: &xcb::Connection, window: x::Window) -> bool {
+    const INPUT_HINT: u32 = 1;
+
+    match get_property(conn, window, x::ATOM_WM_HINTS, x::ATOM_WM_HINTS, 32)
+        .map(|reply| reply.value::<u32>().to_vec())
+        .as_deref()
+    {
+        Some([flags, input, ..]) if flags & INPUT_HINT != 0 => *input != 0,
+        _ => true,
+    }
+}
+
+pub fn get_size_hints(conn: &xcb::Connection, window: x::Window) -> SizeHints {
+    get_property(
+        conn,
+        window,
+        x::ATOM_WM_NORMAL_HINTS,
+        x::ATOM_WM_SIZE_HINTS,
+        32,
+    )
+    .map(|reply| SizeHints::from_values(reply.value::<u32>()))
+    .unwrap_or_default()
+}
+
+pub fn get_motif_hints(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> MotifHints {
+    get_property(conn, window, atoms.motif_wm_hints, atoms.motif_wm_hints, 32)
+        .map(|reply| MotifHints::from_values(reply.value::<u32>()))
+        .unwrap_or_default()
+}
+
+/// Reads _NET_WM_STRUT_PARTIAL, falling back to the older _NET_WM_STRUT.
+pub fn get_struts(conn: &xcb::Connection, atoms: &Atoms, window: x::Window) -> Option<Struts> {
+    [atoms.net_wm_strut_partial, atoms.net_wm_strut]
+        .into_iter()
+        .find_map(|property| get_property(conn, window, property, x::ATOM_CARDINAL, 32))
+        .and_then(|reply| Struts::from_values(reply.value::<u32>()))
+}
@@ -0,0 +1,431 @@
+use crate::geometry::Rect;
+use anyhow::{bail, Result};
+use log::{debug, info, warn};
+use xcb::{randr, x, Xid};
+
+/// Connected RandR output shown by an active CRTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub name: String,
+    pub area: Rect,
+    pub primary: bool,
+    /// Resolution from the physical size, if the output reports one.
+    pub dpi: Option<f32>,
+    /// Counterclockwise rotation in degrees, the area is already rotated.
+    pub rotation: u16,
+}
+
+/// Desired configuration of an output, applied when it is connected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputProfile {
+    /// RandR name of the output.
+    pub output: String,
+    /// Width and height of the mode, the preferred mode when unset.
+    pub mode: Option<(u16, u16)>,
+    /// Position of the top left corner on the screen.
+    pub position: (i16, i16),
+    pub primary: bool,
+}
+
+/// Configures the outputs connected since the last call (those missing from `known`)
+/// following their profiles, growing the screen when needed. Outputs already set up are
+/// left alone. Returns the names of the connected outputs.
+pub fn apply_profiles(
+    conn: &xcb::Connection,
+    root: x::Window,
+    profiles: &[OutputProfile],
+    known: &[String],
+) -> Result<Vec<String>> {
+    if profiles.is_empty() || !available(conn) {
+        return Ok(Vec::new());
+    }
+
+    let cookie = conn.send_request(&randr::GetScreenResourcesCurrent { window: root });
+    let resources = conn.wait_for_reply(cookie)?;
+    let config_timestamp = resources.config_timestamp();
+
+    let cookie = conn.send_request(&randr::GetOutputPrimary { window: root });
+    let primary = conn.wait_for_reply(cookie)?.output();
+
+    let mut crtcs = Vec::new();
+    for crtc in resources.crtcs() {
+        let cookie = conn.send_request(&randr::GetCrtcInfo {
+            crtc: *crtc,
+            config_timestamp,
+        });
+        crtcs.push((*crtc, conn.wait_for_reply(cookie)?));
+    }
+
+    let mut connected = Vec::new();
+    for output in resources.outputs() {
+        let cookie = conn.send_request(&randr::GetOutputInfo {
+            output: *output,
+            config_timestamp,
+        });
+        let info = conn.wait_for_reply(cookie)?;
+        if info.connection() == randr::Connection::Connected {
+            connected.push((*output, info));
+        }
+    }
+
+    let mut claimed = Vec::new();
+    for profile in profiles {
+        // Changes made since the output appeared, e.g. with xrandr, are kept.
+        if known.contains(&profile.output) {
+            continue;
+        }
+        let (output, info) = match connected
+            .iter()
+            .find(|(_, info)| info.name() == profile.output.as_bytes())
+        {
+            Some((output, info)) => (*output, info),
+            None => continue,
+        };
+
+        let mode = info
+            .modes()
+            .iter()
+            .filter_map(|mode| {
+                let mode_info = resources
+                    .modes()
+                    .iter()
+                    .find(|mode_info| mode_info.id == mode.resource_id())?;
+                Some((*mode, (mode_info.width, mode_info.height)))
+            })
+            .find(|(_, size)| profile.mode.is_none_or(|wanted| wanted == *size));
+        let (mode, (width, height)) = match mode {
+            Some(mode) => mode,
+            None => {
+                warn!("Output {} has no mode {:?}", profile.output, profile.mode);
+                continue;
+            }
+        };
+
+        // The current CRTC of the output, or a free one it can use.
+        let crtc = crtcs
+            .iter()
+            .find(|(crtc, _)| *crtc == info.crtc())
+            .or_else(|| {
+                crtcs.iter().find(|(crtc, crtc_info)| {
+                    info.crtcs().contains(crtc)
+                        && crtc_info.outputs().is_empty()
+                        && !claimed.contains(crtc)
+                })
+            });
+        let (crtc, crtc_info) = match crtc {
+            Some(crtc) => crtc,
+            None => {
+                warn!("No CRTC available for output {}", profile.output);
+                continue;
+            }
+        };
+
+        claimed.push(*crtc);
+
+        let (x, y) = profile.position;
+        if crtc_info.mode() != mode || (crtc_info.x(), crtc_info.y()) != (x, y) {
+            // An active output keeps its rotation.
+            let rotation = if crtc_info.mode().is_none() {
+                randr::Rotation::ROTATE_0
+            } else {
+                crtc_info.rotation()
+            };
+            let (width, height) = match degrees(rotation) {
+                90 | 270 => (height, width),
+                _ => (width, height),
+            };
+            let area = Rect::new(x.into(), y.into(), width.into(), height.into());
+            grow_screen(conn, root, &crtcs, *crtc, area)?;
+
+            let cookie = conn.send_request(&randr::SetCrtcConfig {
+                crtc: *crtc,
+                timestamp: x::CURRENT_TIME,
+                config_timestamp,
+                x,
+                y,
+                mode,
+                rotation,
+                outputs: &[output],
+            });
+            match conn.wait_for_reply(cookie)?.status() {
+                randr::SetConfig::Success => {
+                    info!(
+                        "Output {} set to {}x{}+{}+{}",
+                        profile.output, width, height, x, y
+                    );
+                }
+                status => warn!("Configuring output {} failed: {:?}", profile.output, status),
+            }
+        }
+
+        if profile.primary && primary != output {
+            conn.send_request(&randr::SetOutputPrimary {
+                window: root,
+                output,
+            });
+        }
+    }
+
+    conn.flush()?;
+    Ok(connected
+        .iter()
+        .map(|(_, info)| String::from_utf8_lossy(info.name()).into_owned())
+        .collect())
+}
+
+/// Checks that at most one profile makes its output primary, they would otherwise take it
+/// from each other.
+pub fn check_profiles(profiles: &[OutputProfile]) -> Result<()> {
+    let primary: Vec<&str> = profiles
+        .iter()
+        .filter(|profile| profile.primary)
+        .map(|profile| profile.output.as_str())
+        .collect();
+    if primary.len() > 1 {
+        bail!(
+            "Several output profiles are primary: {}",
+            primary.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Makes the screen large enough for the other active CRTCs and `area`.
+fn grow_screen(
+    conn: &xcb::Connection,
+    root: x::Window,
+    crtcs: &[(randr::Crtc, randr::GetCrtcInfoReply)],
+    crtc: randr::Crtc,
+    area: Rect,
+) -> Result<()> {
+    let (width, height) = crtcs
+        .iter()
+        .filter(|(other, info)| *other != crtc && !info.mode().is_none())
+        .map(|(_, info)| {
+            Rect::new(
+                info.x().into(),
+                info.y().into(),
+                info.width().into(),
+                info.height().into(),
+            )
+        })
+        .chain(Some(area))
+        .fold((0, 0), |(width, height), area| {
+            (width.max(area.right()), height.max(area.bottom()))
+        });
+
+    let cookie = conn.send_request(&x::GetGeometry {
+        drawable: x::Drawable::Window(root),
+    });
+    let screen = conn.wait_for_reply(cookie)?;
+    let width = width.max(screen.width().into());
+    let height = height.max(screen.height().into());
+    if (width, height) == (screen.width().into(), screen.height().into()) {
+        return Ok(());
+    }
+
+    debug!("Screen grown to {}x{}", width, height);
+    // The physical size follows from 96 DPI.
+    conn.send_request(&randr::SetScreenSize {
+        window: root,
+        width: width as u16,
+        height: height as u16,
+        mm_width: (width as f32 * 25.4 / 96.0) as u32,
+        mm_height: (height as f32 * 25.4 / 96.0) as u32,
+    });
+    Ok(())
+}
+
+/// Whether the server supports RandR 1.3, needed for the outputs and the primary one.
+pub fn available(conn: &xcb::Connection) -> bool {
+    if !conn
+        .active_extensions()
+        .any(|extension| extension == xcb::Extension::RandR)
+    {
+        return false;
+    }
+
+    let cookie = conn.send_request(&randr::QueryVersion {
+        major_version: 1,
+        minor_version: 5,
+    });
+    conn.wait_for_reply(cookie)
+        .is_ok_and(|reply| (reply.major_version(), reply.minor_version()) >= (1, 3))
+}
+
+/// Queries the outputs showing part of the screen, ordered left to right and top to bottom.
+pub fn query_outputs(conn: &xcb::Connection, root: x::Window) -> Result<Vec<Output>> {
+    if !available(conn) {
+        bail!("RandR 1.3 is not available");
+    }
+
+    let cookie = conn.send_request(&randr::GetScreenResourcesCurrent { window: root });
+    let resources = conn.wait_for_reply(cookie)?;
+    let config_timestamp = resources.config_timestamp();
+
+    let cookie = conn.send_request(&randr::GetOutputPrimary { window: root });
+    let primary = conn.wait_for_reply(cookie)?.output();
+
+    let mut outputs = Vec::new();
+    for output in resources.outputs() {
+        let cookie = conn.send_request(&randr::GetOutputInfo {
+            output: *output,
+            config_timestamp,
+        });
+        let info = conn.wait_for_reply(cookie)?;
+        if info.connection() != randr::Connection::Connected || info.crtc().is_none() {
+            continue;
+        }
+
+        let cookie = conn.send_request(&randr::GetCrtcInfo {
+            crtc: info.crtc(),
+            config_timestamp,
+        });
+        let crtc = conn.wait_for_reply(cookie)?;
+        if crtc.mode().is_none() || crtc.width() == 0 || crtc.height() == 0 {
+            continue;
+        }
+
+        outputs.push(Output {
+            name: String::from_utf8_lossy(info.name()).into_owned(),
+            area: Rect::new(
+                crtc.x().into(),
+                crtc.y().into(),
+                crtc.width().into(),
+                crtc.height().into(),
+            ),
+            primary: *output == primary,
+            dpi: dpi(
+                (crtc.width(), crtc.height()),
+                (info.mm_width(), info.mm_height()),
+            ),
+            rotation: degrees(crtc.rotation()),
+        });
+    }
+
+    outputs.sort_by_key(|output| (output.area.x, output.area.y));
+    Ok(merge_overlapping(outputs))
+}
+
+/// Keeps a single output of those covering the same part of the screen, like mirrored
+/// outputs, the largest one. It is primary if any of the merged ones is. The merged outputs
+/// no longer match their names in the config.
+pub fn merge_overlapping(outputs: Vec<Output>) -> Vec<Output> {
+    let overlap = |a: &Rect, b: &Rect| {
+        a.x < b.right() && b.x < a.right() && a.y < b.bottom() && b.y < a.bottom()
+    };
+    let size = |output: &Output| output.area.width as u64 * output.area.height as u64;
+
+    let mut merged: Vec<Output> = Vec::with_capacity(outputs.len());
+    for mut output in outputs {
+        // A larger output may overlap several of those kept so far.
+        while let Some(index) = merged
+            .iter()
+            .position(|kept| overlap(&kept.area, &output.area))
+        {
+            let kept = merged.remove(index);
+            let primary = kept.primary || output.primary;
+            let (mut larger, smaller) = if size(&output) > size(&kept) {
+                (output, kept)
+            } else {
+                (kept, output)
+            };
+            info!(
+                "Output {} overlaps {}, merged into it",
+                smaller.name, larger.name
+            );
+            larger.primary = primary;
+            output = larger;
+        }
+        merged.push(output);
+    }
+
+    merged.sort_by_key(|output| (output.area.x, output.area.y));
+    merged
+}
+
+fn degrees(rotation: randr::Rotation) -> u16 {
+    if rotation.contains(randr::Rotation::ROTATE_90) {
+        90
+    } else if rotation.contains(randr::Rotation::ROTATE_180) {
+        180
+    } else if rotation.contains(randr::Rotation::ROTATE_270) {
+        270
+    } else {
+        0
+    }
+}
+
+/// Dots per inch along the diagonal, which does not depend on the rotation of the output.
+fn dpi((width, height): (u16, u16), (mm_width, mm_height): (u32, u32)) -> Option<f32> {
+    if mm_width == 0 || mm_height == 0 {
+        return None;
+    }
+
+    let pixels = (width as f32).hypot(height as f32);
+    let inches = (mm_width as f32).hypot(mm_height as f32) / 25.4;
+    Some(pixels / inches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Output, OutputProfile};
+    use crate::geometry::Rect;
+
+    fn output(name: &str, area: Rect, primary: bool) -> Output {
+        Output {
+            name: name.to_owned(),
+            area,
+            primary,
+            dpi: None,
+            rotation: 0,
+        }
+    }
+
+    #[test]
+    fn merges_mirrored_and_overlapping_outputs() {
+        let outputs = vec![
+            output("eDP-1", Rect::new(0, 0, 1920, 1080), true),
+            output("HDMI-1", Rect::new(0, 0, 1920, 1080), false),
+            output("DP-1", Rect::new(0, 0, 2560, 1440), false),
+            output("DP-2", Rect::new(2560, 0, 1920, 1080), false),
+        ];
+
+        let merged = super::merge_overlapping(outputs);
+        assert_eq!(
+            merged,
+            vec![
+                output("DP-1", Rect::new(0, 0, 2560, 1440), true),
+                output("DP-2", Rect::new(2560, 0, 1920, 1080), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_single_profile_can_be_primary() {
+        let profile = |output: &str, primary| OutputProfile {
+            output: output.to_owned(),
+            mode: None,
+            position: (0, 0),
+            primary,
+        };
+
+        assert!(super::check_profiles(&[profile("eDP-1", true), profile("DP-1", false)]).is_ok());
+        assert!(super::check_profiles(&[profile("eDP-1", true), profile("DP-1", true)]).is_err());
+    }
+
+    #[test]
+    fn merges_outputs_bridged_by_a_larger_one() {
+        let outputs = vec![
+            output("DP-1", Rect::new(0, 0, 1000, 1000), false),
+            output("DP-2", Rect::new(0, 1000, 1000, 1000), true),
+            output("HDMI-1", Rect::new(500, 500, 2000, 1000), false),
+        ];
+
+        let merged = super::merge_overlapping(outputs);
+        assert_eq!(
+            merged,
+            vec![output("HDMI-1", Rect::new(500, 500, 2000, 1000), true)]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use crate::client::Client;
+use crate::workspace::InsertionPolicy;
+use regex::Regex;
+
+/// Text criterion of a rule.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Matches the whole text exactly.
+    Literal(String),
+    /// Matches if found anywhere in the text, anchor it with ^ and $ to match the whole text.
+    #[allow(dead_code)] // No built-in rule uses a regex.
+    Regex(Regex),
+}
+
+impl Pattern {
+    #[allow(dead_code)] // No built-in rule uses a regex.
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Pattern::Regex)
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            Pattern::Literal(literal) => literal == text,
+            Pattern::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl From<&str> for Pattern {
+    fn from(literal: &str) -> Self {
+        Pattern::Literal(literal.to_owned())
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Pattern::Literal(a), Pattern::Literal(b)) => a == b,
+            (Pattern::Regex(a), Pattern::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Pattern {}
+
+/// Settings applied to windows matching all the criteria of the rule when they are mapped,
+/// and when their title changes to a matching one.
+///
+/// Empty criteria match any window.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// WM_CLASS instance and class names.
+    pub instance: Option<Pattern>,
+    pub class: Option<Pattern>,
+    /// WM_NAME
+    pub title: Option<Pattern>,
+    /// WM_WINDOW_ROLE, e.g. "browser" or "pop-up".
+    pub role: Option<Pattern>,
+
+    /// Overrides the insertion policy of the workspace.
+    pub insertion_policy: Option<InsertionPolicy>,
+
+    /// Name of the workspace the window is placed on, created if needed.
+    pub workspace: Option<String>,
+    /// Shows the assigned workspace instead of just marking it urgent.
+    pub switch_to_workspace: bool,
+    /// RandR output name of the monitor whose workspace the window is placed on,
+    /// unless a workspace is assigned.
+    pub monitor: Option<String>,
+
+    pub floating: Option<bool>,
+    pub fullscreen: Option<bool>,
+    /// Border width overriding the one of the workspace.
+    pub border_width: Option<u32>,
+    /// Opacity in percent, applied by a compositor.
+    pub opacity: Option<u8>,
+    /// Leaves the focus alone when the window is mapped.
+    pub no_focus: bool,
+
+    /// Terminal that graphical programs started from it swallow: the terminal is hidden
+    /// and the program takes its tile until it exits.
+    pub terminal: bool,
+    /// Never swallows the terminal it was started from.
+    pub no_swallow: bool,
+}
+
+impl Rule {
+    pub fn matches(&self, client: &Client) -> bool {
+        self.matches_with_title(client, &client.title)
+    }
+
+    fn matches_with_title(&self, client: &Client, title: &str) -> bool {
+        let matches = |pattern: &Option<Pattern>, text: &str| {
+            pattern.as_ref().is_none_or(|pattern| pattern.matches(text))
+        };
+
+        matches(&self.instance, &client.instance)
+            && matches(&self.class, &client.class)
+            && matches(&self.title, title)
+            && matches(&self.role, &client.role)
+    }
+}
+
+/// Returns the value set by the last matching rule that sets one.
+fn last<'a, T>(
+    rules: &'a [Rule],
+    client: &Client,
+    value: impl Fn(&'a Rule) -> Option<T>,
+) -> Option<T> {
+    rules
+        .iter()
+        .rev()
+        .filter(|rule| rule.matches(client))
+        .find_map(value)
+}
+
+/// Returns the rules with a title criterion the client matches since its title changed
+/// from `old_title`, to be applied again.
+pub fn matching_new_title(rules: &[Rule], client: &Client, old_title: &str) -> Vec<Rule> {
+    rules
+        .iter()
+        .filter(|rule| {
+            rule.title.is_some()
+                && rule.matches(client)
+                && !rule.matches_with_title(client, old_title)
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn insertion_policy(rules: &[Rule], client: &Client) -> Option<InsertionPolicy> {
+    last(rules, client, |rule| rule.insertion_policy)
+}
+
+/// Returns the assigned workspace and whether to switch to it.
+pub fn workspace<'a>(rules: &'a [Rule], client: &Client) -> Option<(&'a str, bool)> {
+    last(rules, client, |rule| {
+        rule.workspace
+            .as_deref()
+            .map(|workspace| (workspace, rule.switch_to_workspace))
+    })
+}
+
+/// Returns the output name of the assigned monitor.
+pub fn monitor<'a>(rules: &'a [Rule], client: &Client) -> Option<&'a str> {
+    last(rules, client, |rule| rule.monitor.as_deref())
+}
+
+pub fn floating(rules: &[Rule], client: &Client) -> Option<bool> {
+    last(rules, client, |rule| rule.floating)
+}
+
+pub fn fullscreen(rules: &[Rule], client: &Client) -> Option<bool> {
+    last(rules, client, |rule| rule.fullscreen)
+}
+
+pub fn border_width(rules: &[Rule], client: &Client) -> Option<u32> {
+    last(rules, client, |rule| rule.border_width)
+}
+
+pub fn opacity(rules: &[Rule], client: &Client) -> Option<u8> {
+    last(rules, client, |rule| rule.opacity)
+}
+
+/// Whether a matching rule keeps the window from taking the focus when mapped.
+pub fn no_focus(rules: &[Rule], client: &Client) -> bool {
+    rules
+        .iter()
+        .any(|rule| rule.no_focus && rule.matches(client))
+}
+
+/// Whether a matching rule marks the window as a terminal that can be swallowed.
+pub fn is_terminal(rules: &[Rule], client: &Client) -> bool {
+    rules
+        .iter()
+        .any(|rule| rule.terminal && rule.matches(client))
+}
+
+/// Whether the window may swallow the terminal it was started from.
+pub fn swallows(rules: &[Rule], client: &Client) -> bool {
+    !rules
+        .iter()
+        .any(|rule| (rule.terminal || rule.no_swallow) && rule.matches(client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pattern, Rule};
+    use crate::client::Client;
+    use crate::workspace::InsertionPolicy;
+    use xcb::{x, Xid};
+
+    fn client(instance: &str, class: &str, title: &str) -> Client {
+        let mut client = Client::new(x::Window::none());
+        client.instance = instance.to_owned();
+        client.class = class.to_owned();
+        client.title = title.to_owned();
+        client
+    }
+
+    #[test]
+    fn later_matching_rules_win() {
+        let rules = [
+            Rule {
+                class: Some("Firefox".into()),
+                insertion_policy: Some(InsertionPolicy::Master),
+                ..Default::default()
+            },
+            Rule {
+                instance: Some("devtools".into()),
+                class: Some("Firefox".into()),
+                insertion_policy: Some(InsertionPolicy::AfterFocused),
+                ..Default::default()
+            },
+            Rule {
+                class: Some("XTerm".into()),
+                insertion_policy: Some(InsertionPolicy::End),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            super::insertion_policy(&rules, &client("Navigator", "Firefox", "")),
+            Some(InsertionPolicy::Master)
+        );
+        assert_eq!(
+            super::insertion_policy(&rules, &client("devtools", "Firefox", "")),
+            Some(InsertionPolicy::AfterFocused)
+        );
+        assert_eq!(
+            super::insertion_policy(&rules, &client("mpv", "mpv", "")),
+            None
+        );
+    }
+
+    #[test]
+    fn assigns_workspace_by_class() {
+        let rules = [
+            Rule {
+                class: Some("Firefox".into()),
+                workspace: Some("web".to_owned()),
+                ..Default::default()
+            },
+            Rule {
+                class: Some("Firefox".into()),
+                insertion_policy: Some(InsertionPolicy::Master),
+                ..Default::default()
+            },
+            Rule {
+                class: Some("Slack".into()),
+                workspace: Some("chat".to_owned()),
+                switch_to_workspace: true,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            super::workspace(&rules, &client("Navigator", "Firefox", "")),
+            Some(("web", false))
+        );
+        assert_eq!(
+            super::workspace(&rules, &client("slack", "Slack", "")),
+            Some(("chat", true))
+        );
+        assert_eq!(
+            super::workspace(&rules, &client("xterm", "XTerm", "")),
+            None
+        );
+    }
+
+    #[test]
+    fn terminals_are_swallowed_by_other_windows() {
+        let rules = [
+            Rule {
+                class: Some("Alacritty".into()),
+                terminal: true,
+                ..Default::default()
+            },
+            Rule {
+                class: Some("Xephyr".into()),
+                no_swallow: true,
+                ..Default::default()
+            },
+        ];
+
+        assert!(super::is_terminal(
+            &rules,
+            &client("Alacritty", "Alacritty", "")
+        ));
+        assert!(!super::is_terminal(&rules, &client("mpv", "mpv", "")));
+        assert!(super::swallows(&rules, &client("mpv", "mpv", "")));
+        assert!(!super::swallows(&rules, &client("Xephyr", "Xephyr", "")));
+        assert!(!super::swallows(
+            &rules,
+            &client("Alacritty", "Alacritty", "")
+        ));
+    }
+
+    #[test]
+    fn combines_criteria_with_regex_patterns() {
+        let rules = [Rule {
+            class: Some("Firefox".into()),
+            title: Some(Pattern::regex("^Picture-in-Picture$").unwrap()),
+            floating: Some(true),
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            super::floating(&rules, &client("Toolkit", "Firefox", "Picture-in-Picture")),
+            Some(true)
+        );
+        assert_eq!(
+            super::floating(&rules, &client("Navigator", "Firefox", "Mozilla Firefox")),
+            None
+        );
+        assert_eq!(
+            super::floating(&rules, &client("mpv", "mpv", "Picture-in-Picture")),
+            None
+        );
+    }
+
+    #[test]
+    fn reapplies_rules_matching_new_title() {
+        let rules = [
+            Rule {
+                class: Some("Firefox".into()),
+                workspace: Some("web".to_owned()),
+                ..Default::default()
+            },
+            Rule {
+                title: Some(Pattern::regex("YouTube").unwrap()),
+                workspace: Some("video".to_owned()),
+                ..Default::default()
+            },
+        ];
+
+        let firefox = client("Navigator", "Firefox", "Cats - YouTube - Mozilla Firefox");
+        assert_eq!(
+            super::matching_new_title(&rules, &firefox, "Mozilla Firefox"),
+            vec![rules[1].clone()]
+        );
+        assert!(super::matching_new_title(&rules, &firefox, "Dogs - YouTube").is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{bail, Result};
+use log::{debug, info};
+use std::time::{Duration, Instant};
+use xcb::{x, Xid};
+
+/// Time given to the running window manager to exit when replaced.
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Takes the ICCCM manager selection (WM_Sn) of the screen for `window` and announces it,
+/// returns the selection. A window manager owning the selection already is only replaced
+/// with `replace`, once it destroyed its selection window.
+pub fn acquire(
+    conn: &xcb::Connection,
+    screen_num: i32,
+    root: x::Window,
+    window: x::Window,
+    replace: bool,
+) -> Result<x::Atom> {
+    let name = format!("WM_S{}", screen_num);
+    let selection = intern(conn, &name)?;
+
+    let cookie = conn.send_request(&x::GetSelectionOwner { selection });
+    let mut previous = Some(conn.wait_for_reply(cookie)?.owner()).filter(|owner| !owner.is_none());
+
+    if let Some(owner) = previous {
+        if !replace {
+            bail!("Another window manager is running, start with --replace to take over");
+        }
+
+        // Tells when the previous owner is gone, which it may already be.
+        let cookie = conn.send_request_checked(&x::ChangeWindowAttributes {
+            window: owner,
+            value_list: &[x::Cw::EventMask(x::EventMask::STRUCTURE_NOTIFY)],
+        });
+        if conn.check_request(cookie).is_err() {
+            previous = None;
+        }
+    }
+
+    let time = server_time(conn, window)?;
+    conn.send_request(&x::SetSelectionOwner {
+        owner: window,
+        selection,
+        time,
+    });
+
+    let cookie = conn.send_request(&x::GetSelectionOwner { selection });
+    if conn.wait_for_reply(cookie)?.owner() != window {
+        bail!("Failed to acquire the {} selection", name);
+    }
+
+    if let Some(owner) = previous {
+        info!("Waiting for the running window manager to exit");
+        wait_for_destroy(conn, owner)?;
+    }
+
+    let event = x::ClientMessageEvent::new(
+        root,
+        intern(conn, "MANAGER")?,
+        x::ClientMessageData::Data32([time, selection.resource_id(), window.resource_id(), 0, 0]),
+    );
+    conn.send_request(&x::SendEvent {
+        propagate: false,
+        destination: x::SendEventDest::Window(root),
+        event_mask: x::EventMask::STRUCTURE_NOTIFY,
+        event: &event,
+    });
+    conn.flush()?;
+
+    debug!("Acquired the {} selection", name);
+    Ok(selection)
+}
+
+fn intern(conn: &xcb::Connection, name: &str) -> Result<x::Atom> {
+    let cookie = conn.send_request(&x::InternAtom {
+        only_if_exists: false,
+        name: name.as_bytes(),
+    });
+    Ok(conn.wait_for_reply(cookie)?.atom())
+}
+
+/// Current server time, taken from the PropertyNotify caused by an empty property change
+/// on `window`. Selections must not be acquired with CurrentTime.
+fn server_time(conn: &xcb::Connection, window: x::Window) -> Result<u32> {
+    conn.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(x::EventMask::PROPERTY_CHANGE)],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Append,
+        window,
+        property: x::ATOM_WM_NAME,
+        r#type: x::ATOM_STRING,
+        data: &[] as &[u8],
+    });
+    conn.flush()?;
+
+    let time = loop {
+        if let xcb::Event::X(x::Event::PropertyNotify(event)) = conn.wait_for_event()? {
+            if event.window() == window {
+                break event.time();
+            }
+        }
+    };
+
+    conn.send_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(x::EventMask::NO_EVENT)],
+    });
+    Ok(time)
+}
+
+fn wait_for_destroy(conn: &xcb::Connection, window: x::Window) -> Result<()> {
+    let deadline = Instant::now() + REPLACE_TIMEOUT;
+
+    loop {
+        while let Some(event) = conn.poll_for_event()? {
+            if let xcb::Event::X(x::Event::DestroyNotify(event)) = event {
+                if event.window() == window {
+                    return Ok(());
+                }
+            }
+        }
+
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() || !crate::wait_for_input(conn, Some(left)) {
+            bail!("The running window manager did not exit");
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use crate::snapshot::{LayoutSnapshot, WindowIdentity};
+use anyhow::{anyhow, bail, Result};
+use log::{debug, warn};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_ulong, c_void};
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+
+/// How long clients started by the session manager are still sent to their saved workspaces.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(60);
+
+type SmcConn = *mut c_void;
+type IceConn = *mut c_void;
+type SmPointer = *mut c_void;
+type Bool = c_int;
+
+type SaveYourselfProc = unsafe extern "C" fn(SmcConn, SmPointer, c_int, Bool, c_int, Bool);
+type SimpleProc = unsafe extern "C" fn(SmcConn, SmPointer);
+
+#[repr(C)]
+struct Callback<F> {
+    callback: F,
+    client_data: SmPointer,
+}
+
+#[repr(C)]
+struct SmcCallbacks {
+    save_yourself: Callback<SaveYourselfProc>,
+    die: Callback<SimpleProc>,
+    save_complete: Callback<SimpleProc>,
+    shutdown_cancelled: Callback<SimpleProc>,
+}
+
+#[repr(C)]
+struct SmPropValue {
+    length: c_int,
+    value: SmPointer,
+}
+
+#[repr(C)]
+struct SmProp {
+    name: *mut c_char,
+    r#type: *mut c_char,
+    num_vals: c_int,
+    vals: *mut SmPropValue,
+}
+
+const SMC_SAVE_YOURSELF_PROC_MASK: c_ulong = 1 << 0;
+const SMC_DIE_PROC_MASK: c_ulong = 1 << 1;
+const SMC_SAVE_COMPLETE_PROC_MASK: c_ulong = 1 << 2;
+const SMC_SHUTDOWN_CANCELLED_PROC_MASK: c_ulong = 1 << 3;
+
+const SM_RESTART_IMMEDIATELY: u8 = 2;
+const ICE_PROCESS_MESSAGES_SUCCESS: c_int = 0;
+
+#[link(name = "SM")]
+extern "C" {
+    fn SmcOpenConnection(
+        network_ids_list: *mut c_char,
+        context: SmPointer,
+        xsmp_major_rev: c_int,
+        xsmp_minor_rev: c_int,
+        mask: c_ulong,
+        callbacks: *mut SmcCallbacks,
+        previous_id: *const c_char,
+        client_id_ret: *mut *mut c_char,
+        error_length: c_int,
+        error_string_ret: *mut c_char,
+    ) -> SmcConn;
+    fn SmcCloseConnection(conn: SmcConn, count: c_int, reason_msgs: *mut *mut c_char) -> c_int;
+    fn SmcSetProperties(conn: SmcConn, num_props: c_int, props: *mut *mut SmProp);
+    fn SmcSaveYourselfDone(conn: SmcConn, success: Bool);
+    fn SmcGetIceConnection(conn: SmcConn) -> IceConn;
+}
+
+#[link(name = "ICE")]
+extern "C" {
+    fn IceConnectionNumber(conn: IceConn) -> c_int;
+    fn IceProcessMessages(conn: IceConn, reply_wait: *mut c_void, reply_ready: *mut Bool) -> c_int;
+}
+
+/// Messages of the session manager the window manager has to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Request {
+    /// Save the state, then confirm with [`Session::save_yourself_done`].
+    SaveYourself,
+    /// The session ends, exit.
+    Die,
+}
+
+unsafe extern "C" fn save_yourself(
+    _conn: SmcConn,
+    requests: SmPointer,
+    _save_type: c_int,
+    _shutdown: Bool,
+    _interact_style: c_int,
+    _fast: Bool,
+) {
+    (*(requests as *mut Vec<Request>)).push(Request::SaveYourself);
+}
+
+unsafe extern "C" fn die(_conn: SmcConn, requests: SmPointer) {
+    (*(requests as *mut Vec<Request>)).push(Request::Die);
+}
+
+unsafe extern "C" fn ignore(_conn: SmcConn, _requests: SmPointer) {}
+
+/// Connection to the X session manager (XSMP), which restarts the window manager with the
+/// client id of the previous session on the next login.
+pub struct Session {
+    conn: SmcConn,
+    client_id: String,
+    // Filled by the callbacks while processing messages, boxed to keep its address.
+    #[allow(clippy::box_collection)]
+    requests: Box<Vec<Request>>,
+}
+
+impl Session {
+    /// Connects to the session manager named by SESSION_MANAGER, resuming `previous_id`.
+    /// Returns `None` when there is no session manager.
+    pub fn connect(previous_id: Option<&str>) -> Result<Option<Self>> {
+        if std::env::var_os("SESSION_MANAGER").is_none() {
+            return Ok(None);
+        }
+
+        let mut requests = Box::<Vec<Request>>::default();
+        let client_data = &mut *requests as *mut Vec<Request> as SmPointer;
+        let mut callbacks = SmcCallbacks {
+            save_yourself: Callback {
+                callback: save_yourself,
+                client_data,
+            },
+            die: Callback {
+                callback: die,
+                client_data,
+            },
+            save_complete: Callback {
+                callback: ignore,
+                client_data,
+            },
+            shutdown_cancelled: Callback {
+                callback: ignore,
+                client_data,
+            },
+        };
+
+        let previous_id = previous_id.map(CString::new).transpose()?;
+        let mut client_id: *mut c_char = std::ptr::null_mut();
+        let mut error = [0 as c_char; 256];
+
+        let conn = unsafe {
+            SmcOpenConnection(
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                1,
+                0,
+                SMC_SAVE_YOURSELF_PROC_MASK
+                    | SMC_DIE_PROC_MASK
+                    | SMC_SAVE_COMPLETE_PROC_MASK
+                    | SMC_SHUTDOWN_CANCELLED_PROC_MASK,
+                &mut callbacks,
+                previous_id
+                    .as_ref()
+                    .map_or(std::ptr::null(), |id| id.as_ptr()),
+                &mut client_id,
+                error.len() as c_int,
+                error.as_mut_ptr(),
+            )
+        };
+
+        if conn.is_null() {
+            let error = unsafe { CStr::from_ptr(error.as_ptr()) };
+            bail!(
+                "Connecting to the session manager failed: {}",
+                error.to_string_lossy()
+            );
+        }
+
+        let id = unsafe {
+            let id = CStr::from_ptr(client_id).to_string_lossy().into_owned();
+            libc::free(client_id as *mut c_void);
+            id
+        };
+        debug!("Connected to the session manager as {}", id);
+
+        let session = Self {
+            conn,
+            client_id: id,
+            requests,
+        };
+        session.set_properties()?;
+        Ok(Some(session))
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// File descriptor to poll for messages of the session manager.
+    pub fn fd(&self) -> RawFd {
+        unsafe { IceConnectionNumber(SmcGetIceConnection(self.conn)) }
+    }
+
+    /// Reads the pending messages of the session manager, fails when it went away.
+    pub fn process(&mut self) -> Result<Vec<Request>> {
+        let mut fds = [libc::pollfd {
+            fd: self.fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+
+        while unsafe { libc::poll(fds.as_mut_ptr(), 1, 0) } > 0 {
+            let status = unsafe {
+                IceProcessMessages(
+                    SmcGetIceConnection(self.conn),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if status != ICE_PROCESS_MESSAGES_SUCCESS {
+                bail!("Lost the connection to the session manager");
+            }
+        }
+
+        Ok(std::mem::take(&mut *self.requests))
+    }
+
+    pub fn save_yourself_done(&self, success: bool) {
+        unsafe { SmcSaveYourselfDone(self.conn, success as Bool) };
+    }
+
+    /// Tells the session manager how to restart the window manager in the next session.
+    fn set_properties(&self) -> Result<()> {
+        let program = std::env::current_exe()?
+            .into_os_string()
+            .into_string()
+            .map_err(|path| anyhow!("Executable path {:?} is not valid UTF-8", path))?;
+        let user = std::env::var("USER").unwrap_or_default();
+
+        let mut restart = [program.as_str(), "--sm-client-id", &self.client_id]
+            .map(|arg| arg.as_bytes().to_vec());
+        let mut clone = [program.as_bytes().to_vec()];
+        let mut program = program.into_bytes();
+        let mut user = user.into_bytes();
+        let mut hint = [SM_RESTART_IMMEDIATELY];
+
+        let values = |args: &mut [Vec<u8>]| -> Vec<SmPropValue> {
+            args.iter_mut()
+                .map(|arg| SmPropValue {
+                    length: arg.len() as c_int,
+                    value: arg.as_mut_ptr() as SmPointer,
+                })
+                .collect()
+        };
+        let mut restart = values(&mut restart);
+        let mut clone = values(&mut clone);
+        let mut program = values(std::slice::from_mut(&mut program));
+        let mut user = values(std::slice::from_mut(&mut user));
+        let mut hint = [SmPropValue {
+            length: 1,
+            value: hint.as_mut_ptr() as SmPointer,
+        }];
+
+        let names = [
+            "RestartCommand",
+            "CloneCommand",
+            "Program",
+            "UserID",
+            "RestartStyleHint",
+        ]
+        .map(|name| CString::new(name).unwrap());
+        let types = ["LISTofARRAY8", "LISTofARRAY8", "ARRAY8", "ARRAY8", "CARD8"]
+            .map(|name| CString::new(name).unwrap());
+        let vals: [&mut [SmPropValue]; 5] =
+            [&mut restart, &mut clone, &mut program, &mut user, &mut hint];
+
+        let mut props: Vec<SmProp> = vals
+            .into_iter()
+            .enumerate()
+            .map(|(index, vals)| SmProp {
+                name: names[index].as_ptr() as *mut c_char,
+                r#type: types[index].as_ptr() as *mut c_char,
+                num_vals: vals.len() as c_int,
+                vals: vals.as_mut_ptr(),
+            })
+            .collect();
+        let mut props: Vec<*mut SmProp> =
+            props.iter_mut().map(|prop| prop as *mut SmProp).collect();
+
+        unsafe { SmcSetProperties(self.conn, props.len() as c_int, props.as_mut_ptr()) };
+        Ok(())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        unsafe { SmcCloseConnection(self.conn, 0, std::ptr::null_mut()) };
+    }
+}
+
+/// Workspaces saved with the session, restored on the next login.
+///
+/// Stored as the layout snapshots of the workspaces, each one after its name:
+///
+/// ```text
+/// current 1/web
+/// workspace 1/web
+/// layout deck
+/// master_factor 0.5
+/// master_count 1
+/// transform none
+/// client firefox<TAB>Mozilla Firefox
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionState {
+    pub current: Option<String>,
+    pub workspaces: Vec<(String, LayoutSnapshot)>,
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(current) = &self.current {
+            writeln!(f, "current {}", current)?;
+        }
+        for (name, snapshot) in &self.workspaces {
+            writeln!(f, "workspace {}", name)?;
+            write!(f, "{}", snapshot)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for SessionState {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut current = None;
+        let mut sections: Vec<(String, String)> = Vec::new();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            match line.split_once(' ') {
+                Some(("current", name)) => current = Some(name.to_owned()),
+                Some(("workspace", name)) => sections.push((name.to_owned(), String::new())),
+                _ => match sections.last_mut() {
+                    Some((_, snapshot)) => {
+                        snapshot.push_str(line);
+                        snapshot.push('\n');
+                    }
+                    None => bail!("Session line outside of a workspace: {}", line),
+                },
+            }
+        }
+
+        let workspaces = sections
+            .into_iter()
+            .map(|(name, snapshot)| Ok((name, snapshot.parse()?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            current,
+            workspaces,
+        })
+    }
+}
+
+/// Workspaces of the clients saved with the session, for the clients the session manager
+/// starts again.
+pub struct Placements {
+    pending: Vec<(WindowIdentity, String)>,
+    restored: Instant,
+}
+
+impl Placements {
+    pub fn new(state: &SessionState, now: Instant) -> Self {
+        let pending = state
+            .workspaces
+            .iter()
+            .flat_map(|(name, snapshot)| {
+                snapshot
+                    .clients
+                    .iter()
+                    .map(move |client| (client.clone(), name.clone()))
+            })
+            .collect();
+
+        Self {
+            pending,
+            restored: now,
+        }
+    }
+
+    /// Saved workspace of the client, matching by class and title first and by class
+    /// alone second if `by_class` is set. Every saved client places a single window.
+    pub fn take(
+        &mut self,
+        identity: &WindowIdentity,
+        by_class: bool,
+        now: Instant,
+    ) -> Option<String> {
+        if now.duration_since(self.restored) >= RESTORE_TIMEOUT {
+            if !self.pending.is_empty() {
+                warn!("{} saved clients did not come back", self.pending.len());
+                self.pending.clear();
+            }
+            return None;
+        }
+
+        let position = self
+            .pending
+            .iter()
+            .position(|(saved, _)| saved == identity)
+            .or_else(|| {
+                self.pending
+                    .iter()
+                    .position(|(saved, _)| by_class && saved.class == identity.class)
+            })?;
+
+        Some(self.pending.remove(position).1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Placements, SessionState, RESTORE_TIMEOUT};
+    use crate::layouts::LayoutParams;
+    use crate::snapshot::{LayoutSnapshot, WindowIdentity};
+    use std::time::Instant;
+
+    fn identity(class: &str, title: &str) -> WindowIdentity {
+        WindowIdentity {
+            class: class.to_owned(),
+            title: title.to_owned(),
+        }
+    }
+
+    fn state() -> SessionState {
+        let snapshot = |layout: &str, clients| LayoutSnapshot {
+            layout: layout.to_owned(),
+            params: LayoutParams::default(),
+            clients,
+        };
+
+        SessionState {
+            current: Some("1/web".to_owned()),
+            workspaces: vec![
+                (
+                    "1/web".to_owned(),
+                    snapshot("deck", vec![identity("firefox", "Mozilla Firefox")]),
+                ),
+                (
+                    "2".to_owned(),
+                    snapshot("tile", vec![identity("st", "vim"), identity("st", "")]),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let parsed: SessionState = state().to_string().parse().unwrap();
+        assert_eq!(parsed, state());
+
+        assert!("layout deck".parse::<SessionState>().is_err());
+    }
+
+    #[test]
+    fn clients_return_to_their_saved_workspaces_once() {
+        let start = Instant::now();
+        let mut placements = Placements::new(&state(), start);
+
+        let firefox = identity("firefox", "Other title");
+        assert_eq!(
+            placements.take(&firefox, true, start),
+            Some("1/web".to_owned())
+        );
+        assert_eq!(placements.take(&firefox, true, start), None);
+
+        assert_eq!(
+            placements.take(&identity("st", ""), true, start),
+            Some("2".to_owned())
+        );
+        assert_eq!(
+            placements.take(&identity("st", "zsh"), true, start),
+            Some("2".to_owned())
+        );
+        assert_eq!(placements.take(&identity("st", "zsh"), true, start), None);
+
+        let mut placements = Placements::new(&state(), start);
+        assert_eq!(
+            placements.take(&firefox, true, start + RESTORE_TIMEOUT),
+            None
+        );
+    }
+
+    #[test]
+    fn class_matches_can_be_left_out() {
+        let start = Instant::now();
+        let mut placements = Placements::new(&state(), start);
+
+        let firefox = identity("firefox", "Other title");
+        assert_eq!(placements.take(&firefox, false, start), None);
+        assert_eq!(
+            placements.take(&firefox, true, start),
+            Some("1/web".to_owned())
+        );
+    }
+}
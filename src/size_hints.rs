@@ -0,0 +1,259 @@
+use crate::geometry::Rect;
+
+const P_MIN_SIZE: u32 = 1 << 4;
+const P_MAX_SIZE: u32 = 1 << 5;
+const P_RESIZE_INC: u32 = 1 << 6;
+const P_ASPECT: u32 = 1 << 7;
+const P_BASE_SIZE: u32 = 1 << 8;
+const P_WIN_GRAVITY: u32 = 1 << 9;
+
+const STATIC_GRAVITY: u32 = 10;
+
+/// Where the pixels a client refuses to fill (because of its size hints) end up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HintsAlignment {
+    /// Client sticks to the top-left corner of its tile.
+    #[allow(dead_code)] // The built-in config centers clients.
+    Start,
+    /// Client is centered within its tile.
+    #[default]
+    Center,
+    /// Client sticks to the bottom-right corner of its tile.
+    #[allow(dead_code)] // The built-in config centers clients.
+    End,
+}
+
+/// Subset of ICCCM WM_NORMAL_HINTS used to constrain client sizes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SizeHints {
+    pub min: Option<(u32, u32)>,
+    pub max: Option<(u32, u32)>,
+    pub increment: Option<(u32, u32)>,
+    pub base: Option<(u32, u32)>,
+    /// Minimum and maximum width to height ratios, as numerator and denominator.
+    pub min_aspect: Option<(u32, u32)>,
+    pub max_aspect: Option<(u32, u32)>,
+    /// ICCCM win_gravity, unset means NorthWest.
+    pub gravity: Option<u32>,
+}
+
+impl SizeHints {
+    /// Parses the 32-bit values of a WM_SIZE_HINTS property.
+    pub fn from_values(values: &[u32]) -> Self {
+        let value = |index: usize| values.get(index).copied().unwrap_or(0);
+        let pair = |flag: u32, index: usize| {
+            let set = value(0) & flag != 0;
+            set.then(|| (value(index), value(index + 1)))
+        };
+
+        Self {
+            min: pair(P_MIN_SIZE, 5),
+            max: pair(P_MAX_SIZE, 7).filter(|(w, h)| *w > 0 && *h > 0),
+            increment: pair(P_RESIZE_INC, 9).filter(|(w, h)| *w > 0 && *h > 0),
+            base: pair(P_BASE_SIZE, 15),
+            min_aspect: pair(P_ASPECT, 11).filter(|(x, y)| *x > 0 && *y > 0),
+            max_aspect: pair(P_ASPECT, 13).filter(|(x, y)| *x > 0 && *y > 0),
+            gravity: (value(0) & P_WIN_GRAVITY != 0).then(|| value(17)),
+        }
+    }
+
+    /// Keeps only the minimum and maximum sizes, which every geometry is clamped to.
+    pub fn limits(&self) -> Self {
+        Self {
+            min: self.min.or(self.base),
+            max: self.max,
+            ..Default::default()
+        }
+    }
+
+    /// Brings the proportions within the aspect ratio limits by adjusting the height, or the
+    /// width when `keep_width` is false.
+    pub fn keep_aspect(&self, width: u32, height: u32, keep_width: bool) -> (u32, u32) {
+        let (width, height) = (u64::from(width), u64::from(height));
+
+        let ratio = match (self.min_aspect, self.max_aspect) {
+            (Some((x, y)), _) if width * u64::from(y) < height * u64::from(x) => (x, y),
+            (_, Some((x, y))) if width * u64::from(y) > height * u64::from(x) => (x, y),
+            _ => return (width as u32, height as u32),
+        };
+        let (x, y) = (u64::from(ratio.0), u64::from(ratio.1));
+
+        if keep_width {
+            (width as u32, (width * y / x).max(1) as u32)
+        } else {
+            ((height * x / y).max(1) as u32, height as u32)
+        }
+    }
+
+    /// Returns how far to move a window whose border grows by `border_delta` on each side, so
+    /// the reference point picked by its gravity stays in place.
+    pub fn gravity_offset(&self, border_delta: i32) -> (i32, i32) {
+        match self.gravity {
+            Some(STATIC_GRAVITY) => (-border_delta, -border_delta),
+            Some(gravity @ 1..=9) => {
+                // NorthWest..SouthEast, row by row: the column picks x, the row picks y.
+                let column = (gravity - 1) % 3;
+                let row = (gravity - 1) / 3;
+                (
+                    -(column as i32) * border_delta,
+                    -(row as i32) * border_delta,
+                )
+            }
+            _ => (0, 0),
+        }
+    }
+
+    /// Returns the size closest to `width`x`height` (not bigger) satisfying the hints,
+    /// except for the minimum size which wins over the requested size.
+    pub fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+        // ICCCM: base size falls back to the minimum size and the other way around.
+        let base = self.base.or(self.min).unwrap_or((0, 0));
+        let min = self.min.or(self.base).unwrap_or((1, 1));
+
+        let mut width = width;
+        let mut height = height;
+
+        if let Some((width_inc, height_inc)) = self.increment {
+            width -= width.saturating_sub(base.0) % width_inc;
+            height -= height.saturating_sub(base.1) % height_inc;
+        }
+
+        if let Some((max_width, max_height)) = self.max {
+            width = width.min(max_width);
+            height = height.min(max_height);
+        }
+
+        (width.max(min.0).max(1), height.max(min.1).max(1))
+    }
+
+    /// Fits a client into `tile`, aligning it as configured when the hints
+    /// prevent it from filling the tile.
+    pub fn fit(&self, tile: Rect, alignment: HintsAlignment) -> Rect {
+        let (width, height) = self.constrain(tile.width, tile.height);
+
+        let offset = |free: u32| -> i32 {
+            match alignment {
+                HintsAlignment::Start => 0,
+                HintsAlignment::Center => (free / 2) as i32,
+                HintsAlignment::End => free as i32,
+            }
+        };
+
+        Rect::new(
+            tile.x + offset(tile.width.saturating_sub(width)),
+            tile.y + offset(tile.height.saturating_sub(height)),
+            width,
+            height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HintsAlignment, SizeHints};
+    use crate::geometry::Rect;
+
+    #[test]
+    fn parses_flagged_fields_only() {
+        let mut values = [0u32; 18];
+        values[0] = super::P_MIN_SIZE | super::P_RESIZE_INC;
+        values[5] = 100;
+        values[6] = 50;
+        values[7] = 300;
+        values[8] = 300;
+        values[9] = 8;
+        values[10] = 16;
+
+        assert_eq!(
+            SizeHints::from_values(&values),
+            SizeHints {
+                min: Some((100, 50)),
+                max: None,
+                increment: Some((8, 16)),
+                base: None,
+                min_aspect: None,
+                max_aspect: None,
+                gravity: None,
+            }
+        );
+    }
+
+    #[test]
+    fn snaps_to_increments_above_base() {
+        let hints = SizeHints {
+            increment: Some((7, 15)),
+            base: Some((4, 4)),
+            ..Default::default()
+        };
+
+        assert_eq!(hints.constrain(500, 300), (494, 289));
+    }
+
+    #[test]
+    fn minimum_wins_over_tile_size() {
+        let hints = SizeHints {
+            min: Some((400, 200)),
+            max: Some((600, 600)),
+            ..Default::default()
+        };
+
+        assert_eq!(hints.constrain(300, 1000), (400, 600));
+    }
+
+    #[test]
+    fn limits_ignore_increments() {
+        let hints = SizeHints {
+            max: Some((800, 600)),
+            increment: Some((7, 15)),
+            base: Some((100, 100)),
+            ..Default::default()
+        };
+
+        assert_eq!(hints.limits().constrain(500, 50), (500, 100));
+        assert_eq!(hints.limits().constrain(1000, 1000), (800, 600));
+    }
+
+    #[test]
+    fn keeps_aspect_ratio_along_dominant_axis() {
+        let hints = SizeHints {
+            min_aspect: Some((16, 9)),
+            max_aspect: Some((16, 9)),
+            ..Default::default()
+        };
+
+        assert_eq!(hints.keep_aspect(1600, 300, true), (1600, 900));
+        assert_eq!(hints.keep_aspect(1600, 450, false), (800, 450));
+        assert_eq!(hints.keep_aspect(160, 90, true), (160, 90));
+    }
+
+    #[test]
+    fn gravity_keeps_reference_point() {
+        let gravity = |gravity| SizeHints {
+            gravity: Some(gravity),
+            ..Default::default()
+        };
+
+        assert_eq!(SizeHints::default().gravity_offset(2), (0, 0));
+        assert_eq!(gravity(5).gravity_offset(2), (-2, -2));
+        assert_eq!(gravity(9).gravity_offset(2), (-4, -4));
+        assert_eq!(gravity(3).gravity_offset(-1), (2, 0));
+        assert_eq!(gravity(10).gravity_offset(2), (-2, -2));
+    }
+
+    #[test]
+    fn centers_client_in_tile() {
+        let hints = SizeHints {
+            max: Some((100, 100)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            hints.fit(Rect::new(0, 0, 200, 100), HintsAlignment::Center),
+            Rect::new(50, 0, 100, 100)
+        );
+        assert_eq!(
+            hints.fit(Rect::new(0, 0, 200, 100), HintsAlignment::End),
+            Rect::new(100, 0, 100, 100)
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::layouts::{resize_master_factor, LayoutParams};
+use anyhow::{anyhow, bail, Context};
+use std::path::PathBuf;
+
+/// Identifies a window across restarts, when X resource ids are no longer valid.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WindowIdentity {
+    pub class: String,
+    pub title: String,
+}
+
+/// Serializable arrangement of a single workspace.
+///
+/// Stored as a line based text file:
+///
+/// ```text
+/// layout three-column
+/// master_factor 0.55
+/// master_count 1
+/// transform mirror
+/// client firefox<TAB>Mozilla Firefox
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutSnapshot {
+    pub layout: String,
+    pub params: LayoutParams,
+    pub clients: Vec<WindowIdentity>,
+}
+
+impl LayoutSnapshot {
+    /// Orders `windows` following the snapshot, matching by class and title first
+    /// and by class alone second. Windows missing from the snapshot keep their
+    /// relative order after the matched ones.
+    pub fn order<T: Copy>(&self, windows: &[(T, WindowIdentity)]) -> Vec<T> {
+        let mut remaining: Vec<_> = windows.iter().collect();
+        let mut ordered = Vec::with_capacity(windows.len());
+
+        for wanted in &self.clients {
+            let position = remaining
+                .iter()
+                .position(|(_, identity)| identity == wanted)
+                .or_else(|| {
+                    remaining
+                        .iter()
+                        .position(|(_, identity)| identity.class == wanted.class)
+                });
+
+            if let Some(position) = position {
+                ordered.push(remaining.remove(position).0);
+            }
+        }
+
+        ordered.extend(remaining.into_iter().map(|(window, _)| *window));
+        ordered
+    }
+}
+
+impl std::fmt::Display for LayoutSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "layout {}", self.layout)?;
+        writeln!(f, "master_factor {}", self.params.master_factor)?;
+        writeln!(f, "master_count {}", self.params.master_count)?;
+        writeln!(f, "transform {}", self.params.transform)?;
+
+        for client in &self.clients {
+            let title = client.title.replace(['\t', '\n'], " ");
+            writeln!(f, "client {}\t{}", client.class, title)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for LayoutSnapshot {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut layout = None;
+        let mut params = LayoutParams::default();
+        let mut clients = Vec::new();
+
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("Malformed snapshot line: {}", line))?;
+
+            match key {
+                "layout" => layout = Some(value.to_owned()),
+                "master_factor" => {
+                    let master_factor: f32 = value.parse()?;
+                    if !master_factor.is_finite() {
+                        bail!("Invalid master factor: {}", value);
+                    }
+                    // Edited files may leave no room for either area.
+                    params.master_factor = resize_master_factor(master_factor, 0.0);
+                }
+                "master_count" => params.master_count = value.parse()?,
+                "transform" => params.transform = value.parse()?,
+                "client" => {
+                    let (class, title) = value.split_once('\t').unwrap_or((value, ""));
+                    clients.push(WindowIdentity {
+                        class: class.to_owned(),
+                        title: title.to_owned(),
+                    });
+                }
+                _ => bail!("Unknown snapshot key: {}", key),
+            }
+        }
+
+        Ok(Self {
+            layout: layout.ok_or_else(|| anyhow!("Snapshot is missing a layout"))?,
+            params,
+            clients,
+        })
+    }
+}
+
+/// `$XDG_STATE_HOME/rwm/<name>`, falling back to `~/.local/state/rwm/<name>`.
+pub fn state_file(name: &str) -> anyhow::Result<PathBuf> {
+    let state_home = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            PathBuf::from(std::env::var_os("HOME").context("HOME is not set")?).join(".local/state")
+        }
+    };
+
+    Ok(state_home.join(env!("CARGO_PKG_NAME")).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LayoutSnapshot, WindowIdentity};
+    use crate::layouts::LayoutParams;
+
+    fn identity(class: &str, title: &str) -> WindowIdentity {
+        WindowIdentity {
+            class: class.to_owned(),
+            title: title.to_owned(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let snapshot = LayoutSnapshot {
+            layout: "deck".to_owned(),
+            params: LayoutParams {
+                master_factor: 0.6,
+                master_count: 2,
+                ..Default::default()
+            },
+            clients: vec![identity("firefox", "Mozilla Firefox"), identity("st", "")],
+        };
+
+        let parsed: LayoutSnapshot = snapshot.to_string().parse().unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+
+    #[test]
+    fn order_prefers_exact_matches() {
+        let snapshot = LayoutSnapshot {
+            layout: "deck".to_owned(),
+            params: LayoutParams::default(),
+            clients: vec![identity("st", "htop"), identity("firefox", "")],
+        };
+
+        let windows = [
+            (1, identity("firefox", "news")),
+            (2, identity("st", "vim")),
+            (3, identity("st", "htop")),
+        ];
+
+        assert_eq!(snapshot.order(&windows), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn master_factor_is_clamped() {
+        let parsed: LayoutSnapshot = "layout deck\nmaster_factor 1.5".parse().unwrap();
+        assert_eq!(parsed.params.master_factor, 0.95);
+
+        let parsed: LayoutSnapshot = "layout deck\nmaster_factor -1".parse().unwrap();
+        assert_eq!(parsed.params.master_factor, 0.05);
+
+        assert!("layout deck\nmaster_factor NaN"
+            .parse::<LayoutSnapshot>()
+            .is_err());
+    }
+
+    #[test]
+    fn missing_layout_is_an_error() {
+        assert!("master_count 1".parse::<LayoutSnapshot>().is_err());
+    }
+}
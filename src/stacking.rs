@@ -0,0 +1,67 @@
+use crate::client::Client;
+
+/// Stacking layers of clients, from the bottom up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    /// Desktop windows drawing the background, e.g. icons of a file manager.
+    Desktop,
+    /// Clients asking to stay below the others (_NET_WM_STATE_BELOW).
+    Below,
+    Tiled,
+    Floating,
+    /// Always on top clients.
+    Above,
+    Fullscreen,
+    Dock,
+    /// Unmanaged notifications, tooltips and menus.
+    Popup,
+}
+
+impl Layer {
+    pub fn of(client: &Client) -> Self {
+        if client.popup {
+            Layer::Popup
+        } else if client.dock {
+            Layer::Dock
+        } else if client.desktop {
+            Layer::Desktop
+        } else if client.fullscreen {
+            Layer::Fullscreen
+        } else if client.above {
+            Layer::Above
+        } else if client.below {
+            Layer::Below
+        } else if client.floating {
+            Layer::Floating
+        } else {
+            Layer::Tiled
+        }
+    }
+}
+
+/// Sorts windows (bottom to top) by layer, those of the same layer keep their order.
+pub fn stacking_order<W: Copy>(windows: &[(W, Layer)]) -> Vec<W> {
+    let mut windows = windows.to_vec();
+    windows.sort_by_key(|(_, layer)| *layer);
+    windows.into_iter().map(|(window, _)| window).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Layer;
+
+    #[test]
+    fn sorts_by_layer_keeping_raise_order() {
+        let windows = [
+            (1, Layer::Floating),
+            (2, Layer::Dock),
+            (3, Layer::Tiled),
+            (4, Layer::Floating),
+            (5, Layer::Fullscreen),
+            (6, Layer::Tiled),
+            (7, Layer::Above),
+        ];
+
+        assert_eq!(super::stacking_order(&windows), vec![3, 6, 1, 4, 7, 5, 2]);
+    }
+}
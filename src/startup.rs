@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+/// How long windows of a launched application are still sent to the workspace it was
+/// launched from.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Launch {
+    id: String,
+    workspace: String,
+    started: Instant,
+}
+
+/// Applications launched by the window manager, told apart by the startup id given to them
+/// in DESKTOP_STARTUP_ID and set by their windows in _NET_STARTUP_ID.
+#[derive(Default)]
+pub struct Launches {
+    count: u32,
+    pending: Vec<Launch>,
+}
+
+impl Launches {
+    /// Records a launch from `workspace` at server time `time`, returns its startup id.
+    /// The time lets the application tell when it was launched, as a user time.
+    pub fn start(&mut self, workspace: String, time: u32, now: Instant) -> String {
+        self.expire(now);
+
+        self.count += 1;
+        let id = format!("rwm-{}-{}_TIME{}", std::process::id(), self.count, time);
+        self.pending.push(Launch {
+            id: id.clone(),
+            workspace,
+            started: now,
+        });
+        id
+    }
+
+    /// Workspace the application with the startup id `id` was launched from.
+    pub fn workspace(&mut self, id: &str, now: Instant) -> Option<&str> {
+        self.expire(now);
+
+        self.pending
+            .iter()
+            .find(|launch| launch.id == id)
+            .map(|launch| launch.workspace.as_str())
+    }
+
+    fn expire(&mut self, now: Instant) {
+        self.pending
+            .retain(|launch| now.duration_since(launch.started) < LAUNCH_TIMEOUT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Launches, LAUNCH_TIMEOUT};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn windows_find_the_workspace_of_their_launch_until_it_expires() {
+        let start = Instant::now();
+        let mut launches = Launches::default();
+
+        let first = launches.start("3".to_owned(), 1000, start);
+        let second = launches.start("web".to_owned(), 1000, start);
+        assert_ne!(first, second);
+        assert!(first.ends_with("_TIME1000"));
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(launches.workspace(&first, later), Some("3"));
+        assert_eq!(launches.workspace(&first, later), Some("3"));
+        assert_eq!(launches.workspace(&second, later), Some("web"));
+        assert_eq!(launches.workspace("other", later), None);
+
+        assert_eq!(launches.workspace(&first, start + LAUNCH_TIMEOUT), None);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::geometry::Rect;
+
+/// Space reserved by a dock or bar at the edges of the screen (_NET_WM_STRUT_PARTIAL or
+/// _NET_WM_STRUT).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Struts {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+    /// Extents (first and last pixel) along the left, right, top and bottom edges,
+    /// None for the whole edge.
+    pub ranges: Option<[(u32, u32); 4]>,
+}
+
+impl Struts {
+    /// Parses _NET_WM_STRUT or _NET_WM_STRUT_PARTIAL.
+    pub fn from_values(values: &[u32]) -> Option<Self> {
+        match *values {
+            [left, right, top, bottom, ref partial @ ..] => Some(Self {
+                left,
+                right,
+                top,
+                bottom,
+                ranges: match *partial {
+                    [left_start_y, left_end_y, right_start_y, right_end_y, top_start_x, top_end_x, bottom_start_x, bottom_end_x, ..] => {
+                        Some([
+                            (left_start_y, left_end_y),
+                            (right_start_y, right_end_y),
+                            (top_start_x, top_end_x),
+                            (bottom_start_x, bottom_end_x),
+                        ])
+                    }
+                    _ => None,
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Areas of the `screen` reserved at the left, right, top and bottom edges.
+    fn reserved(&self, screen: Rect) -> [Rect; 4] {
+        let range = |edge: usize, start: i32, length: u32| match self.ranges {
+            Some(ranges) => {
+                // Clients may send ranges past the screen.
+                let (first, last) = ranges[edge];
+                let first = first.min(length);
+                let end = last.saturating_add(1).min(length);
+                (start + first as i32, end.saturating_sub(first))
+            }
+            None => (start, length),
+        };
+
+        let (y, height) = range(0, screen.y, screen.height);
+        let left = Rect::new(screen.x, y, self.left, height);
+        let (y, height) = range(1, screen.y, screen.height);
+        let right = Rect::new(screen.right() - self.right as i32, y, self.right, height);
+        let (x, width) = range(2, screen.x, screen.width);
+        let top = Rect::new(x, screen.y, width, self.top);
+        let (x, width) = range(3, screen.x, screen.width);
+        let bottom = Rect::new(x, screen.bottom() - self.bottom as i32, width, self.bottom);
+
+        [left, right, top, bottom]
+    }
+}
+
+/// Part of the `monitor` left for clients once the areas reserved by `struts` at the edges
+/// of the `screen` are taken away. Struts only reserve space on the monitors they touch.
+pub fn work_area(struts: &[Struts], screen: Rect, monitor: Rect) -> Rect {
+    let overlaps = |area: &Rect| {
+        area.width > 0
+            && area.height > 0
+            && area.x < monitor.right()
+            && monitor.x < area.right()
+            && area.y < monitor.bottom()
+            && monitor.y < area.bottom()
+    };
+    let inset = |value: i32| value.max(0) as u32;
+
+    let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+    for struts in struts {
+        let [l, r, t, b] = struts.reserved(screen);
+        if overlaps(&l) {
+            left = left.max(inset(l.right() - monitor.x));
+        }
+        if overlaps(&r) {
+            right = right.max(inset(monitor.right() - r.x));
+        }
+        if overlaps(&t) {
+            top = top.max(inset(t.bottom() - monitor.y));
+        }
+        if overlaps(&b) {
+            bottom = bottom.max(inset(monitor.bottom() - b.y));
+        }
+    }
+
+    monitor.inset(left, top, right, bottom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Struts;
+    use crate::geometry::Rect;
+
+    #[test]
+    fn reserves_largest_strut_on_each_edge() {
+        let bar = Struts::from_values(&[0, 0, 20, 0, 0, 0, 0, 0, 0, 1920, 0, 0]).unwrap();
+        let dock = Struts::from_values(&[48, 0, 10, 0]).unwrap();
+        let screen = Rect::new(0, 0, 1920, 1080);
+
+        assert_eq!(
+            super::work_area(&[bar, dock], screen, screen),
+            Rect::new(48, 20, 1872, 1060)
+        );
+        assert_eq!(Struts::from_values(&[1, 2]), None);
+    }
+
+    #[test]
+    fn ranges_may_end_past_the_screen() {
+        let bar = Struts::from_values(&[0, 0, 20, 0, 0, 0, 0, 0, 0, u32::MAX, 0, 0]).unwrap();
+        let screen = Rect::new(0, 0, 1920, 1080);
+
+        assert_eq!(
+            super::work_area(&[bar], screen, screen),
+            Rect::new(0, 20, 1920, 1060)
+        );
+    }
+
+    #[test]
+    fn partial_struts_only_reserve_space_on_their_monitor() {
+        let screen = Rect::new(0, 0, 3840, 1080);
+        let left = Rect::new(0, 0, 1920, 1080);
+        let right = Rect::new(1920, 0, 1920, 1080);
+
+        // Bar at the top of the right monitor.
+        let bar = Struts::from_values(&[0, 0, 24, 0, 0, 0, 0, 0, 1920, 3839, 0, 0]).unwrap();
+
+        assert_eq!(super::work_area(&[bar], screen, left), left);
+        assert_eq!(
+            super::work_area(&[bar], screen, right),
+            Rect::new(1920, 24, 1920, 1056)
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use crate::geometry::Rect;
+use crate::properties;
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, warn};
+use std::path::PathBuf;
+use xcb::{x, Xid};
+
+/// What a monitor shows of the root window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Background {
+    /// RGB color, written as `#rrggbb`.
+    Color(u32),
+    /// Binary PPM (P6) image filling the monitor, cropped to its proportions.
+    Image(PathBuf),
+}
+
+impl std::str::FromStr for Background {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('#') {
+            Some(hex) if hex.len() == 6 => Ok(Self::Color(u32::from_str_radix(hex, 16)?)),
+            Some(_) => bail!("Colors are written as #rrggbb: {}", s),
+            None if s.is_empty() => bail!("Background is empty"),
+            None => Ok(Self::Image(expand_home(s))),
+        }
+    }
+}
+
+/// Replaces a leading `~/` with the home directory, like a shell would.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Decoded RGB image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u32>,
+}
+
+impl Image {
+    /// Decodes a binary PPM (P6) image.
+    pub fn parse_ppm(data: &[u8]) -> Result<Self> {
+        let mut rest = data
+            .strip_prefix(b"P6")
+            .ok_or_else(|| anyhow!("Not a binary PPM image"))?;
+
+        // Width, height and the maximum value, separated by whitespace and comments.
+        let mut header = [0u32; 3];
+        for value in &mut header {
+            loop {
+                match rest.first() {
+                    Some(byte) if byte.is_ascii_whitespace() => rest = &rest[1..],
+                    Some(b'#') => {
+                        let end = rest.iter().position(|byte| *byte == b'\n');
+                        rest = &rest[end.map_or(rest.len(), |end| end + 1)..];
+                    }
+                    _ => break,
+                }
+            }
+            let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+            *value = std::str::from_utf8(&rest[..digits])?
+                .parse()
+                .context("Malformed PPM header")?;
+            rest = &rest[digits..];
+        }
+        let [width, height, max] = header;
+
+        // A single whitespace character ends the header.
+        rest = rest.get(1..).unwrap_or_default();
+        if width == 0 || height == 0 {
+            bail!("PPM image is empty");
+        }
+        if max == 0 || max > 255 {
+            bail!("Unsupported PPM maximum value {}", max);
+        }
+        let len = width as usize * height as usize;
+        if rest.len() < len * 3 {
+            bail!("PPM image is truncated");
+        }
+
+        let scale = |value: u8| value as u32 * 255 / max;
+        let pixels = rest[..len * 3]
+            .chunks_exact(3)
+            .map(|rgb| scale(rgb[0]) << 16 | scale(rgb[1]) << 8 | scale(rgb[2]))
+            .collect();
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Pixels of the image scaled to cover `width` by `height`, cropping the middle part
+    /// that fits those proportions.
+    pub fn cover(&self, width: u32, height: u32) -> Vec<u32> {
+        let (width, height) = (width as u64, height as u64);
+        let (image_width, image_height) = (self.width as u64, self.height as u64);
+
+        // Part of the image shown, with the proportions of the target.
+        let (visible_width, visible_height) = if image_width * height > image_height * width {
+            (image_height * width / height, image_height)
+        } else {
+            (image_width, image_width * height / width)
+        };
+        let left = (image_width - visible_width) / 2;
+        let top = (image_height - visible_height) / 2;
+
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let source_y = top + y * visible_height / height;
+            for x in 0..width {
+                let source_x = left + x * visible_width / width;
+                pixels.push(self.pixels[(source_y * image_width + source_x) as usize]);
+            }
+        }
+        pixels
+    }
+}
+
+/// Draws the backgrounds of the monitors into a new root pixmap, advertised in
+/// _XROOTPMAP_ID for pseudo-transparent clients. The pixmap is kept from a separate
+/// connection, like other background setters do, so that they can free it.
+pub fn set(backgrounds: &[(Rect, Background)]) -> Result<()> {
+    let (conn, screen_num) = xcb::Connection::connect(None)?;
+    let setup = conn.get_setup();
+    let screen = setup
+        .roots()
+        .nth(screen_num as usize)
+        .ok_or_else(|| anyhow!("Screen {} not found", screen_num))?;
+    let root = screen.root();
+    let depth = screen.root_depth();
+
+    let cookie = conn.send_request(&x::GetGeometry {
+        drawable: x::Drawable::Window(root),
+    });
+    let size = conn.wait_for_reply(cookie)?;
+
+    let pixmap: x::Pixmap = conn.generate_id();
+    conn.send_request(&x::CreatePixmap {
+        depth,
+        pid: pixmap,
+        drawable: x::Drawable::Window(root),
+        width: size.width(),
+        height: size.height(),
+    });
+    let gc: x::Gcontext = conn.generate_id();
+    conn.send_request(&x::CreateGc {
+        cid: gc,
+        drawable: x::Drawable::Pixmap(pixmap),
+        value_list: &[x::Gc::Foreground(screen.black_pixel())],
+    });
+    conn.send_request(&x::PolyFillRectangle {
+        drawable: x::Drawable::Pixmap(pixmap),
+        gc,
+        rectangles: &[x::Rectangle {
+            x: 0,
+            y: 0,
+            width: size.width(),
+            height: size.height(),
+        }],
+    });
+
+    for (area, background) in backgrounds {
+        match background {
+            // Colors and pixels are written as RGB, only fitting 24 and 32-bit visuals.
+            _ if depth != 24 && depth != 32 => {
+                warn!("Backgrounds need a 24 or 32-bit visual, not {}", depth);
+            }
+            Background::Color(color) => {
+                conn.send_request(&x::ChangeGc {
+                    gc,
+                    value_list: &[x::Gc::Foreground(*color)],
+                });
+                conn.send_request(&x::PolyFillRectangle {
+                    drawable: x::Drawable::Pixmap(pixmap),
+                    gc,
+                    rectangles: &[x::Rectangle {
+                        x: area.x as i16,
+                        y: area.y as i16,
+                        width: area.width as u16,
+                        height: area.height as u16,
+                    }],
+                });
+            }
+            Background::Image(path) => {
+                let image = match std::fs::read(path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|data| Image::parse_ppm(&data))
+                {
+                    Ok(image) => image,
+                    Err(e) => {
+                        warn!("Loading background {:?} failed: {}", path, e);
+                        continue;
+                    }
+                };
+                put_image(
+                    &conn,
+                    pixmap,
+                    gc,
+                    depth,
+                    *area,
+                    &image.cover(area.width, area.height),
+                );
+            }
+        }
+    }
+
+    let xrootpmap_id = intern(&conn, "_XROOTPMAP_ID")?;
+    let esetroot_pmap_id = intern(&conn, "ESETROOT_PMAP_ID")?;
+
+    // The previous pixmap is freed along with the resources of the client keeping it.
+    let previous = |property| {
+        let cookie = conn.send_request(&x::GetProperty {
+            delete: false,
+            window: root,
+            property,
+            r#type: x::ATOM_PIXMAP,
+            long_offset: 0,
+            long_length: 1,
+        });
+        conn.wait_for_reply(cookie)
+            .ok()
+            .filter(|reply| reply.r#type() == x::ATOM_PIXMAP)
+            .and_then(|reply| properties::with_format(reply, 32))
+            .and_then(|reply| reply.value::<u32>().first().copied())
+    };
+    if let Some(id) = previous(xrootpmap_id).filter(|id| Some(*id) == previous(esetroot_pmap_id)) {
+        debug!("Freeing the previous root pixmap {:#x}", id);
+        let cookie = conn.send_request_checked(&x::KillClient { resource: id });
+        // Fails if it is already gone.
+        let _ = conn.check_request(cookie);
+    }
+
+    for property in [xrootpmap_id, esetroot_pmap_id] {
+        conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: root,
+            property,
+            r#type: x::ATOM_PIXMAP,
+            data: &[pixmap.resource_id()],
+        });
+    }
+    conn.send_request(&x::ChangeWindowAttributes {
+        window: root,
+        value_list: &[x::Cw::BackPixmap(pixmap)],
+    });
+    conn.send_request(&x::ClearArea {
+        exposures: false,
+        window: root,
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+    });
+    conn.send_request(&x::FreeGc { gc });
+    conn.send_request(&x::SetCloseDownMode {
+        mode: x::CloseDown::RetainPermanent,
+    });
+    conn.flush()?;
+
+    debug!("Root pixmap {:?} set", pixmap);
+    Ok(())
+}
+
+/// Sends the pixels in strips of 128KB, large images exceed the maximum request length.
+fn put_image(
+    conn: &xcb::Connection,
+    pixmap: x::Pixmap,
+    gc: x::Gcontext,
+    depth: u8,
+    area: Rect,
+    pixels: &[u32],
+) {
+    let rows = (32768 / area.width.max(1)).max(1) as usize;
+
+    for (strip, chunk) in pixels.chunks(rows * area.width as usize).enumerate() {
+        let data: Vec<u8> = chunk.iter().flat_map(|pixel| pixel.to_ne_bytes()).collect();
+        conn.send_request(&x::PutImage {
+            format: x::ImageFormat::ZPixmap,
+            drawable: x::Drawable::Pixmap(pixmap),
+            gc,
+            width: area.width as u16,
+            height: (chunk.len() / area.width as usize) as u16,
+            dst_x: area.x as i16,
+            dst_y: (area.y + (strip * rows) as i32) as i16,
+            left_pad: 0,
+            depth,
+            data: &data,
+        });
+    }
+}
+
+fn intern(conn: &xcb::Connection, name: &str) -> Result<x::Atom> {
+    let cookie = conn.send_request(&x::InternAtom {
+        only_if_exists: false,
+        name: name.as_bytes(),
+    });
+    Ok(conn.wait_for_reply(cookie)?.atom())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Background, Image};
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_backgrounds() {
+        assert_eq!(
+            "#285577".parse::<Background>().unwrap(),
+            Background::Color(0x285577)
+        );
+        assert_eq!(
+            "/usr/share/wall.ppm".parse::<Background>().unwrap(),
+            Background::Image(PathBuf::from("/usr/share/wall.ppm"))
+        );
+        if let Some(home) = std::env::var_os("HOME") {
+            assert_eq!(
+                "~/wall.ppm".parse::<Background>().unwrap(),
+                Background::Image(PathBuf::from(home).join("wall.ppm"))
+            );
+        }
+        assert!("#fff".parse::<Background>().is_err());
+        assert!("".parse::<Background>().is_err());
+    }
+
+    #[test]
+    fn decodes_ppm_images() {
+        let mut data = b"P6\n# comment\n2 1\n255\n".to_vec();
+        data.extend([255, 0, 0, 0, 128, 255]);
+
+        let image = Image::parse_ppm(&data).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.pixels, vec![0xFF0000, 0x0080FF]);
+
+        assert!(Image::parse_ppm(b"P6 2 1 255\n\x00").is_err());
+        assert!(Image::parse_ppm(b"P3 1 1 255\n0 0 0").is_err());
+    }
+
+    #[test]
+    fn covers_the_area_cropping_the_middle() {
+        // Four columns, shown on a square: the middle two remain.
+        let image = Image {
+            width: 4,
+            height: 2,
+            pixels: vec![1, 2, 3, 4, 1, 2, 3, 4],
+        };
+
+        assert_eq!(image.cover(2, 2), vec![2, 3, 2, 3]);
+        assert_eq!(image.cover(4, 1), vec![1, 2, 3, 4]);
+    }
+}
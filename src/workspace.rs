@@ -0,0 +1,470 @@
+use crate::geometry::{Direction, Rect};
+use crate::layouts::{BoxLayout, Layout, LayoutClient, LayoutParams, Preselection};
+use std::collections::{HashMap, HashSet};
+use xcb::x;
+
+/// How workspaces are shared between monitors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceModel {
+    /// Every monitor has its own set of workspaces (dwm, xmonad).
+    #[default]
+    PerMonitor,
+    /// All monitors share one set, viewing a workspace pulls it to the current monitor (i3).
+    Shared,
+}
+
+/// Workspace addressed by a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceTarget {
+    Index(usize),
+    /// Workspace at the given offset from the current one in the workspace order, wrapping around.
+    Relative(isize),
+    /// Workspace with the given name, created if it does not exist.
+    Name(String),
+}
+
+/// Workspace group addressed by a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupTarget {
+    /// Group at the given offset from the active one, wrapping around.
+    Relative(isize),
+    Name(String),
+}
+
+/// Position in the tiling order given to newly mapped windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InsertionPolicy {
+    /// New window becomes the first master.
+    #[allow(dead_code)] // The built-in config appends new windows.
+    Master,
+    /// New window is placed right after the focused one.
+    #[allow(dead_code)] // The built-in config appends new windows.
+    AfterFocused,
+    /// New window is appended to the stack.
+    #[default]
+    End,
+}
+
+pub struct Workspace {
+    pub name: String,
+
+    /// Index of the monitor the workspace belongs to, or was last shown on
+    /// with [`WorkspaceModel::Shared`].
+    pub monitor: usize,
+
+    /// Index of the group (activity) the workspace belongs to.
+    pub group: usize,
+
+    /// Non-persistent workspaces are destroyed once empty and hidden.
+    pub persistent: bool,
+
+    /// Clients in tiling order, the first ones being the masters.
+    pub clients: Vec<x::Window>,
+
+    /// Clients in the order they were focused, the most recent last.
+    focus_history: Vec<x::Window>,
+
+    /// Layout weights of clients that were resized, others weigh 1.
+    weights: HashMap<x::Window, f32>,
+
+    /// Clients left out of the layout (floating, fullscreen, minimized...), layout indices
+    /// only count the others, see [`Workspace::tiled`].
+    untiled: HashSet<x::Window>,
+
+    layouts: Vec<BoxLayout>,
+    layout_index: usize,
+
+    pub params: LayoutParams,
+
+    pub insertion_policy: InsertionPolicy,
+}
+
+impl Workspace {
+    pub fn new(
+        name: String,
+        monitor: usize,
+        layouts: Vec<BoxLayout>,
+        params: LayoutParams,
+        insertion_policy: InsertionPolicy,
+    ) -> Self {
+        assert!(
+            !layouts.is_empty(),
+            "Workspace requires at least one layout"
+        );
+
+        Self {
+            name,
+            monitor,
+            group: 0,
+            persistent: true,
+            clients: Vec::new(),
+            focus_history: Vec::new(),
+            weights: HashMap::new(),
+            untiled: HashSet::new(),
+            layouts,
+            layout_index: 0,
+            params,
+            insertion_policy,
+        }
+    }
+
+    pub fn layout(&self) -> &dyn Layout {
+        self.layouts[self.layout_index].as_ref()
+    }
+
+    /// Switches to the layout called `name`, returns false if there is no such layout.
+    pub fn set_layout(&mut self, name: &str) -> bool {
+        match self.layouts.iter().position(|layout| layout.name() == name) {
+            Some(index) => {
+                self.layouts[self.layout_index].leave();
+                self.layout_index = index;
+                self.layouts[self.layout_index].enter();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches back to the first layout.
+    pub fn reset_layout(&mut self) {
+        self.layouts[self.layout_index].leave();
+        self.layout_index = 0;
+        self.layouts[self.layout_index].enter();
+    }
+
+    pub fn next_layout(&mut self) {
+        self.layouts[self.layout_index].leave();
+        self.layout_index = (self.layout_index + 1) % self.layouts.len();
+        self.layouts[self.layout_index].enter();
+    }
+
+    pub fn contains(&self, window: x::Window) -> bool {
+        self.clients.contains(&window)
+    }
+
+    /// Adds the window following `policy` (or the workspace policy), unless the
+    /// layout asks for a specific position. Untiled windows stay out of the layout.
+    pub fn add(
+        &mut self,
+        window: x::Window,
+        tiled: bool,
+        policy: Option<InsertionPolicy>,
+        focused: Option<x::Window>,
+    ) {
+        if self.contains(window) {
+            return;
+        }
+
+        let requested = self.layout().insertion_index().filter(|_| tiled);
+        let index = match requested {
+            Some(index) => self
+                .tiled()
+                .get(index)
+                .and_then(|next| self.clients.iter().position(|w| w == next))
+                .unwrap_or(self.clients.len()),
+            None => match policy.unwrap_or(self.insertion_policy) {
+                InsertionPolicy::Master => 0,
+                InsertionPolicy::AfterFocused => focused
+                    .and_then(|focused| self.clients.iter().position(|w| *w == focused))
+                    .map_or(self.clients.len(), |index| index + 1),
+                InsertionPolicy::End => self.clients.len(),
+            },
+        };
+
+        self.clients.insert(index, window);
+
+        if tiled {
+            self.insert_tiled(window);
+        } else {
+            self.untiled.insert(window);
+        }
+    }
+
+    /// Clients taking part in the layout, in tiling order. Layout indices refer to them.
+    pub fn tiled(&self) -> Vec<x::Window> {
+        self.clients
+            .iter()
+            .copied()
+            .filter(|window| !self.untiled.contains(window))
+            .collect()
+    }
+
+    fn tiled_index(&self, window: x::Window) -> Option<usize> {
+        self.tiled().iter().position(|w| *w == window)
+    }
+
+    /// Puts a client of the workspace in the layout or takes it out of it.
+    pub fn set_tiled(&mut self, window: x::Window, tiled: bool) {
+        if !self.contains(window) || self.untiled.contains(&window) != tiled {
+            return;
+        }
+
+        if tiled {
+            self.untiled.remove(&window);
+            self.insert_tiled(window);
+        } else {
+            self.remove_tiled(window);
+            self.untiled.insert(window);
+        }
+    }
+
+    fn insert_tiled(&mut self, window: x::Window) {
+        let tiled = self.tiled();
+        if let Some(index) = tiled.iter().position(|w| *w == window) {
+            for layout in &mut self.layouts {
+                layout.insert(index, tiled.len());
+            }
+        }
+    }
+
+    fn remove_tiled(&mut self, window: x::Window) {
+        if let Some(index) = self.tiled_index(window) {
+            let count = self.tiled().len() - 1;
+            for layout in &mut self.layouts {
+                layout.remove(index, count);
+            }
+        }
+    }
+
+    /// Exchanges the tiles of two clients, the sizes of the tiles stay in place.
+    pub fn swap(&mut self, a: x::Window, b: x::Window) -> bool {
+        let position = |window| self.clients.iter().position(|w| *w == window);
+        let (first, second) = match (position(a), position(b)) {
+            (Some(first), Some(second)) if first != second => (first, second),
+            _ => return false,
+        };
+
+        self.clients.swap(first, second);
+
+        let weight_a = self.weights.remove(&a);
+        let weight_b = self.weights.remove(&b);
+        if let Some(weight) = weight_a {
+            self.weights.insert(b, weight);
+        }
+        if let Some(weight) = weight_b {
+            self.weights.insert(a, weight);
+        }
+
+        true
+    }
+
+    /// Puts `new` in the place of `old` in the tiling order, returns whether `old` was found.
+    pub fn replace(&mut self, old: x::Window, new: x::Window) -> bool {
+        let index = match self.clients.iter().position(|w| *w == old) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        self.clients[index] = new;
+        if self.untiled.remove(&old) {
+            self.untiled.insert(new);
+        }
+        self.focus_history.retain(|w| *w != new);
+        for window in &mut self.focus_history {
+            if *window == old {
+                *window = new;
+            }
+        }
+        if let Some(weight) = self.weights.remove(&old) {
+            self.weights.insert(new, weight);
+        }
+
+        true
+    }
+
+    /// Removes the window and returns whether it belonged to the workspace.
+    pub fn remove(&mut self, window: x::Window) -> bool {
+        if !self.contains(window) {
+            return false;
+        }
+
+        self.remove_tiled(window);
+        self.clients.retain(|w| *w != window);
+        self.untiled.remove(&window);
+        self.focus_history.retain(|w| *w != window);
+        self.weights.remove(&window);
+
+        true
+    }
+
+    pub fn layout_clients(&self, focused: Option<x::Window>) -> Vec<LayoutClient> {
+        self.tiled()
+            .iter()
+            .map(|window| self.layout_client(*window, focused))
+            .collect()
+    }
+
+    /// Layout state of `window`, which does not have to belong to the workspace.
+    pub fn layout_client(&self, window: x::Window, focused: Option<x::Window>) -> LayoutClient {
+        LayoutClient {
+            focused: focused == Some(window),
+            weight: self.weights.get(&window).copied().unwrap_or(1.0),
+        }
+    }
+
+    pub fn focus(&mut self, window: x::Window) {
+        if !self.contains(window) {
+            return;
+        }
+
+        self.focus_history.retain(|w| *w != window);
+        self.focus_history.push(window);
+
+        if let Some(index) = self.tiled_index(window) {
+            self.layouts[self.layout_index].focus(&self.params, index);
+        }
+    }
+
+    /// Clients from the most to the least recently focused, the never focused ones last.
+    pub fn focus_order(&self) -> Vec<x::Window> {
+        let mut order: Vec<x::Window> = self.focus_history.iter().rev().copied().collect();
+        order.extend(
+            self.clients
+                .iter()
+                .filter(|window| !self.focus_history.contains(window)),
+        );
+        order
+    }
+
+    /// Replaces the focus history with `order` (most recent first), see [`Workspace::focus_order`].
+    pub fn set_focus_order(&mut self, order: &[x::Window]) {
+        self.focus_history = order
+            .iter()
+            .rev()
+            .copied()
+            .filter(|window| self.clients.contains(window))
+            .collect();
+    }
+
+    /// Moves the edge of `window` facing `direction` (on screen) by `delta`,
+    /// see [`Layout::resize`].
+    pub fn resize(&mut self, window: x::Window, direction: Direction, delta: f32) -> bool {
+        let index = match self.tiled_index(window) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let mut clients = self.layout_clients(None);
+        let direction = self.params.transform.layout_direction(direction);
+
+        let resized = self.layouts[self.layout_index].resize(
+            &mut self.params,
+            &mut clients,
+            index,
+            direction,
+            delta,
+        );
+
+        for (window, client) in self.tiled().into_iter().zip(clients) {
+            self.weights.insert(window, client.weight);
+        }
+
+        resized
+    }
+
+    /// Undoes manual resizes: client weights, the master factor and the layout's own splits.
+    pub fn balance(&mut self, master_factor: f32) {
+        self.weights.clear();
+        self.params.master_factor = master_factor;
+        self.layouts[self.layout_index].balance();
+    }
+
+    /// Reserves part of the area of `window` (on the `direction` side on screen) for
+    /// the next client. Returns false if the current layout does not support it.
+    pub fn preselect(&mut self, window: x::Window, direction: Direction, ratio: f32) -> bool {
+        let index = match self.tiled_index(window) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let direction = self.params.transform.layout_direction(direction);
+        let layout = &mut self.layouts[self.layout_index];
+
+        let preselection = Preselection {
+            index,
+            direction,
+            ratio,
+        };
+
+        // Repeating the same preselection cancels it.
+        let toggled = match layout.preselection() {
+            Some(current) if current.index == index && current.direction == direction => None,
+            _ => Some(preselection),
+        };
+
+        layout.preselect(toggled)
+    }
+
+    /// Screen area reserved by the current preselection.
+    pub fn preselection_area(&self, area: Rect) -> Option<Rect> {
+        let transform = self.params.transform;
+        let clients = self.layout_clients(None);
+
+        self.layout()
+            .preselection_area(&self.params, &clients, transform.layout_area(area))
+            .map(|rect| transform.apply(rect, area))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InsertionPolicy, Workspace};
+    use crate::geometry::{Direction, Rect};
+    use crate::layouts::{BoxLayout, Bsp, Deck, LayoutParams};
+    use xcb::{x, XidNew};
+
+    fn workspace(layout: BoxLayout) -> Workspace {
+        Workspace::new(
+            String::from("1"),
+            0,
+            vec![layout],
+            LayoutParams::default(),
+            InsertionPolicy::End,
+        )
+    }
+
+    fn windows<const N: usize>() -> [x::Window; N] {
+        std::array::from_fn(|index| unsafe { x::Window::new(index as u32 + 1) })
+    }
+
+    #[test]
+    fn floating_clients_do_not_shift_the_focused_tile() {
+        let [master, floating, first, second] = windows();
+        let mut workspace = workspace(Box::new(Deck::default()));
+        workspace.add(master, true, None, None);
+        workspace.add(floating, false, None, None);
+        workspace.add(first, true, None, None);
+        workspace.add(second, true, None, None);
+
+        workspace.focus(first);
+        workspace.focus(master);
+
+        let clients = workspace.layout_clients(Some(master));
+        assert_eq!(workspace.tiled(), vec![master, first, second]);
+        assert_eq!(
+            workspace.layout().raised(&workspace.params, &clients),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn floating_clients_do_not_shift_the_resized_tile() {
+        let [left, floating, right] = windows();
+        let mut workspace = workspace(Box::new(Bsp::default()));
+        workspace.add(left, true, None, None);
+        workspace.add(floating, true, None, None);
+        workspace.add(right, true, None, None);
+        workspace.set_tiled(floating, false);
+
+        assert!(workspace.resize(right, Direction::Left, 0.25));
+
+        let clients = workspace.layout_clients(None);
+        let area = Rect::new(0, 0, 800, 400);
+        assert_eq!(
+            workspace
+                .layout()
+                .arrange(&workspace.params, &clients, area),
+            vec![Rect::new(0, 0, 200, 400), Rect::new(200, 0, 600, 400)]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use crate::geometry::Rect;
+use crate::randr::{self, Output};
+use anyhow::{bail, Result};
+use xcb::xinerama;
+
+/// Queries the Xinerama screens, for servers without a recent enough RandR. The screens
+/// have no names.
+pub fn query_screens(conn: &xcb::Connection) -> Result<Vec<Output>> {
+    if !conn
+        .active_extensions()
+        .any(|extension| extension == xcb::Extension::Xinerama)
+    {
+        bail!("Xinerama is not available");
+    }
+
+    let cookie = conn.send_request(&xinerama::IsActive {});
+    if conn.wait_for_reply(cookie)?.state() == 0 {
+        bail!("Xinerama is not active");
+    }
+
+    let cookie = conn.send_request(&xinerama::QueryScreens {});
+    let mut screens: Vec<Output> = conn
+        .wait_for_reply(cookie)?
+        .screen_info()
+        .iter()
+        .map(|screen| Output {
+            name: String::new(),
+            area: Rect::new(
+                screen.x_org.into(),
+                screen.y_org.into(),
+                screen.width.into(),
+                screen.height.into(),
+            ),
+            primary: false,
+            dpi: None,
+            rotation: 0,
+        })
+        .collect();
+
+    // Ordered like RandR outputs, cloned screens are reported like mirrored outputs.
+    screens.sort_by_key(|screen| (screen.area.x, screen.area.y));
+    Ok(randr::merge_overlapping(screens))
+}